@@ -30,6 +30,13 @@
 use std::{future::Future, pin::Pin, sync::OnceLock};
 use thiserror::Error;
 
+#[cfg(feature = "test-executor")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-executor")))]
+mod test_executor;
+#[cfg(feature = "test-executor")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-executor")))]
+pub use test_executor::*;
+
 /// A future that has been pinned.
 pub type PinnedFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
 /// A future that has been pinned.
@@ -0,0 +1,280 @@
+//! A deterministic executor for tests, driven by hand.
+
+use crate::{CustomExecutor, PinnedFuture, PinnedLocalFuture};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    time::Duration,
+};
+use thiserror::Error;
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+    // set while a task is being polled, so that nested calls to `poll_local` do nothing
+    static RUNNING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The number of polls after which [`TestExecutor::run_until_idle`] gives up, by default.
+const DEFAULT_POLL_LIMIT: usize = 10_000;
+
+/// An executor for tests, which only makes progress when the test tells it to.
+///
+/// Spawned tasks are queued rather than run, and a test can then [step](TestExecutor::step)
+/// through them one poll at a time, or [run them until they are idle](TestExecutor::run_until_idle).
+/// Timers created with [`TestExecutor::sleep`] use a virtual clock, which only moves when the
+/// test [advances](TestExecutor::advance) it.
+///
+/// Each thread has its own queue and clock: tasks are queued on the thread that spawns them, and
+/// only run when that thread drives the executor. Because the test harness runs each test on its
+/// own thread, tests in the same binary do not see one another's tasks.
+///
+/// ```
+/// use any_spawner::{Executor, TestExecutor};
+/// use std::{
+///     sync::{
+///         atomic::{AtomicBool, Ordering},
+///         Arc,
+///     },
+///     time::Duration,
+/// };
+///
+/// _ = Executor::init_custom_executor(TestExecutor);
+///
+/// let done = Arc::new(AtomicBool::new(false));
+/// Executor::spawn({
+///     let done = Arc::clone(&done);
+///     async move {
+///         TestExecutor::sleep(Duration::from_secs(1)).await;
+///         done.store(true, Ordering::Relaxed);
+///     }
+/// });
+///
+/// TestExecutor::run_until_idle().unwrap();
+/// assert!(!done.load(Ordering::Relaxed));
+/// TestExecutor::advance(Duration::from_secs(1)).unwrap();
+/// assert!(done.load(Ordering::Relaxed));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TestExecutor;
+
+/// Returned when the tasks on the [`TestExecutor`] keep waking each other up, and do not become
+/// idle within the poll limit.
+///
+/// This usually means that two or more effects are updating signals that the others depend on.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error(
+    "Tasks did not settle after {polls} polls; {ready} were still ready to \
+     run."
+)]
+pub struct Unsettled {
+    /// The number of polls that were made.
+    pub polls: usize,
+    /// The number of tasks that were still ready to run.
+    pub ready: usize,
+}
+
+impl CustomExecutor for TestExecutor {
+    fn spawn(&self, fut: PinnedFuture<()>) {
+        TestExecutor::queue(fut);
+    }
+
+    fn spawn_local(&self, fut: PinnedLocalFuture<()>) {
+        TestExecutor::queue(fut);
+    }
+
+    fn poll_local(&self) {
+        if !RUNNING.get() {
+            _ = TestExecutor::run_until_idle();
+        }
+    }
+}
+
+impl TestExecutor {
+    /// Polls the next task that is ready to run on this thread.
+    ///
+    /// Returns `false` if no task was ready.
+    pub fn step() -> bool {
+        let Some((id, mut task, ready)) = STATE.with_borrow_mut(|state| {
+            loop {
+                let id = state.ready.lock().unwrap().pop_front()?;
+                // a task can be woken after it has finished
+                if let Some(task) = state.tasks.remove(&id) {
+                    return Some((id, task, Arc::clone(&state.ready)));
+                }
+            }
+        }) else {
+            return false;
+        };
+
+        let waker = Waker::from(Arc::new(TaskWaker { id, ready }));
+        let prev = RUNNING.replace(true);
+        let poll = task.as_mut().poll(&mut Context::from_waker(&waker));
+        RUNNING.set(prev);
+        if poll.is_pending() {
+            STATE.with_borrow_mut(|state| state.tasks.insert(id, task));
+        }
+        true
+    }
+
+    /// Polls tasks until none are ready to run on this thread, and returns how many polls were
+    /// made.
+    ///
+    /// Returns an error if the tasks are still running after the
+    /// [poll limit](TestExecutor::set_poll_limit), which defaults to 10,000 polls.
+    pub fn run_until_idle() -> Result<usize, Unsettled> {
+        let limit = STATE.with_borrow(|state| state.poll_limit);
+        let mut polls = 0;
+        while polls < limit {
+            if !Self::step() {
+                return Ok(polls);
+            }
+            polls += 1;
+        }
+        match Self::ready() {
+            0 => Ok(polls),
+            ready => Err(Unsettled { polls, ready }),
+        }
+    }
+
+    /// Moves the virtual clock forward by `duration`, running tasks until they are idle each time
+    /// a timer fires, and returns how many polls were made.
+    ///
+    /// Timers fire in the order of their deadlines, and the clock reads each deadline while the
+    /// tasks waiting on it run.
+    pub fn advance(duration: Duration) -> Result<usize, Unsettled> {
+        let target = Self::now() + duration;
+        let mut polls = Self::run_until_idle()?;
+        while let Some(due) = STATE.with_borrow_mut(|state| {
+            let deadline = state
+                .timers
+                .iter()
+                .map(|(deadline, _)| *deadline)
+                .filter(|deadline| *deadline <= target)
+                .min()?;
+            state.now = deadline;
+            let (due, timers) =
+                std::mem::take(&mut state.timers)
+                    .into_iter()
+                    .partition::<Vec<_>, _>(|(at, _)| *at <= deadline);
+            state.timers = timers;
+            Some(due)
+        }) {
+            for (_, waker) in due {
+                waker.wake();
+            }
+            polls += Self::run_until_idle()?;
+        }
+        STATE.with_borrow_mut(|state| state.now = target);
+        Ok(polls)
+    }
+
+    /// Returns how long the virtual clock on this thread has been advanced in total.
+    pub fn now() -> Duration {
+        STATE.with_borrow(|state| state.now)
+    }
+
+    /// Returns a `Future` that resolves once the virtual clock has been advanced by `duration`.
+    pub fn sleep(duration: Duration) -> Sleep {
+        Sleep {
+            deadline: Self::now() + duration,
+        }
+    }
+
+    /// Returns the number of tasks on this thread that have not finished yet.
+    pub fn pending() -> usize {
+        STATE.with_borrow(|state| state.tasks.len())
+    }
+
+    /// Returns the number of tasks on this thread that are ready to run.
+    pub fn ready() -> usize {
+        STATE.with_borrow(|state| {
+            let ready = state.ready.lock().unwrap();
+            ready
+                .iter()
+                .filter(|id| state.tasks.contains_key(id))
+                .count()
+        })
+    }
+
+    /// Sets the number of polls after which [`TestExecutor::run_until_idle`] decides that the
+    /// tasks on this thread will never settle.
+    pub fn set_poll_limit(limit: usize) {
+        STATE.with_borrow_mut(|state| state.poll_limit = limit);
+    }
+
+    fn queue(fut: PinnedLocalFuture<()>) {
+        STATE.with_borrow_mut(|state| {
+            let id = state.next_id;
+            state.next_id += 1;
+            state.tasks.insert(id, fut);
+            state.ready.lock().unwrap().push_back(id);
+        });
+    }
+}
+
+/// A timer on the virtual clock of the [`TestExecutor`], created with [`TestExecutor::sleep`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Sleep {
+    deadline: Duration,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        STATE.with_borrow_mut(|state| {
+            if state.now >= self.deadline {
+                Poll::Ready(())
+            } else {
+                state.timers.push((self.deadline, cx.waker().clone()));
+                Poll::Pending
+            }
+        })
+    }
+}
+
+struct State {
+    next_id: usize,
+    tasks: HashMap<usize, PinnedLocalFuture<()>>,
+    // shared with the wakers, which can be called from other threads
+    ready: Arc<Mutex<VecDeque<usize>>>,
+    now: Duration,
+    timers: Vec<(Duration, Waker)>,
+    poll_limit: usize,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            tasks: HashMap::new(),
+            ready: Default::default(),
+            now: Duration::ZERO,
+            timers: Vec::new(),
+            poll_limit: DEFAULT_POLL_LIMIT,
+        }
+    }
+}
+
+struct TaskWaker {
+    id: usize,
+    ready: Arc<Mutex<VecDeque<usize>>>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let mut ready = self.ready.lock().unwrap();
+        if !ready.contains(&self.id) {
+            ready.push_back(self.id);
+        }
+    }
+}
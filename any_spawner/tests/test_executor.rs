@@ -0,0 +1,85 @@
+#![cfg(feature = "test-executor")]
+
+use any_spawner::{Executor, TestExecutor, Unsettled};
+use futures::future::poll_fn;
+use std::{
+    sync::{Arc, Mutex},
+    task::Poll,
+    time::Duration,
+};
+
+fn init() {
+    _ = Executor::init_custom_executor(TestExecutor);
+}
+
+#[test]
+fn tasks_only_run_when_stepped() {
+    init();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    for name in ["a", "b"] {
+        let log = Arc::clone(&log);
+        Executor::spawn(async move {
+            log.lock().unwrap().push(name);
+            Executor::tick().await;
+            log.lock().unwrap().push(name);
+        });
+    }
+
+    assert!(log.lock().unwrap().is_empty());
+    assert_eq!(TestExecutor::pending(), 2);
+    assert!(TestExecutor::step());
+    assert_eq!(*log.lock().unwrap(), ["a"]);
+    assert!(TestExecutor::step());
+    assert_eq!(*log.lock().unwrap(), ["a", "b"]);
+
+    TestExecutor::run_until_idle().unwrap();
+    assert_eq!(*log.lock().unwrap(), ["a", "b", "a", "b"]);
+    assert_eq!(TestExecutor::pending(), 0);
+    assert!(!TestExecutor::step());
+}
+
+#[test]
+fn timers_fire_in_order_on_the_virtual_clock() {
+    init();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    for secs in [3, 1, 2] {
+        let log = Arc::clone(&log);
+        Executor::spawn_local(async move {
+            TestExecutor::sleep(Duration::from_secs(secs)).await;
+            log.lock().unwrap().push((secs, TestExecutor::now()));
+        });
+    }
+
+    TestExecutor::run_until_idle().unwrap();
+    TestExecutor::advance(Duration::from_millis(1500)).unwrap();
+    assert_eq!(*log.lock().unwrap(), [(1, Duration::from_secs(1))]);
+    assert_eq!(TestExecutor::now(), Duration::from_millis(1500));
+
+    TestExecutor::advance(Duration::from_secs(10)).unwrap();
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            (1, Duration::from_secs(1)),
+            (2, Duration::from_secs(2)),
+            (3, Duration::from_secs(3))
+        ]
+    );
+}
+
+#[test]
+fn tasks_that_never_settle_are_reported() {
+    init();
+    TestExecutor::set_poll_limit(100);
+    Executor::spawn(poll_fn(|cx| {
+        cx.waker().wake_by_ref();
+        Poll::<()>::Pending
+    }));
+
+    assert_eq!(
+        TestExecutor::run_until_idle(),
+        Err(Unsettled {
+            polls: 100,
+            ready: 1
+        })
+    );
+}
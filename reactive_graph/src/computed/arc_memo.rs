@@ -154,14 +154,21 @@ where
     pub fn new_owning(
         fun: impl Fn(Option<T>) -> (T, bool) + Send + Sync + 'static,
     ) -> Self {
+        let defined_at = Location::caller();
         let inner = Arc::new_cyclic(|weak| {
             let subscriber = AnySubscriber(
                 weak.as_ptr() as usize,
                 Weak::clone(weak) as Weak<dyn Subscriber + Send + Sync>,
             );
 
-            MemoInner::new(Arc::new(fun), subscriber)
+            MemoInner::new(Arc::new(fun), subscriber, defined_at)
         });
+        #[cfg(feature = "introspection")]
+        crate::introspection::register(
+            crate::introspection::NodeKind::Memo,
+            Some(Location::caller()),
+            &inner,
+        );
         Self {
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             defined_at: Location::caller(),
@@ -237,6 +237,12 @@ macro_rules! spawn_derived {
             suspenses: Vec::new(),
             pending_suspenses: Vec::new()
         }));
+        #[cfg(feature = "introspection")]
+        crate::introspection::register(
+            crate::introspection::NodeKind::AsyncDerived,
+            Some(Location::caller()),
+            &inner,
+        );
         let value = Arc::new(AsyncRwLock::new($initial));
         let wakers = Arc::new(RwLock::new(Vec::new()));
 
@@ -353,11 +359,11 @@ macro_rules! spawn_derived {
                                         Box::pin(fut)
                                     });
 
-                                    // register with global transition listener, if any
+                                    // register with the transition running under this owner, if any
                                     let ready_tx = first_run.take().unwrap_or_else(|| {
                                         let (ready_tx, ready_rx) = oneshot::channel();
                                         if !was_ready {
-                                            AsyncTransition::register(ready_rx);
+                                            owner.with(|| AsyncTransition::register(ready_rx));
                                         }
                                         ready_tx
                                     });
@@ -96,6 +96,21 @@ impl Source for RwLock<ArcAsyncDerivedInner> {
     }
 }
 
+#[cfg(feature = "introspection")]
+impl crate::introspection::Inspect for RwLock<ArcAsyncDerivedInner> {
+    fn sources(&self) -> Vec<usize> {
+        self.read().or_poisoned().sources.ids()
+    }
+
+    fn subscribers(&self) -> Vec<usize> {
+        self.read().or_poisoned().subscribers.ids()
+    }
+
+    fn hold(&self, registration: crate::introspection::Registration) {
+        self.write().or_poisoned().subscribers.hold(registration);
+    }
+}
+
 impl Subscriber for RwLock<ArcAsyncDerivedInner> {
     fn add_source(&self, source: AnySource) {
         self.write().or_poisoned().sources.insert(source);
@@ -21,6 +21,8 @@ where
     pub(crate) fun: Arc<dyn Fn(Option<T>) -> (T, bool) + Send + Sync>,
     pub(crate) owner: Owner,
     pub(crate) reactivity: RwLock<MemoInnerReactivity>,
+    #[cfg(debug_assertions)]
+    defined_at: &'static std::panic::Location<'static>,
 }
 
 pub(crate) struct MemoInnerReactivity {
@@ -44,9 +46,11 @@ where
     S: Storage<T>,
 {
     #[allow(clippy::type_complexity)]
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
     pub fn new(
         fun: Arc<dyn Fn(Option<T>) -> (T, bool) + Send + Sync>,
         any_subscriber: AnySubscriber,
+        defined_at: &'static std::panic::Location<'static>,
     ) -> Self {
         Self {
             value: Arc::new(RwLock::new(None)),
@@ -58,6 +62,24 @@ where
                 subscribers: SubscriberSet::new(),
                 any_subscriber,
             }),
+            #[cfg(debug_assertions)]
+            defined_at,
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+impl<T, S> MemoInner<T, S>
+where
+    S: Storage<T>,
+{
+    /// A memo that is notified while it runs has written to one of its own sources, and will
+    /// never settle on a value.
+    fn panic_on_cycle(&self) {
+        if let Some(cycle) = crate::diagnostics::notified_while_running(
+            self as *const Self as usize,
+        ) {
+            panic!("{cycle}");
         }
     }
 }
@@ -67,6 +89,8 @@ where
     S: Storage<T>,
 {
     fn mark_dirty(&self) {
+        #[cfg(debug_assertions)]
+        self.panic_on_cycle();
         self.reactivity.write().or_poisoned().state = ReactiveNodeState::Dirty;
         self.mark_subscribers_check();
     }
@@ -86,6 +110,8 @@ where
                 sub.mark_check();
             }
         }
+        #[cfg(debug_assertions)]
+        self.panic_on_cycle();
         inner(&self.reactivity);
     }
 
@@ -117,6 +143,13 @@ where
         }
 
         if needs_update(&self.reactivity) {
+            #[cfg(debug_assertions)]
+            let _running = crate::diagnostics::enter_running(
+                self as *const Self as usize,
+                "Memo",
+                Some(self.defined_at),
+            );
+
             // No deadlock risk, because we only hold the value lock.
             let value = self.value.write().or_poisoned().take();
 
@@ -204,6 +237,28 @@ where
     }
 }
 
+#[cfg(feature = "introspection")]
+impl<T: 'static, S> crate::introspection::Inspect for MemoInner<T, S>
+where
+    S: Storage<T>,
+{
+    fn sources(&self) -> Vec<usize> {
+        self.reactivity.read().or_poisoned().sources.ids()
+    }
+
+    fn subscribers(&self) -> Vec<usize> {
+        self.reactivity.read().or_poisoned().subscribers.ids()
+    }
+
+    fn hold(&self, registration: crate::introspection::Registration) {
+        self.reactivity
+            .write()
+            .or_poisoned()
+            .subscribers
+            .hold(registration);
+    }
+}
+
 impl<T: 'static, S> Subscriber for MemoInner<T, S>
 where
     S: Storage<T>,
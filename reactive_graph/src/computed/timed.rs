@@ -0,0 +1,275 @@
+use crate::{
+    effect::Effect,
+    graph::untrack,
+    owner::Owner,
+    signal::{arc_signal, ArcReadSignal, ReadSignal},
+    traits::{Get, Set},
+};
+use futures::future::{AbortHandle, Abortable};
+use or_poisoned::OrPoisoned;
+use std::{
+    future::Future,
+    panic::Location,
+    pin::Pin,
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+use thiserror::Error;
+
+/// Waits for a given amount of time, for [debounced](debounced) and [throttled](throttled)
+/// signals.
+///
+/// A timer is set for the whole program with [`set_timer`]. This makes it possible to use the
+/// timer that matches the async runtime, or to use a virtual clock in tests, so that they do not
+/// need to wait in real time. With the `tokio` feature, `tokio::time::sleep` is used until
+/// another timer is set.
+///
+/// Any `Fn(Duration) -> impl Future<Output = ()>` can be used as a timer:
+/// ```rust,ignore
+/// reactive_graph::computed::set_timer(tokio::time::sleep).unwrap();
+/// ```
+pub trait Timer: Send + Sync + 'static {
+    /// Returns a `Future` that resolves once `duration` has passed.
+    fn sleep(
+        &self,
+        duration: Duration,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+impl<F, Fut> Timer for F
+where
+    F: Fn(Duration) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    fn sleep(
+        &self,
+        duration: Duration,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(self(duration))
+    }
+}
+
+/// Returned by [`set_timer`] if a timer has already been set.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("The global timer has already been set.")]
+pub struct TimerAlreadySet;
+
+static TIMER: OnceLock<Box<dyn Timer>> = OnceLock::new();
+
+/// Sets the [`Timer`] used by debounced and throttled signals.
+///
+/// This can only be called once. Without the `tokio` feature, it must be called before a
+/// debounced or throttled signal's source changes, or that signal will panic.
+pub fn set_timer(timer: impl Timer) -> Result<(), TimerAlreadySet> {
+    TIMER.set(Box::new(timer)).map_err(|_| TimerAlreadySet)
+}
+
+#[cfg(feature = "tokio")]
+static DEFAULT_TIMER: fn(Duration) -> tokio::time::Sleep = tokio::time::sleep;
+
+/// Returns the timer set with [`set_timer`], or the default one if none has been set.
+///
+/// # Panics
+/// Panics if no timer has been set and the `tokio` feature is not enabled.
+#[cfg_attr(feature = "tokio", allow(unused_variables))]
+fn timer(defined_at: &'static Location<'static>) -> &'static dyn Timer {
+    match TIMER.get() {
+        Some(timer) => &**timer,
+        #[cfg(feature = "tokio")]
+        None => &DEFAULT_TIMER,
+        #[cfg(not(feature = "tokio"))]
+        None => panic!(
+            "The debounced or throttled signal created at {defined_at} needs \
+             a timer. Call `reactive_graph::computed::set_timer` first, or \
+             enable the `tokio` feature of `reactive_graph`."
+        ),
+    }
+}
+
+/// The wait that a debounced or throttled signal is running, if any.
+#[derive(Clone, Default)]
+pub(crate) struct PendingWait(Arc<Mutex<Option<AbortHandle>>>);
+
+impl PendingWait {
+    /// Spawns `wait`, aborting the wait that was pending before it.
+    fn spawn(&self, wait: impl Future<Output = ()> + Send + 'static) {
+        let (handle, registration) = AbortHandle::new_pair();
+        if let Some(prev) = self.0.lock().or_poisoned().replace(handle) {
+            prev.abort();
+        }
+        crate::spawn(async move {
+            _ = Abortable::new(wait, registration).await;
+        });
+    }
+
+    /// Aborts the pending wait.
+    fn abort(&self) {
+        if let Some(handle) = self.0.lock().or_poisoned().take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Follows the `source` signal, stopping when the current [`Owner`] is cleaned up. `on_change`
+/// is called with each new value of the source, and the wait it can spawn, which is aborted when
+/// the owner is cleaned up.
+pub(crate) fn follow<T>(
+    source: impl Get<Value = T> + Send + Sync + 'static,
+    mut on_change: impl FnMut(T, &PendingWait) + Send + Sync + 'static,
+) where
+    T: Clone + Send + Sync + 'static,
+{
+    let pending = PendingWait::default();
+    Owner::on_cleanup({
+        let pending = pending.clone();
+        move || pending.abort()
+    });
+    Effect::new_sync(move |prev: Option<()>| {
+        let value = source.get();
+        // the first run only subscribes to the source
+        if prev.is_some() {
+            on_change(value, &pending);
+        }
+    });
+}
+
+/// Creates a signal that follows `source`, but only updates once `source` has stopped changing
+/// for `duration`.
+///
+/// This is useful for work that should not be repeated on every change, such as searching while
+/// the user is still typing. Each change restarts the wait, and only the last value is kept.
+/// The signal stops updating when the current [`Owner`] is cleaned up.
+///
+/// Like an [`Effect`], this only follows the source when the `effects` feature is enabled. The
+/// wait is measured by the [`Timer`] set with [`set_timer`].
+/// ```
+/// # use reactive_graph::{computed::*, prelude::*, signal::*};
+/// # use any_spawner::{Executor, TestExecutor};
+/// # use std::time::Duration;
+/// # _ = Executor::init_custom_executor(TestExecutor);
+/// # _ = set_timer(TestExecutor::sleep);
+/// # let owner = reactive_graph::owner::Owner::new(); owner.set();
+/// let query = ArcRwSignal::new(String::new());
+/// let debounced_query = arc_debounced(query.clone(), Duration::from_millis(300));
+/// # TestExecutor::run_until_idle().unwrap();
+///
+/// query.set("lep".to_string());
+/// # TestExecutor::advance(Duration::from_millis(100)).unwrap();
+/// query.set("leptos".to_string());
+/// # TestExecutor::advance(Duration::from_millis(299)).unwrap();
+/// // still waiting for the input to settle
+/// assert_eq!(debounced_query.get(), "");
+///
+/// // 300ms after the last change, `debounced_query` is updated to "leptos"
+/// ```
+#[track_caller]
+pub fn arc_debounced<T>(
+    source: impl Get<Value = T> + Send + Sync + 'static,
+    duration: Duration,
+) -> ArcReadSignal<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let defined_at = Location::caller();
+    let (value, set_value) = arc_signal(untrack(|| source.get()));
+    follow(source, move |next, pending| {
+        let timer = timer(defined_at);
+        let set_value = set_value.clone();
+        // restarting the wait aborts the one for the previous value
+        pending.spawn(async move {
+            timer.sleep(duration).await;
+            set_value.set(next);
+        });
+    });
+    value
+}
+
+/// Creates a signal that follows `source`, but only updates once `source` has stopped changing
+/// for `duration`.
+///
+/// This is an arena-allocated version of [`arc_debounced`].
+#[track_caller]
+pub fn debounced<T>(
+    source: impl Get<Value = T> + Send + Sync + 'static,
+    duration: Duration,
+) -> ReadSignal<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    arc_debounced(source, duration).into()
+}
+
+/// Creates a signal that follows `source`, but updates at most once every `duration`.
+///
+/// The first change is passed on at once, and starts a wait of `duration`. If the source changes
+/// during the wait, its latest value is passed on when the wait ends, and another wait starts;
+/// otherwise, the next change is again passed on at once. The signal stops updating when the
+/// current [`Owner`] is cleaned up.
+///
+/// Like an [`Effect`], this only follows the source when the `effects` feature is enabled. The
+/// wait is measured by the [`Timer`] set with [`set_timer`].
+#[track_caller]
+pub fn arc_throttled<T>(
+    source: impl Get<Value = T> + Send + Sync + 'static,
+    duration: Duration,
+) -> ArcReadSignal<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let defined_at = Location::caller();
+    let (value, set_value) = arc_signal(untrack(|| source.get()));
+    let throttle = Arc::new(Mutex::new(Throttle {
+        waiting: false,
+        pending: None,
+    }));
+    follow(source, move |next, pending| {
+        let timer = timer(defined_at);
+        let mut lock = throttle.lock().or_poisoned();
+        if lock.waiting {
+            lock.pending = Some(next);
+            return;
+        }
+        lock.waiting = true;
+        drop(lock);
+        set_value.set(next);
+
+        let throttle = Arc::clone(&throttle);
+        let set_value = set_value.clone();
+        pending.spawn(async move {
+            loop {
+                timer.sleep(duration).await;
+                let mut lock = throttle.lock().or_poisoned();
+                match lock.pending.take() {
+                    Some(next) => {
+                        drop(lock);
+                        set_value.set(next);
+                    }
+                    None => {
+                        lock.waiting = false;
+                        break;
+                    }
+                }
+            }
+        });
+    });
+    value
+}
+
+/// Creates a signal that follows `source`, but updates at most once every `duration`.
+///
+/// This is an arena-allocated version of [`arc_throttled`].
+#[track_caller]
+pub fn throttled<T>(
+    source: impl Get<Value = T> + Send + Sync + 'static,
+    duration: Duration,
+) -> ReadSignal<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    arc_throttled(source, duration).into()
+}
+
+struct Throttle<T> {
+    waiting: bool,
+    pending: Option<T>,
+}
@@ -7,6 +7,12 @@
 //!
 //! This module provides utilities to suppress those warnings by entering a
 //! [`SpecialNonReactiveZone`].
+//!
+//! In debug mode, this module also detects cycles in the reactive graph: a memo that reads itself,
+//! directly or through other memos, or a memo or effect that writes to a signal it depends on.
+//! A cycle through a memo causes a panic, because the memo can never settle on a value, and a
+//! cycle through an effect causes a warning, because the effect will run again. Either way, the
+//! message lists each node in the cycle, with the location where it was created.
 
 /// Marks an execution block that is known not to be reactive, and suppresses warnings.
 #[derive(Debug)]
@@ -23,6 +29,8 @@ use std::{
     pin::Pin,
     task::{Context, Poll},
 };
+#[cfg(debug_assertions)]
+use std::{cell::RefCell, fmt::Write, panic::Location};
 
 thread_local! {
     static IS_SPECIAL_ZONE: Cell<bool> = const { Cell::new(false) };
@@ -93,3 +101,146 @@ pub fn suppress_resource_load(suppress: bool) {
 pub fn is_suppressing_resource_load() -> bool {
     SUPPRESS_RESOURCE_LOAD.with(|w| w.get())
 }
+
+/// A memo or effect that is running on this thread.
+#[cfg(debug_assertions)]
+#[derive(Debug, Clone, Copy)]
+struct RunningNode {
+    id: usize,
+    kind: &'static str,
+    defined_at: Option<&'static Location<'static>>,
+}
+
+#[cfg(debug_assertions)]
+impl std::fmt::Display for RunningNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        describe(f, self.kind, self.defined_at)
+    }
+}
+
+#[cfg(debug_assertions)]
+fn describe(
+    f: &mut impl Write,
+    kind: &str,
+    defined_at: Option<&'static Location<'static>>,
+) -> std::fmt::Result {
+    match defined_at {
+        Some(location) => write!(f, "{kind} defined at {location}"),
+        None => f.write_str(kind),
+    }
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+    static RUNNING: RefCell<Vec<RunningNode>> = const { RefCell::new(Vec::new()) };
+    // the location of the signal that is notifying its subscribers, if any
+    static NOTIFYING: Cell<Option<Option<&'static Location<'static>>>> =
+        const { Cell::new(None) };
+}
+
+/// Marks a memo or effect as running on this thread until it is dropped.
+#[cfg(debug_assertions)]
+#[derive(Debug)]
+pub(crate) struct RunningGuard(());
+
+#[cfg(debug_assertions)]
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.with_borrow_mut(|running| running.pop());
+    }
+}
+
+/// Marks the memo or effect with the given ID as running until the guard is dropped.
+///
+/// Panics if the node is already running, because it then depends on itself.
+#[cfg(debug_assertions)]
+pub(crate) fn enter_running(
+    id: usize,
+    kind: &'static str,
+    defined_at: Option<&'static Location<'static>>,
+) -> RunningGuard {
+    let node = RunningNode {
+        id,
+        kind,
+        defined_at,
+    };
+    let cycle = RUNNING.with_borrow_mut(|running| {
+        let cycle =
+            running.iter().position(|node| node.id == id).map(|start| {
+                let mut message = format!(
+                "Detected a cycle in the reactive graph: {node} depends on \
+                 itself.\n    {}",
+                running[start]
+            );
+                for node in running[start + 1..].iter().chain([&node]) {
+                    _ = write!(message, "\n      reads {node}");
+                }
+                message
+            });
+        if cycle.is_none() {
+            running.push(node);
+        }
+        cycle
+    });
+    if let Some(cycle) = cycle {
+        panic!("{cycle}");
+    }
+    RunningGuard(())
+}
+
+/// Marks an immediate effect as running until the guard is dropped.
+///
+/// Unlike [`enter_running`], this does not panic if the effect is already running: an immediate
+/// effect that writes to one of its own sources runs again recursively, which is reported as a
+/// warning when it is notified.
+#[cfg(debug_assertions)]
+pub(crate) fn enter_running_recursive(
+    id: usize,
+    kind: &'static str,
+    defined_at: Option<&'static Location<'static>>,
+) -> RunningGuard {
+    RUNNING.with_borrow_mut(|running| {
+        running.push(RunningNode {
+            id,
+            kind,
+            defined_at,
+        })
+    });
+    RunningGuard(())
+}
+
+/// Runs `fun` while the signal defined at `defined_at` notifies its subscribers, so that a cycle
+/// can be reported with the signal that closed it.
+#[cfg(debug_assertions)]
+pub(crate) fn notifying<T>(
+    defined_at: Option<&'static Location<'static>>,
+    fun: impl FnOnce() -> T,
+) -> T {
+    let prev = NOTIFYING.replace(Some(defined_at));
+    let value = fun();
+    NOTIFYING.set(prev);
+    value
+}
+
+/// Checks whether the memo or effect with the given ID is being notified while it runs, which
+/// means that it wrote to one of its own sources. If so, returns a description of the cycle.
+#[cfg(debug_assertions)]
+pub(crate) fn notified_while_running(id: usize) -> Option<String> {
+    RUNNING.with_borrow(|running| {
+        let start = running.iter().position(|node| node.id == id)?;
+        let node = running[start];
+        let mut message = format!(
+            "Detected a cycle in the reactive graph: {node} wrote to a signal \
+             it depends on while it was running.\n    {node}"
+        );
+        for node in &running[start + 1..] {
+            _ = write!(message, "\n      reads {node}");
+        }
+        if let Some(defined_at) = NOTIFYING.get() {
+            message.push_str("\n      writes ");
+            _ = describe(&mut message, "Signal", defined_at);
+        }
+        _ = write!(message, "\n      notifies {node}");
+        Some(message)
+    })
+}
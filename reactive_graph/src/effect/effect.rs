@@ -1,6 +1,8 @@
 use crate::{
     channel::{channel, Receiver},
-    effect::{inner::EffectInner, EffectFunction},
+    effect::{
+        inner::EffectInner, queue::Queued, EffectFunction, EffectPriority,
+    },
     graph::{
         AnySubscriber, ReactiveNode, SourceSet, Subscriber, ToAnySubscriber,
         WithObserver,
@@ -105,6 +107,7 @@ fn effect_base() -> (Receiver, Owner, Arc<RwLock<EffectInner>>) {
         dirty: true,
         observer,
         sources: SourceSet::new(),
+        queued: None,
     }));
 
     (rx, owner, inner)
@@ -165,12 +168,21 @@ impl Effect<LocalStorage> {
     /// This spawns a task on the local thread using
     /// [`spawn_local`](any_spawner::Executor::spawn_local). For an effect that can be spawned on
     /// any thread, use [`new_sync`](Effect::new_sync).
+    #[track_caller]
     pub fn new<T, M>(mut fun: impl EffectFunction<T, M> + 'static) -> Self
     where
         T: 'static,
     {
+        #[cfg(any(debug_assertions, feature = "introspection"))]
+        let defined_at = std::panic::Location::caller();
         let inner = cfg!(feature = "effects").then(|| {
             let (mut rx, owner, inner) = effect_base();
+            #[cfg(feature = "introspection")]
+            crate::introspection::register(
+                crate::introspection::NodeKind::Effect,
+                Some(defined_at),
+                &inner,
+            );
             let value = Arc::new(RwLock::new(None::<T>));
             let mut first_run = true;
 
@@ -188,6 +200,12 @@ impl Effect<LocalStorage> {
                             first_run = false;
                             subscriber.clear_sources(&subscriber);
 
+                            #[cfg(debug_assertions)]
+                            let _running = crate::diagnostics::enter_running(
+                                subscriber.0,
+                                "Effect",
+                                Some(defined_at),
+                            );
                             let old_value =
                                 mem::take(&mut *value.write().or_poisoned());
                             let new_value = owner.with_cleanup(|| {
@@ -207,6 +225,74 @@ impl Effect<LocalStorage> {
         Self { inner }
     }
 
+    /// Creates a new effect that runs in the effect queue for the current thread, rather than in
+    /// its own task.
+    ///
+    /// Like [`Effect::new`], the effect runs once on the next “tick”, and then runs again when
+    /// reactive values that are read inside it change. Whenever the queue is flushed, queued
+    /// effects run in the order of their [`EffectPriority`], so that, for example, every
+    /// [`EffectPriority::Render`] effect has settled before any [`EffectPriority::Idle`] effect
+    /// runs. The queue can also be flushed by hand with [`flush_effects`](super::flush_effects).
+    ///
+    /// If the effect is notified while its [`Owner`] is [paused](Owner::pause), it keeps its
+    /// place in the queue, and runs once the owner is [resumed](Owner::resume).
+    #[track_caller]
+    pub fn new_with_priority<T, M>(
+        priority: EffectPriority,
+        mut fun: impl EffectFunction<T, M> + 'static,
+    ) -> Self
+    where
+        T: 'static,
+    {
+        #[cfg(any(debug_assertions, feature = "introspection"))]
+        let defined_at = std::panic::Location::caller();
+        let inner = cfg!(feature = "effects").then(|| {
+            let (_, owner, inner) = effect_base();
+            #[cfg(feature = "introspection")]
+            crate::introspection::register(
+                crate::introspection::NodeKind::Effect,
+                Some(defined_at),
+                &inner,
+            );
+            let mut value = None::<T>;
+            let mut first_run = true;
+
+            let queued = Queued::new(priority, &owner, {
+                let subscriber = inner.to_any_subscriber();
+                let owner = owner.clone();
+
+                move || {
+                    if subscriber
+                        .with_observer(|| subscriber.update_if_necessary())
+                        || first_run
+                    {
+                        first_run = false;
+                        subscriber.clear_sources(&subscriber);
+
+                        #[cfg(debug_assertions)]
+                        let _running = crate::diagnostics::enter_running(
+                            subscriber.0,
+                            "Effect",
+                            Some(defined_at),
+                        );
+                        let old_value = value.take();
+                        value = Some(owner.with_cleanup(|| {
+                            subscriber.with_observer(|| {
+                                run_in_effect_scope(|| fun.run(old_value))
+                            })
+                        }));
+                    }
+                }
+            });
+            queued.notify();
+            inner.write().or_poisoned().queued = Some(queued);
+
+            ArenaItem::new_with_storage(Some(inner))
+        });
+
+        Self { inner }
+    }
+
     /// A version of [`Effect::new`] that only listens to any dependency
     /// that is accessed inside `dependency_fn`.
     ///
@@ -310,6 +396,7 @@ impl Effect<LocalStorage> {
     /// # }).await;
     /// # });
     /// ```
+    #[track_caller]
     pub fn watch<D, T>(
         mut dependency_fn: impl FnMut() -> D + 'static,
         mut handler: impl FnMut(&D, Option<&D>, Option<T>) -> T + 'static,
@@ -319,8 +406,16 @@ impl Effect<LocalStorage> {
         D: 'static,
         T: 'static,
     {
+        #[cfg(feature = "introspection")]
+        let defined_at = std::panic::Location::caller();
         let inner = cfg!(feature = "effects").then(|| {
             let (mut rx, owner, inner) = effect_base();
+            #[cfg(feature = "introspection")]
+            crate::introspection::register(
+                crate::introspection::NodeKind::Effect,
+                Some(defined_at),
+                &inner,
+            );
             let mut first_run = true;
             let dep_value = Arc::new(RwLock::new(None::<D>));
             let watch_value = Arc::new(RwLock::new(None::<T>));
@@ -383,6 +478,7 @@ impl Effect<SyncStorage> {
     ///
     /// This spawns a task that can be run on any thread. For an effect that will be spawned on
     /// the current thread, use [`new`](Effect::new).
+    #[track_caller]
     pub fn new_sync<T, M>(
         fun: impl EffectFunction<T, M> + Send + Sync + 'static,
     ) -> Self
@@ -400,13 +496,22 @@ impl Effect<SyncStorage> {
     /// that are read inside it change.
     ///
     /// This will run whether the `effects` feature is enabled or not.
+    #[track_caller]
     pub fn new_isomorphic<T, M>(
         mut fun: impl EffectFunction<T, M> + Send + Sync + 'static,
     ) -> Self
     where
         T: Send + Sync + 'static,
     {
+        #[cfg(any(debug_assertions, feature = "introspection"))]
+        let defined_at = std::panic::Location::caller();
         let (mut rx, owner, inner) = effect_base();
+        #[cfg(feature = "introspection")]
+        crate::introspection::register(
+            crate::introspection::NodeKind::Effect,
+            Some(defined_at),
+            &inner,
+        );
         let mut first_run = true;
         let value = Arc::new(RwLock::new(None::<T>));
 
@@ -424,6 +529,12 @@ impl Effect<SyncStorage> {
                         first_run = false;
                         subscriber.clear_sources(&subscriber);
 
+                        #[cfg(debug_assertions)]
+                        let _running = crate::diagnostics::enter_running(
+                            subscriber.0,
+                            "Effect",
+                            Some(defined_at),
+                        );
                         let old_value =
                             mem::take(&mut *value.write().or_poisoned());
                         let new_value = owner.with_cleanup(|| {
@@ -445,6 +556,7 @@ impl Effect<SyncStorage> {
     }
 
     /// This is to [`Effect::watch`] what [`Effect::new_sync`] is to [`Effect::new`].
+    #[track_caller]
     pub fn watch_sync<D, T>(
         mut dependency_fn: impl FnMut() -> D + Send + Sync + 'static,
         mut handler: impl FnMut(&D, Option<&D>, Option<T>) -> T
@@ -458,6 +570,12 @@ impl Effect<SyncStorage> {
         T: Send + Sync + 'static,
     {
         let (mut rx, owner, inner) = effect_base();
+        #[cfg(feature = "introspection")]
+        crate::introspection::register(
+            crate::introspection::NodeKind::Effect,
+            Some(std::panic::Location::caller()),
+            &inner,
+        );
         let mut first_run = true;
         let dep_value = Arc::new(RwLock::new(None::<D>));
         let watch_value = Arc::new(RwLock::new(None::<T>));
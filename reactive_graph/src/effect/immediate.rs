@@ -151,6 +151,9 @@ impl DefinedAt for ImmediateEffect {
 ///
 /// NOTE: this affects only [ImmediateEffect]s, not other effects.
 ///
+/// Every write to a [history signal](crate::signal::ArcHistorySignal) inside the batch is
+/// recorded as a single entry in its history.
+///
 /// NOTE: this is rarely needed, but it is useful for example when multiple signals
 /// need to be updated atomically (for example a double-bound signal tree).
 pub fn batch<T>(f: impl FnOnce() -> T) -> T {
@@ -172,6 +175,7 @@ pub fn batch<T>(f: impl FnOnce() -> T) -> T {
         let mut batch = inner::BATCH.write().or_poisoned();
         if batch.is_none() {
             execute_on_drop = Some(ExecuteOnDrop);
+            inner::BATCH_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         } else {
             // Nested batching has no effect.
         }
@@ -182,6 +186,15 @@ pub fn batch<T>(f: impl FnOnce() -> T) -> T {
     ret
 }
 
+/// Returns an ID for the outermost [batch] that is currently running, if any.
+pub(crate) fn current_batch() -> Option<u64> {
+    inner::BATCH
+        .read()
+        .or_poisoned()
+        .is_some()
+        .then(|| inner::BATCH_ID.load(std::sync::atomic::Ordering::Relaxed))
+}
+
 mod inner {
     use crate::{
         graph::{
@@ -205,6 +218,10 @@ mod inner {
     pub(super) static BATCH: RwLock<Option<RwLock<IndexSet<AnySubscriber>>>> =
         RwLock::new(None);
 
+    /// Incremented each time an outermost batch starts.
+    pub(super) static BATCH_ID: std::sync::atomic::AtomicU64 =
+        std::sync::atomic::AtomicU64::new(0);
+
     /// Handles subscription logic for effects.
     ///
     /// To handle parallelism and recursion we assign ordered (1..) ids to each run.
@@ -263,7 +280,7 @@ mod inner {
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             let defined_at = Location::caller();
 
-            Arc::new_cyclic(|weak| {
+            let inner = Arc::new_cyclic(|weak| {
                 let any_subscriber = AnySubscriber(
                     weak.as_ptr() as usize,
                     Weak::clone(weak) as Weak<dyn Subscriber + Send + Sync>,
@@ -282,7 +299,14 @@ mod inner {
                     sources: SourceSet::new(),
                     any_subscriber,
                 })
-            })
+            });
+            #[cfg(feature = "introspection")]
+            crate::introspection::register(
+                crate::introspection::NodeKind::ImmediateEffect,
+                Some(Location::caller()),
+                &inner,
+            );
+            inner
         }
     }
 
@@ -337,6 +361,8 @@ mod inner {
                 let owner = guard.owner.clone();
                 let any_subscriber = guard.any_subscriber.clone();
                 let fun = guard.fun.clone();
+                #[cfg(debug_assertions)]
+                let defined_at = guard.defined_at;
 
                 // New run has started.
                 guard.run_count_start += 1;
@@ -357,7 +383,15 @@ mod inner {
 
                 // We execute the effect.
                 // Note that *this could happen in parallel across threads*.
+                #[cfg(debug_assertions)]
+                let running = crate::diagnostics::enter_running_recursive(
+                    any_subscriber.0,
+                    "ImmediateEffect",
+                    Some(defined_at),
+                );
                 owner.with_cleanup(|| any_subscriber.with_observer(|| fun()));
+                #[cfg(debug_assertions)]
+                drop(running);
 
                 let mut guard = self.write().or_poisoned();
 
@@ -387,16 +421,42 @@ mod inner {
         }
 
         fn mark_check(&self) {
+            #[cfg(debug_assertions)]
+            warn_on_cycle(self);
             self.write().or_poisoned().state = ReactiveNodeState::Check;
             self.update_if_necessary();
         }
 
         fn mark_dirty(&self) {
+            #[cfg(debug_assertions)]
+            warn_on_cycle(self);
             self.write().or_poisoned().state = ReactiveNodeState::Dirty;
             self.update_if_necessary();
         }
     }
 
+    /// An effect that is notified while it runs has written to one of its own sources, and will
+    /// run again recursively.
+    #[cfg(debug_assertions)]
+    fn warn_on_cycle(effect: &RwLock<EffectInner>) {
+        if let Some(cycle) = crate::diagnostics::notified_while_running(
+            effect as *const RwLock<EffectInner> as usize,
+        ) {
+            log_warning(format_args!("{cycle}"));
+        }
+    }
+
+    #[cfg(feature = "introspection")]
+    impl crate::introspection::Inspect for RwLock<EffectInner> {
+        fn sources(&self) -> Vec<usize> {
+            self.read().or_poisoned().sources.ids()
+        }
+
+        fn hold(&self, registration: crate::introspection::Registration) {
+            self.write().or_poisoned().sources.hold(registration);
+        }
+    }
+
     impl Subscriber for RwLock<EffectInner> {
         fn add_source(&self, source: AnySource) {
             let mut guard = self.write().or_poisoned();
@@ -1,5 +1,6 @@
 use crate::{
     channel::Sender,
+    effect::queue::Queued,
     graph::{
         AnySource, AnySubscriber, ReactiveNode, SourceSet, Subscriber,
         ToAnySubscriber,
@@ -14,6 +15,17 @@ pub struct EffectInner {
     pub(crate) dirty: bool,
     pub(crate) observer: Sender,
     pub(crate) sources: SourceSet,
+    /// Set for effects that are run by the effect queue rather than by their own task.
+    pub(crate) queued: Option<Queued>,
+}
+
+impl EffectInner {
+    fn notify(&mut self) {
+        match &self.queued {
+            Some(queued) => queued.notify(),
+            None => self.observer.notify(),
+        }
+    }
 }
 
 impl ToAnySubscriber for Arc<RwLock<EffectInner>> {
@@ -46,13 +58,39 @@ impl ReactiveNode for RwLock<EffectInner> {
     }
 
     fn mark_check(&self) {
-        self.write().or_poisoned().observer.notify()
+        #[cfg(debug_assertions)]
+        warn_on_cycle(self);
+        self.write().or_poisoned().notify()
     }
 
     fn mark_dirty(&self) {
+        #[cfg(debug_assertions)]
+        warn_on_cycle(self);
         let mut lock = self.write().or_poisoned();
         lock.dirty = true;
-        lock.observer.notify()
+        lock.notify()
+    }
+}
+
+/// An effect that is notified while it runs has written to one of its own sources, and will run
+/// again.
+#[cfg(debug_assertions)]
+fn warn_on_cycle(effect: &RwLock<EffectInner>) {
+    if let Some(cycle) = crate::diagnostics::notified_while_running(
+        effect as *const RwLock<EffectInner> as usize,
+    ) {
+        crate::log_warning(format_args!("{cycle}"));
+    }
+}
+
+#[cfg(feature = "introspection")]
+impl crate::introspection::Inspect for RwLock<EffectInner> {
+    fn sources(&self) -> Vec<usize> {
+        self.read().or_poisoned().sources.ids()
+    }
+
+    fn hold(&self, registration: crate::introspection::Registration) {
+        self.write().or_poisoned().sources.hold(registration);
     }
 }
 
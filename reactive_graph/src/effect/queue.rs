@@ -0,0 +1,260 @@
+use crate::owner::Owner;
+use any_spawner::Executor;
+use futures::{
+    channel::mpsc::{self, UnboundedSender},
+    StreamExt,
+};
+use or_poisoned::OrPoisoned;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, ThreadId},
+};
+
+/// The order in which queued effects run, when the effect queue is flushed.
+///
+/// Effects created with [`Effect::new_with_priority`](super::Effect::new_with_priority) do not
+/// each run in their own task. Instead, they are added to a queue for the current thread when
+/// they are notified, and the queue is flushed once per tick: first every `Render` effect, then
+/// every `User` effect, and finally every `Idle` effect. If running an effect notifies an effect
+/// with a higher priority, that effect runs next.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EffectPriority {
+    /// Runs before any other queued effect, for effects that update what will be drawn.
+    Render,
+    /// Runs once render effects have settled. This is the priority for most effects.
+    #[default]
+    User,
+    /// Runs once all other queued effects have settled, for work such as persisting state.
+    Idle,
+}
+
+/// Runs every queued effect on the current thread now, in priority order, rather than waiting
+/// for the next tick.
+///
+/// Effects that are notified while the queue is flushed are run before this returns, so this can
+/// be used to let every [`EffectPriority::Render`] effect settle before drawing a frame. Calling
+/// this from inside a queued effect does nothing.
+pub fn flush_effects() {
+    if FLUSHING.replace(true) {
+        return;
+    }
+    let _flushing = Flushing;
+    let queue = FlushQueue::current();
+    while let Some((priority, id)) = queue.pop() {
+        queue.prune();
+        let runner = RUNNERS.with_borrow_mut(|runners| runners.remove(&id));
+        if let Some(run) = runner {
+            let mut running = Running { id, run: Some(run) };
+            // a paused effect keeps its place until its owner is resumed
+            if !running.run() {
+                DEFERRED.with_borrow_mut(|deferred| {
+                    deferred.insert((priority, id))
+                });
+            }
+        }
+    }
+    queue.prune();
+}
+
+/// Marks the queue as no longer being flushed when dropped, even if an effect panics.
+struct Flushing;
+
+impl Drop for Flushing {
+    fn drop(&mut self) {
+        FLUSHING.set(false);
+    }
+}
+
+/// Puts the function of an effect back when dropped, even if the effect panics.
+struct Running {
+    id: usize,
+    run: Option<Runner>,
+}
+
+impl Running {
+    fn run(&mut self) -> bool {
+        (self
+            .run
+            .as_mut()
+            .expect("the runner is only taken when dropped"))()
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        if let Some(run) = self.run.take() {
+            RUNNERS.with_borrow_mut(|runners| runners.insert(self.id, run));
+        }
+    }
+}
+
+/// Queues the effects on the current thread that were notified while their owners were paused,
+/// so that they run if their owners are no longer paused.
+pub(crate) fn resume_deferred() {
+    let deferred = DEFERRED.take();
+    if !deferred.is_empty() {
+        let queue = FlushQueue::current();
+        for (priority, id) in deferred {
+            queue.push(priority, id);
+        }
+    }
+}
+
+type Runner = Box<dyn FnMut() -> bool>;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static QUEUE: Arc<FlushQueue> = Arc::new(FlushQueue {
+        thread: thread::current().id(),
+        pending: Default::default(),
+        flush: Default::default(),
+    });
+    // the effect functions can only run on the thread that created them
+    static RUNNERS: RefCell<FxHashMap<usize, Runner>> = Default::default();
+    static DEFERRED: RefCell<BTreeSet<(EffectPriority, usize)>> = Default::default();
+    static FLUSHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The place of an effect in the queue of the thread that created it.
+///
+/// The effect is removed from the queue when this is dropped.
+#[derive(Debug)]
+pub(crate) struct Queued {
+    id: usize,
+    priority: EffectPriority,
+    queue: Arc<FlushQueue>,
+}
+
+impl Queued {
+    /// Adds an effect to the queue for the current thread, to run `run` whenever it is notified.
+    ///
+    /// `run` returns `false` if the effect could not run because its owner is paused.
+    pub fn new(
+        priority: EffectPriority,
+        owner: &Owner,
+        mut run: impl FnMut() + 'static,
+    ) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let owner = owner.clone();
+        let runner: Runner = Box::new(move || {
+            if owner.paused() {
+                return false;
+            }
+            run();
+            true
+        });
+        RUNNERS.with_borrow_mut(|runners| runners.insert(id, runner));
+        Self {
+            id,
+            priority,
+            queue: FlushQueue::current(),
+        }
+    }
+
+    /// Schedules the effect to run when the queue is next flushed.
+    pub fn notify(&self) {
+        self.queue.push(self.priority, self.id);
+    }
+}
+
+impl Drop for Queued {
+    fn drop(&mut self) {
+        let mut pending = self.queue.pending.lock().or_poisoned();
+        pending.ids.remove(&self.id);
+        pending.removed.push(self.id);
+    }
+}
+
+#[derive(Debug)]
+struct FlushQueue {
+    thread: ThreadId,
+    pending: Mutex<Pending>,
+    // wakes the task that flushes this queue
+    flush: Mutex<Option<UnboundedSender<()>>>,
+}
+
+#[derive(Debug, Default)]
+struct Pending {
+    next: u64,
+    queued: BTreeSet<(EffectPriority, u64, usize)>,
+    ids: FxHashSet<usize>,
+    removed: Vec<usize>,
+}
+
+impl FlushQueue {
+    fn current() -> Arc<Self> {
+        QUEUE.with(Arc::clone)
+    }
+
+    fn push(&self, priority: EffectPriority, id: usize) {
+        let mut pending = self.pending.lock().or_poisoned();
+        if pending.ids.insert(id) {
+            let next = pending.next;
+            pending.next += 1;
+            pending.queued.insert((priority, next, id));
+        }
+        drop(pending);
+        self.wake();
+    }
+
+    fn pop(&self) -> Option<(EffectPriority, usize)> {
+        let mut pending = self.pending.lock().or_poisoned();
+        loop {
+            let (priority, _, id) = pending.queued.pop_first()?;
+            // the effect may have been dropped after it was queued
+            if pending.ids.remove(&id) {
+                return Some((priority, id));
+            }
+        }
+    }
+
+    /// Drops the functions of effects that have been dropped. This must be called on the thread
+    /// that owns the queue.
+    fn prune(&self) {
+        let removed =
+            std::mem::take(&mut self.pending.lock().or_poisoned().removed);
+        if !removed.is_empty() {
+            let runners = RUNNERS.with_borrow_mut(|runners| {
+                removed
+                    .iter()
+                    .filter_map(|id| runners.remove(id))
+                    .collect::<Vec<_>>()
+            });
+            DEFERRED.with_borrow_mut(|deferred| {
+                deferred.retain(|(_, id)| !removed.contains(id))
+            });
+            // dropping an effect function can drop other effects, so do it outside the borrow
+            drop(runners);
+        }
+    }
+
+    /// Makes sure that the queue will be flushed on the next tick.
+    fn wake(&self) {
+        let mut flush = self.flush.lock().or_poisoned();
+        let sent = flush
+            .as_ref()
+            .is_some_and(|tx| tx.unbounded_send(()).is_ok());
+        // the flushing task can only be spawned from the thread the effects live on; if it has
+        // stopped, the queue is flushed the next time an effect on that thread is notified
+        if !sent && thread::current().id() == self.thread {
+            let (tx, mut rx) = mpsc::unbounded();
+            _ = tx.unbounded_send(());
+            *flush = Some(tx);
+            drop(flush);
+            Executor::spawn_local(async move {
+                while rx.next().await.is_some() {
+                    // notifications since the last flush are handled by this one
+                    while let Ok(Some(_)) = rx.try_next() {}
+                    flush_effects();
+                }
+            });
+        }
+    }
+}
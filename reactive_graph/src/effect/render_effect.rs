@@ -59,6 +59,7 @@ where
     T: 'static,
 {
     /// Creates a new render effect, which immediately runs `fun`.
+    #[track_caller]
     pub fn new(fun: impl FnMut(Option<T>) -> T + 'static) -> Self {
         #[cfg(feature = "subsecond")]
         let (hot_fn_ptr, fun) = {
@@ -87,6 +88,7 @@ where
     }
 
     /// Creates a new render effect with an initial value.
+    #[track_caller]
     pub fn new_with_value(
         fun: impl FnMut(Option<T>) -> T + 'static,
         initial_value: Option<T>,
@@ -134,6 +136,7 @@ where
         .await
     }
 
+    #[track_caller]
     fn new_with_value_erased(
         #[allow(unused_mut)] mut fun: Box<dyn FnMut(Option<T>) -> T + 'static>,
         initial_value: Option<T>,
@@ -152,11 +155,18 @@ where
                 dirty: false,
                 observer,
                 sources: SourceSet::new(),
+                queued: None,
             }));
             (owner, inner, rx)
         }
 
         let (owner, inner, mut rx) = prep();
+        #[cfg(feature = "introspection")]
+        crate::introspection::register(
+            crate::introspection::NodeKind::RenderEffect,
+            Some(std::panic::Location::caller()),
+            &inner,
+        );
 
         let value = Arc::new(RwLock::new(None::<T>));
 
@@ -170,6 +180,8 @@ where
 
         #[cfg(feature = "effects")]
         {
+            #[cfg(debug_assertions)]
+            let defined_at = std::panic::Location::caller();
             let subscriber = inner.to_any_subscriber();
 
             #[cfg(all(feature = "subsecond", debug_assertions))]
@@ -215,9 +227,15 @@ where
                 move |prev| fun.call((prev,))
             };
 
-            *value.write().or_poisoned() = Some(
-                owner.with(|| subscriber.with_observer(|| fun(initial_value))),
-            );
+            *value.write().or_poisoned() = Some(owner.with(|| {
+                #[cfg(debug_assertions)]
+                let _running = crate::diagnostics::enter_running(
+                    subscriber.0,
+                    "RenderEffect",
+                    Some(defined_at),
+                );
+                subscriber.with_observer(|| fun(initial_value))
+            }));
 
             any_spawner::Executor::spawn_local({
                 let value = Arc::clone(&value);
@@ -231,6 +249,12 @@ where
                         {
                             subscriber.clear_sources(&subscriber);
 
+                            #[cfg(debug_assertions)]
+                            let _running = crate::diagnostics::enter_running(
+                                subscriber.0,
+                                "RenderEffect",
+                                Some(defined_at),
+                            );
                             let old_value =
                                 mem::take(&mut *value.write().or_poisoned());
                             let new_value = owner.with_cleanup(|| {
@@ -259,11 +283,18 @@ where
                 dirty: false,
                 observer,
                 sources: SourceSet::new(),
+                queued: None,
             }));
             (owner, inner, rx)
         }
 
         let (owner, inner, mut rx) = prep();
+        #[cfg(feature = "introspection")]
+        crate::introspection::register(
+            crate::introspection::NodeKind::RenderEffect,
+            None,
+            &inner,
+        );
 
         let value = Arc::new(RwLock::new(None::<T>));
 
@@ -298,6 +329,12 @@ where
                         {
                             subscriber.clear_sources(&subscriber);
 
+                            #[cfg(debug_assertions)]
+                            let _running = crate::diagnostics::enter_running(
+                                subscriber.0,
+                                "RenderEffect",
+                                None,
+                            );
                             let old_value =
                                 mem::take(&mut *value.write().or_poisoned());
                             let new_value = owner.with_cleanup(|| {
@@ -332,6 +369,7 @@ where
     T: Send + Sync + 'static,
 {
     /// Creates a render effect that will run whether the `effects` feature is enabled or not.
+    #[track_caller]
     pub fn new_isomorphic(
         fun: impl FnMut(Option<T>) -> T + Send + Sync + 'static,
     ) -> Self {
@@ -340,6 +378,7 @@ where
         #[cfg(feature = "subsecond")]
         let fun = move |prev| fun.call((prev,));
 
+        #[track_caller]
         fn erased<T: Send + Sync + 'static>(
             mut fun: Box<dyn FnMut(Option<T>) -> T + Send + Sync + 'static>,
         ) -> RenderEffect<T> {
@@ -350,10 +389,27 @@ where
                 dirty: false,
                 observer,
                 sources: SourceSet::new(),
+                queued: None,
             }));
+            #[cfg(feature = "introspection")]
+            crate::introspection::register(
+                crate::introspection::NodeKind::RenderEffect,
+                Some(std::panic::Location::caller()),
+                &inner,
+            );
 
-            let initial_value = owner
-                .with(|| inner.to_any_subscriber().with_observer(|| fun(None)));
+            #[cfg(debug_assertions)]
+            let defined_at = std::panic::Location::caller();
+            let initial_value = owner.with(|| {
+                let subscriber = inner.to_any_subscriber();
+                #[cfg(debug_assertions)]
+                let _running = crate::diagnostics::enter_running(
+                    subscriber.0,
+                    "RenderEffect",
+                    Some(defined_at),
+                );
+                subscriber.with_observer(|| fun(None))
+            });
             *value.write().or_poisoned() = Some(initial_value);
 
             crate::spawn({
@@ -369,6 +425,12 @@ where
                         {
                             subscriber.clear_sources(&subscriber);
 
+                            #[cfg(debug_assertions)]
+                            let _running = crate::diagnostics::enter_running(
+                                subscriber.0,
+                                "RenderEffect",
+                                Some(defined_at),
+                            );
                             let old_value =
                                 mem::take(&mut *value.write().or_poisoned());
                             let new_value = owner.with_cleanup(|| {
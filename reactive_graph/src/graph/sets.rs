@@ -6,6 +6,8 @@
 //! a linear search is not significantly more expensive than a hash and lookup.
 
 use super::{AnySource, AnySubscriber, Source};
+#[cfg(feature = "introspection")]
+use crate::introspection::Registration;
 use indexmap::IndexSet;
 use rustc_hash::FxHasher;
 use std::{hash::BuildHasherDefault, mem};
@@ -13,11 +15,15 @@ use std::{hash::BuildHasherDefault, mem};
 type FxIndexSet<T> = IndexSet<T, BuildHasherDefault<FxHasher>>;
 
 #[derive(Default, Clone, Debug)]
-pub struct SourceSet(FxIndexSet<AnySource>);
+pub struct SourceSet(
+    FxIndexSet<AnySource>,
+    // keeps the subscriber registered for introspection until it is dropped
+    #[cfg(feature = "introspection")] Option<Registration>,
+);
 
 impl SourceSet {
     pub fn new() -> Self {
-        Self(Default::default())
+        Self::default()
     }
 
     pub fn insert(&mut self, source: AnySource) {
@@ -36,7 +42,19 @@ impl SourceSet {
         self.0.len()
     }
 
+    #[cfg(feature = "introspection")]
+    pub(crate) fn hold(&mut self, registration: Registration) {
+        self.1 = Some(registration);
+    }
+
+    #[cfg(feature = "introspection")]
+    pub(crate) fn ids(&self) -> Vec<usize> {
+        self.0.iter().map(|node| node.0).collect()
+    }
+
     pub fn clear_sources(&mut self, subscriber: &AnySubscriber) {
+        #[cfg(feature = "introspection")]
+        crate::introspection::rerun(subscriber.0);
         for source in self.take() {
             source.remove_subscriber(subscriber);
         }
@@ -61,11 +79,19 @@ impl<'a> IntoIterator for &'a SourceSet {
     }
 }
 #[derive(Debug, Default, Clone)]
-pub struct SubscriberSet(FxIndexSet<AnySubscriber>);
+pub struct SubscriberSet(
+    FxIndexSet<AnySubscriber>,
+    // keeps the source registered for introspection until it is dropped
+    #[cfg(feature = "introspection")] Option<Registration>,
+);
 
 impl SubscriberSet {
     pub fn new() -> Self {
-        Self(FxIndexSet::with_capacity_and_hasher(2, Default::default()))
+        Self(
+            FxIndexSet::with_capacity_and_hasher(2, Default::default()),
+            #[cfg(feature = "introspection")]
+            None,
+        )
     }
 
     pub fn subscribe(&mut self, subscriber: AnySubscriber) {
@@ -90,6 +116,16 @@ impl SubscriberSet {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    #[cfg(feature = "introspection")]
+    pub(crate) fn hold(&mut self, registration: Registration) {
+        self.1 = Some(registration);
+    }
+
+    #[cfg(feature = "introspection")]
+    pub(crate) fn ids(&self) -> Vec<usize> {
+        self.0.iter().map(|node| node.0).collect()
+    }
 }
 
 impl IntoIterator for SubscriberSet {
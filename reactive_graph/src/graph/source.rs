@@ -30,6 +30,16 @@ pub struct AnySource(
     pub(crate)  &'static Location<'static>,
 );
 
+impl AnySource {
+    /// Returns a unique identifier for this source, which can be used to identify it for
+    /// debugging purposes.
+    ///
+    /// Intended for debugging only; this is not guaranteed to be stable between runs.
+    pub fn debug_id(&self) -> usize {
+        self.0
+    }
+}
+
 impl DefinedAt for AnySource {
     fn defined_at(&self) -> Option<&'static Location<'static>> {
         #[cfg(any(debug_assertions, leptos_debuginfo))]
@@ -183,6 +183,8 @@ impl Subscriber for AnySubscriber {
 
 impl ReactiveNode for AnySubscriber {
     fn mark_dirty(&self) {
+        #[cfg(feature = "introspection")]
+        crate::introspection::marked_dirty(self.0);
         if let Some(inner) = self.1.upgrade() {
             inner.mark_dirty()
         }
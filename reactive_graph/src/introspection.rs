@@ -0,0 +1,402 @@
+//! An opt-in API to inspect the live reactive graph, for building devtools and leak detectors.
+//!
+//! When the `introspection` feature is enabled, every [`Owner`] and every
+//! signal, trigger, memo, async derived and effect registers itself here when it is created, and
+//! unregisters when it is dropped.
+//!
+//! [`snapshot`] returns the owners and nodes that are currently alive, and the edges between
+//! nodes, and [`snapshot_owner`] returns the part of it under one owner. Snapshots can be
+//! exported as [Graphviz DOT](GraphSnapshot::to_dot) or [JSON](GraphSnapshot::to_json), and
+//! [compared](GraphSnapshot::diff) with each other. [`events`] streams changes to the graph as
+//! they happen.
+//!
+//! Identifiers are the same values returned by [`Owner::debug_id`](crate::owner::Owner::debug_id)
+//! and used by [`AnySource`](crate::graph::AnySource) and
+//! [`AnySubscriber`](crate::graph::AnySubscriber). They are only unique among live nodes: the
+//! identifier of a disposed node can be reused by one created later.
+
+use crate::owner::Owner;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use or_poisoned::OrPoisoned;
+use rustc_hash::FxHashMap;
+use std::{
+    panic::Location,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex, Weak,
+    },
+};
+
+mod export;
+
+pub use export::*;
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(Default::default);
+
+// checked before locking the registry for frequent events, like a node being marked dirty
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+/// The kind of a reactive node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NodeKind {
+    /// A signal, like [`ArcRwSignal`](crate::signal::ArcRwSignal).
+    Signal,
+    /// A [`ArcTrigger`](crate::signal::ArcTrigger).
+    Trigger,
+    /// A memo, like [`ArcMemo`](crate::computed::ArcMemo).
+    Memo,
+    /// An async derived signal, like [`ArcAsyncDerived`](crate::computed::ArcAsyncDerived).
+    AsyncDerived,
+    /// An [`Effect`](crate::effect::Effect).
+    Effect,
+    /// A [`RenderEffect`](crate::effect::RenderEffect).
+    RenderEffect,
+    /// An [`ImmediateEffect`](crate::effect::ImmediateEffect).
+    ImmediateEffect,
+}
+
+/// A change to the reactive graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GraphEvent {
+    /// An owner was created.
+    OwnerCreated {
+        /// The owner's identifier.
+        id: usize,
+        /// The identifier of the owner's parent, if any.
+        parent: Option<usize>,
+    },
+    /// An owner was dropped.
+    OwnerDisposed {
+        /// The owner's identifier.
+        id: usize,
+    },
+    /// A reactive node was created.
+    NodeCreated {
+        /// The node's identifier.
+        id: usize,
+        /// The kind of node.
+        kind: NodeKind,
+        /// Where the node was created, if known.
+        defined_at: Option<&'static Location<'static>>,
+        /// The owner that was current when the node was created, if any.
+        owner: Option<usize>,
+    },
+    /// A reactive node was dropped.
+    NodeDisposed {
+        /// The node's identifier.
+        id: usize,
+    },
+    /// A reactive node was marked dirty, because it or one of its sources changed.
+    MarkedDirty {
+        /// The node's identifier.
+        id: usize,
+    },
+    /// A memo, async derived or effect is running, and tracking its sources anew.
+    Rerun {
+        /// The node's identifier.
+        id: usize,
+    },
+}
+
+/// An owner in a [`GraphSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OwnerInfo {
+    /// The owner's identifier.
+    pub id: usize,
+    /// The identifier of the owner's parent, if any.
+    pub parent: Option<usize>,
+    /// The identifiers of the owner's live children, in the order they were created.
+    pub children: Vec<usize>,
+    /// The identifiers of the live nodes created under this owner, in the order they were created.
+    pub nodes: Vec<usize>,
+}
+
+/// A reactive node in a [`GraphSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct NodeInfo {
+    /// The node's identifier.
+    pub id: usize,
+    /// The kind of node.
+    pub kind: NodeKind,
+    /// Where the node was created, if known.
+    pub defined_at: Option<&'static Location<'static>>,
+    /// The owner that was current when the node was created, if any.
+    pub owner: Option<usize>,
+    /// The identifiers of the nodes this node currently depends on.
+    pub sources: Vec<usize>,
+    /// The identifiers of the nodes that currently depend on this node.
+    pub subscribers: Vec<usize>,
+}
+
+/// The owners and reactive nodes that were alive when [`snapshot`] was called.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GraphSnapshot {
+    /// Every live owner, in the order they were created.
+    pub owners: Vec<OwnerInfo>,
+    /// Every live node, in the order they were created.
+    pub nodes: Vec<NodeInfo>,
+}
+
+impl GraphSnapshot {
+    /// Returns the owner with the given identifier, if it was alive.
+    pub fn owner(&self, id: usize) -> Option<&OwnerInfo> {
+        self.owners.iter().find(|owner| owner.id == id)
+    }
+
+    /// Returns the node with the given identifier, if it was alive.
+    pub fn node(&self, id: usize) -> Option<&NodeInfo> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+}
+
+/// Returns the owners and reactive nodes that are currently alive.
+pub fn snapshot() -> GraphSnapshot {
+    // the registry is released before reading the nodes, because dropping the last reference to
+    // a node while it is held would try to unregister it
+    let (mut owners, mut nodes) = {
+        let registry = REGISTRY.lock().or_poisoned();
+        let owners = registry
+            .owners
+            .iter()
+            .map(|(id, owner)| (owner.seq, *id, owner.parent))
+            .collect::<Vec<_>>();
+        let nodes = registry
+            .nodes
+            .iter()
+            .map(|(id, node)| (node.seq, *id, node.clone()))
+            .collect::<Vec<_>>();
+        (owners, nodes)
+    };
+    owners.sort_by_key(|(seq, ..)| *seq);
+    nodes.sort_by_key(|(seq, ..)| *seq);
+
+    let nodes = nodes
+        .into_iter()
+        .filter_map(|(_, id, record)| {
+            let node = record.node.upgrade()?;
+            Some(NodeInfo {
+                id,
+                kind: record.kind,
+                defined_at: record.defined_at,
+                owner: record.owner,
+                sources: node.sources(),
+                subscribers: node.subscribers(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let owners = owners
+        .iter()
+        .map(|(_, id, parent)| OwnerInfo {
+            id: *id,
+            parent: *parent,
+            children: owners
+                .iter()
+                .filter(|(.., parent)| *parent == Some(*id))
+                .map(|(_, child, _)| *child)
+                .collect(),
+            nodes: nodes
+                .iter()
+                .filter(|node| node.owner == Some(*id))
+                .map(|node| node.id)
+                .collect(),
+        })
+        .collect();
+
+    GraphSnapshot { owners, nodes }
+}
+
+/// Returns the part of the graph under the given owner.
+///
+/// This includes the owner and its descendants, the nodes created under any of them, and the
+/// nodes those depend on or are depended on by, even if they were created elsewhere.
+pub fn snapshot_owner(owner: &Owner) -> GraphSnapshot {
+    let GraphSnapshot { owners, nodes } = snapshot();
+
+    let mut scope = vec![owner.debug_id()];
+    let mut i = 0;
+    while let Some(id) = scope.get(i) {
+        if let Some(owner) = owners.iter().find(|owner| owner.id == *id) {
+            scope.extend(&owner.children);
+        }
+        i += 1;
+    }
+    let owned = nodes
+        .iter()
+        .filter(|node| node.owner.is_some_and(|owner| scope.contains(&owner)))
+        .flat_map(|node| {
+            [node.id]
+                .into_iter()
+                .chain(node.sources.iter().copied())
+                .chain(node.subscribers.iter().copied())
+        })
+        .collect::<Vec<_>>();
+
+    GraphSnapshot {
+        owners: owners
+            .into_iter()
+            .filter(|owner| scope.contains(&owner.id))
+            .collect(),
+        nodes: nodes
+            .into_iter()
+            .filter(|node| owned.contains(&node.id))
+            .collect(),
+    }
+}
+
+/// Returns a stream of every change to the reactive graph from now on.
+///
+/// Events are buffered until they are read, and stop being sent once the stream is dropped.
+pub fn events() -> UnboundedReceiver<GraphEvent> {
+    let (tx, rx) = mpsc::unbounded();
+    REGISTRY.lock().or_poisoned().listeners.push(tx);
+    LISTENING.store(true, Ordering::Relaxed);
+    rx
+}
+
+/// Reads the edges of a registered reactive node.
+pub(crate) trait Inspect {
+    /// The identifiers of the node's sources.
+    fn sources(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// The identifiers of the node's subscribers.
+    fn subscribers(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// Keeps the registration alive for as long as the node is.
+    fn hold(&self, registration: Registration);
+}
+
+/// Unregisters an owner or node when the last clone of it is dropped.
+#[derive(Debug, Clone)]
+pub(crate) struct Registration(#[allow(dead_code)] Arc<Registered>);
+
+#[derive(Debug)]
+enum Registered {
+    Owner(usize),
+    Node(usize),
+}
+
+impl Drop for Registered {
+    fn drop(&mut self) {
+        let mut registry = REGISTRY.lock().or_poisoned();
+        match *self {
+            Registered::Owner(id) => {
+                registry.owners.remove(&id);
+                registry.emit(GraphEvent::OwnerDisposed { id });
+            }
+            Registered::Node(id) => {
+                registry.nodes.remove(&id);
+                registry.emit(GraphEvent::NodeDisposed { id });
+            }
+        }
+    }
+}
+
+/// Registers an owner with the given parent.
+pub(crate) fn register_owner(id: usize, parent: Option<usize>) -> Registration {
+    let mut registry = REGISTRY.lock().or_poisoned();
+    let seq = registry.next_seq();
+    registry.owners.insert(id, OwnerRecord { seq, parent });
+    registry.emit(GraphEvent::OwnerCreated { id, parent });
+    Registration(Arc::new(Registered::Owner(id)))
+}
+
+/// Registers a reactive node under the current owner.
+pub(crate) fn register<T>(
+    kind: NodeKind,
+    defined_at: Option<&'static Location<'static>>,
+    node: &Arc<T>,
+) where
+    T: Inspect + Send + Sync + 'static,
+{
+    let id = Arc::as_ptr(node) as usize;
+    let owner = Owner::current().map(|owner| owner.debug_id());
+    {
+        let mut registry = REGISTRY.lock().or_poisoned();
+        let seq = registry.next_seq();
+        registry.nodes.insert(
+            id,
+            NodeRecord {
+                seq,
+                kind,
+                defined_at,
+                owner,
+                node: Arc::downgrade(node) as Weak<dyn Inspect + Send + Sync>,
+            },
+        );
+        registry.emit(GraphEvent::NodeCreated {
+            id,
+            kind,
+            defined_at,
+            owner,
+        });
+    }
+    node.hold(Registration(Arc::new(Registered::Node(id))));
+}
+
+/// Notifies listeners that the node was marked dirty.
+pub(crate) fn marked_dirty(id: usize) {
+    notify(id, GraphEvent::MarkedDirty { id });
+}
+
+/// Notifies listeners that the node is running again.
+pub(crate) fn rerun(id: usize) {
+    notify(id, GraphEvent::Rerun { id });
+}
+
+fn notify(id: usize, event: GraphEvent) {
+    if LISTENING.load(Ordering::Relaxed) {
+        let mut registry = REGISTRY.lock().or_poisoned();
+        if registry.nodes.contains_key(&id) {
+            registry.emit(event);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    seq: u64,
+    owners: FxHashMap<usize, OwnerRecord>,
+    nodes: FxHashMap<usize, NodeRecord>,
+    listeners: Vec<UnboundedSender<GraphEvent>>,
+}
+
+impl Registry {
+    fn next_seq(&mut self) -> u64 {
+        self.seq += 1;
+        self.seq
+    }
+
+    fn emit(&mut self, event: GraphEvent) {
+        if self.listeners.is_empty() {
+            return;
+        }
+        self.listeners
+            .retain(|listener| listener.unbounded_send(event.clone()).is_ok());
+        if self.listeners.is_empty() {
+            LISTENING.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+struct OwnerRecord {
+    seq: u64,
+    parent: Option<usize>,
+}
+
+#[derive(Clone)]
+struct NodeRecord {
+    seq: u64,
+    kind: NodeKind,
+    defined_at: Option<&'static Location<'static>>,
+    owner: Option<usize>,
+    node: Weak<dyn Inspect + Send + Sync>,
+}
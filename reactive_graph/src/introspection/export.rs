@@ -0,0 +1,282 @@
+use super::{GraphSnapshot, NodeInfo, NodeKind, OwnerInfo};
+use std::fmt::{self, Display, Write};
+
+/// The differences between two [`GraphSnapshot`]s, as returned by [`GraphSnapshot::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GraphDiff {
+    /// The identifiers of nodes that are only in the later snapshot.
+    pub added_nodes: Vec<usize>,
+    /// The identifiers of nodes that are only in the earlier snapshot.
+    pub removed_nodes: Vec<usize>,
+    /// The `(source, subscriber)` edges that are only in the later snapshot.
+    pub added_edges: Vec<(usize, usize)>,
+    /// The `(source, subscriber)` edges that are only in the earlier snapshot.
+    pub removed_edges: Vec<(usize, usize)>,
+}
+
+impl GraphDiff {
+    /// Whether the two snapshots had the same nodes and edges.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+impl Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            NodeKind::Signal => "Signal",
+            NodeKind::Trigger => "Trigger",
+            NodeKind::Memo => "Memo",
+            NodeKind::AsyncDerived => "AsyncDerived",
+            NodeKind::Effect => "Effect",
+            NodeKind::RenderEffect => "RenderEffect",
+            NodeKind::ImmediateEffect => "ImmediateEffect",
+        })
+    }
+}
+
+impl NodeInfo {
+    /// A short, human-readable name for the node: its kind, followed by the file name and line
+    /// where it was created, if known.
+    pub fn label(&self) -> String {
+        match self.defined_at {
+            Some(location) => {
+                let file = location
+                    .file()
+                    .rsplit(['/', '\\'])
+                    .next()
+                    .unwrap_or_default();
+                format!("{} ({file}:{})", self.kind, location.line())
+            }
+            None => self.kind.to_string(),
+        }
+    }
+}
+
+impl GraphSnapshot {
+    /// Returns every `(source, subscriber)` edge between nodes in the snapshot.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = Vec::new();
+        for node in &self.nodes {
+            let from_sources =
+                node.sources.iter().map(|source| (*source, node.id));
+            let from_subscribers = node
+                .subscribers
+                .iter()
+                .map(|subscriber| (node.id, *subscriber));
+            for edge in from_sources.chain(from_subscribers) {
+                let in_snapshot =
+                    self.node(edge.0).is_some() && self.node(edge.1).is_some();
+                if in_snapshot && !edges.contains(&edge) {
+                    edges.push(edge);
+                }
+            }
+        }
+        edges
+    }
+
+    /// Compares this snapshot with one taken later, returning the nodes and edges that were added
+    /// or removed in between.
+    ///
+    /// This can be used in a test to find out why a memo or effect ran: a changed edge shows that
+    /// it started or stopped depending on a source.
+    pub fn diff(&self, later: &GraphSnapshot) -> GraphDiff {
+        let (before, after) = (self.edges(), later.edges());
+        GraphDiff {
+            added_nodes: later
+                .nodes
+                .iter()
+                .filter(|node| self.node(node.id).is_none())
+                .map(|node| node.id)
+                .collect(),
+            removed_nodes: self
+                .nodes
+                .iter()
+                .filter(|node| later.node(node.id).is_none())
+                .map(|node| node.id)
+                .collect(),
+            added_edges: after
+                .iter()
+                .filter(|edge| !before.contains(edge))
+                .copied()
+                .collect(),
+            removed_edges: before
+                .iter()
+                .filter(|edge| !after.contains(edge))
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Renders the snapshot as a [Graphviz](https://graphviz.org/) DOT graph.
+    ///
+    /// Each owner is drawn as a cluster around the nodes created under it and its children, and
+    /// each edge points from a source to one of its subscribers.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        let is_root = |owner: &&OwnerInfo| {
+            owner
+                .parent
+                .is_none_or(|parent| self.owner(parent).is_none())
+        };
+        for owner in self.owners.iter().filter(is_root) {
+            self.write_dot_owner(&mut dot, owner, 1);
+        }
+        for node in &self.nodes {
+            let owned = node.owner.is_some_and(|id| self.owner(id).is_some());
+            if !owned {
+                write_dot_node(&mut dot, node, 1);
+            }
+        }
+        for (source, subscriber) in self.edges() {
+            _ = writeln!(dot, "    n{source} -> n{subscriber};");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot_owner(
+        &self,
+        dot: &mut String,
+        owner: &OwnerInfo,
+        depth: usize,
+    ) {
+        let indent = "    ".repeat(depth);
+        _ = writeln!(dot, "{indent}subgraph cluster_{} {{", owner.id);
+        _ = writeln!(dot, "{indent}    label=\"Owner {}\";", owner.id);
+        for node in owner.nodes.iter().filter_map(|id| self.node(*id)) {
+            write_dot_node(dot, node, depth + 1);
+        }
+        for child in owner.children.iter().filter_map(|id| self.owner(*id)) {
+            self.write_dot_owner(dot, child, depth + 1);
+        }
+        _ = writeln!(dot, "{indent}}}");
+    }
+
+    /// Renders the snapshot as JSON.
+    ///
+    /// The output is an object with three arrays: `owners`, each with an `id`, `parent`,
+    /// `children` and `nodes`; `nodes`, each with an `id`, `type`, `label`, `location` and
+    /// `owner`; and `edges`, each with a `source` and a `subscriber`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"owners\":[");
+        for (i, owner) in self.owners.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            _ = write!(
+                json,
+                "{{\"id\":{},\"parent\":{},\"children\":{},\"nodes\":{}}}",
+                owner.id,
+                JsonOption(owner.parent),
+                JsonList(&owner.children),
+                JsonList(&owner.nodes)
+            );
+        }
+        json.push_str("],\"nodes\":[");
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            _ = write!(
+                json,
+                "{{\"id\":{},\"type\":\"{}\",\"label\":{},\"location\":{},\
+                 \"owner\":{}}}",
+                node.id,
+                node.kind,
+                JsonString(&node.label()),
+                match node.defined_at {
+                    Some(location) =>
+                        JsonString(&location.to_string()).to_string(),
+                    None => "null".to_string(),
+                },
+                JsonOption(node.owner)
+            );
+        }
+        json.push_str("],\"edges\":[");
+        for (i, (source, subscriber)) in self.edges().into_iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            _ = write!(
+                json,
+                "{{\"source\":{source},\"subscriber\":{subscriber}}}"
+            );
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+fn write_dot_node(dot: &mut String, node: &NodeInfo, depth: usize) {
+    let shape = match node.kind {
+        NodeKind::Signal | NodeKind::Trigger => "ellipse",
+        NodeKind::Memo | NodeKind::AsyncDerived => "box",
+        _ => "hexagon",
+    };
+    let label = match node.defined_at {
+        Some(location) => format!("{}\n{location}", node.label()),
+        None => node.label(),
+    };
+    _ = writeln!(
+        dot,
+        "{}n{} [label=\"{}\", shape={shape}];",
+        "    ".repeat(depth),
+        node.id,
+        escape_dot(&label),
+    );
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+struct JsonString<'a>(&'a str);
+
+impl Display for JsonString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+struct JsonOption(Option<usize>);
+
+impl Display for JsonOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{value}"),
+            None => f.write_str("null"),
+        }
+    }
+}
+
+struct JsonList<'a>(&'a [usize]);
+
+impl Display for JsonList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('[')?;
+        for (i, value) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            write!(f, "{value}")?;
+        }
+        f.write_char(']')
+    }
+}
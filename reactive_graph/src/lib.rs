@@ -80,6 +80,8 @@ pub mod computed;
 pub mod diagnostics;
 pub mod effect;
 pub mod graph;
+#[cfg(feature = "introspection")]
+pub mod introspection;
 pub mod owner;
 pub mod send_wrapper_ext;
 #[cfg(feature = "serde")]
@@ -167,10 +167,14 @@ impl Owner {
                     .map(|parent| parent.read().or_poisoned().arena.clone())
                     .unwrap_or_default(),
                 paused: false,
+                #[cfg(feature = "introspection")]
+                registration: None,
             })),
             #[cfg(feature = "hydration")]
             shared_context,
         };
+        #[cfg(feature = "introspection")]
+        this.introspect(parent.as_ref().map(|parent| parent.as_ptr() as usize));
         if let Some(parent) = parent.and_then(|n| n.upgrade()) {
             parent
                 .write()
@@ -201,10 +205,14 @@ impl Owner {
                 #[cfg(feature = "sandboxed-arenas")]
                 arena: Default::default(),
                 paused: false,
+                #[cfg(feature = "introspection")]
+                registration: None,
             })),
             #[cfg(feature = "hydration")]
             shared_context,
         };
+        #[cfg(feature = "introspection")]
+        this.introspect(None);
         this.set();
         this
     }
@@ -245,14 +253,26 @@ impl Owner {
                 #[cfg(feature = "sandboxed-arenas")]
                 arena,
                 paused,
+                #[cfg(feature = "introspection")]
+                registration: None,
             })),
             #[cfg(feature = "hydration")]
             shared_context: self.shared_context.clone(),
         };
         inner.children.push(Arc::downgrade(&child.inner));
+        drop(inner);
+        #[cfg(feature = "introspection")]
+        child.introspect(Some(self.debug_id()));
         child
     }
 
+    #[cfg(feature = "introspection")]
+    fn introspect(&self, parent: Option<usize>) {
+        let registration =
+            crate::introspection::register_owner(self.debug_id(), parent);
+        self.inner.write().or_poisoned().registration = Some(registration);
+    }
+
     /// Sets this as the current `Owner`.
     pub fn set(&self) {
         OWNER.with_borrow_mut(|owner| *owner = Some(self.downgrade()));
@@ -433,7 +453,9 @@ impl Owner {
     /// If this owner is the owner for an [`Effect`](crate::effect::Effect) or [`RenderEffect`](crate::effect::RenderEffect), this effect will not run until [`Owner::resume`] is called. All children of this effects are also paused.
     ///
     /// Any notifications will be ignored; effects that are notified will paused will not run when
-    /// resumed, until they are notified again by a source after being resumed.
+    /// resumed, until they are notified again by a source after being resumed. The exception is
+    /// effects created with [`Effect::new_with_priority`](crate::effect::Effect::new_with_priority):
+    /// a queued run of one of these effects is deferred until its owner is resumed.
     pub fn pause(&self) {
         let mut stack = Vec::with_capacity(16);
         stack.push(Arc::downgrade(&self.inner));
@@ -456,7 +478,12 @@ impl Owner {
     /// All children will also be resumed.
     ///
     /// This will *not* cause side effects that were notified while paused to run, until they are
-    /// notified again by a source after being resumed.
+    /// notified again by a source after being resumed, except for queued effects created with
+    /// [`Effect::new_with_priority`](crate::effect::Effect::new_with_priority), which are queued
+    /// again.
+    ///
+    /// Every deferred effect on the current thread is queued again, not only those owned by this
+    /// owner: effects whose owners are still paused are deferred again when the queue is flushed.
     pub fn resume(&self) {
         let mut stack = Vec::with_capacity(16);
         stack.push(Arc::downgrade(&self.inner));
@@ -467,6 +494,7 @@ impl Owner {
                 stack.extend(curr.children.iter().map(Weak::clone));
             }
         }
+        crate::effect::resume_deferred();
     }
 }
 
@@ -495,6 +523,9 @@ pub(crate) struct OwnerInner {
     #[cfg(feature = "sandboxed-arenas")]
     arena: Arc<RwLock<ArenaMap>>,
     paused: bool,
+    // unregisters the owner from introspection when it is dropped
+    #[cfg(feature = "introspection")]
+    registration: Option<crate::introspection::Registration>,
 }
 
 impl Debug for OwnerInner {
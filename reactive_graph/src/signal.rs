@@ -1,26 +1,46 @@
 //! Reactive primitives for root values that can be changed, notifying other nodes in the reactive
 //! graph.
 
+mod arc_history;
+#[cfg(feature = "serde")]
+mod arc_persisted;
 mod arc_read;
 mod arc_rw;
+mod arc_stream;
 mod arc_trigger;
 mod arc_write;
 pub mod guards;
+mod history;
 mod mapped;
+#[cfg(feature = "serde")]
+mod persisted;
+#[cfg(feature = "serde")]
+mod persistence;
 mod read;
 mod rw;
+mod stream;
 mod subscriber_traits;
 mod trigger;
 mod write;
 
 use crate::owner::LocalStorage;
+pub use arc_history::*;
+#[cfg(feature = "serde")]
+pub use arc_persisted::*;
 pub use arc_read::*;
 pub use arc_rw::*;
+pub use arc_stream::*;
 pub use arc_trigger::*;
 pub use arc_write::*;
+pub use history::*;
 pub use mapped::*;
+#[cfg(feature = "serde")]
+pub use persisted::*;
+#[cfg(feature = "serde")]
+pub use persistence::*;
 pub use read::*;
 pub use rw::*;
+pub use stream::*;
 pub use trigger::*;
 pub use write::*;
 
@@ -0,0 +1,402 @@
+use super::{
+    guards::{Plain, ReadGuard, UntrackedWriteGuard, WriteGuard},
+    subscriber_traits::AsSubscriberSet,
+    ArcRwSignal, ArcTrigger,
+};
+use crate::{
+    effect::current_batch,
+    graph::SubscriberSet,
+    traits::{
+        DefinedAt, IsDisposed, Notify, ReadUntracked, Track, UntrackableGuard,
+        Write,
+    },
+};
+use or_poisoned::OrPoisoned;
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    fmt::{Debug, Formatter, Result},
+    hash::Hash,
+    ops::Deref,
+    panic::Location,
+    sync::{Arc, RwLock},
+};
+
+/// The number of entries an [`ArcHistorySignal`] keeps by default.
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100;
+
+/// A reference-counted signal that remembers its previous values, so that changes to it can be
+/// undone and redone.
+///
+/// Each time the signal is written to, its value from before the write is added to its history,
+/// up to a maximum number of entries, after which the oldest entries are forgotten. Writing to the
+/// signal also forgets any changes that have been undone, so they can no longer be redone.
+///
+/// Several writes can be recorded as a single entry by making them inside
+/// [`group`](ArcHistorySignal::group), or inside a [`batch`](crate::effect::batch). Untracked
+/// writes, such as [`update_untracked`](crate::traits::UpdateUntracked), and writes that do not
+/// notify, such as a [`maybe_update`](crate::traits::Update::maybe_update) that returns `false`,
+/// are not recorded.
+///
+/// This is a reference-counted signal, which is `Clone` but not `Copy`.
+/// For arena-allocated `Copy` signals, use [`HistorySignal`](super::HistorySignal).
+///
+/// ## Examples
+///
+/// ```
+/// # use reactive_graph::prelude::*;
+/// # use reactive_graph::signal::*; let owner = reactive_graph::owner::Owner::new(); owner.set();
+/// let text = ArcHistorySignal::new(String::new());
+///
+/// text.set("Hello".to_string());
+/// text.update(|text| text.push_str(", world"));
+/// assert_eq!(text.get(), "Hello, world");
+/// assert!(text.can_undo());
+///
+/// text.undo();
+/// assert_eq!(text.get(), "Hello");
+/// text.undo();
+/// assert_eq!(text.get(), "");
+/// assert!(!text.can_undo());
+///
+/// text.redo();
+/// assert_eq!(text.get(), "Hello");
+///
+/// // several writes can be undone at once
+/// text.group(|| {
+///     text.set("Goodbye".to_string());
+///     text.update(|text| text.push('!'));
+/// });
+/// text.undo();
+/// assert_eq!(text.get(), "Hello");
+/// ```
+pub struct ArcHistorySignal<T> {
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    pub(crate) defined_at: &'static Location<'static>,
+    pub(crate) value: ArcRwSignal<T>,
+    history: Arc<RwLock<History<T>>>,
+    // notified when it becomes possible or impossible to undo or redo
+    changed: ArcTrigger,
+}
+
+struct History<T> {
+    undo: VecDeque<T>,
+    redo: Vec<T>,
+    capacity: usize,
+    // the number of `group` calls that are running
+    grouping: usize,
+    // whether the current group or batch has been recorded yet
+    recorded_group: bool,
+    recorded_batch: Option<u64>,
+}
+
+impl<T> Clone for ArcHistorySignal<T> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: self.defined_at,
+            value: self.value.clone(),
+            history: Arc::clone(&self.history),
+            changed: self.changed.clone(),
+        }
+    }
+}
+
+impl<T> Debug for ArcHistorySignal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("ArcHistorySignal")
+            .field("type", &std::any::type_name::<T>())
+            .field("value", &Arc::as_ptr(&self.value.value))
+            .finish()
+    }
+}
+
+impl<T> PartialEq for ArcHistorySignal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for ArcHistorySignal<T> {}
+
+impl<T> Hash for ArcHistorySignal<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T> Default for ArcHistorySignal<T>
+where
+    T: Default,
+{
+    #[track_caller]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> ArcHistorySignal<T> {
+    /// Creates a new signal that keeps up to [`DEFAULT_HISTORY_CAPACITY`] entries of history.
+    #[track_caller]
+    pub fn new(value: T) -> Self {
+        Self::with_capacity(value, DEFAULT_HISTORY_CAPACITY)
+    }
+
+    /// Creates a new signal that keeps up to `capacity` entries of history.
+    #[track_caller]
+    pub fn with_capacity(value: T, capacity: usize) -> Self {
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+            value: ArcRwSignal::new(value),
+            history: Arc::new(RwLock::new(History {
+                undo: VecDeque::new(),
+                redo: Vec::new(),
+                capacity,
+                grouping: 0,
+                recorded_group: false,
+                recorded_batch: None,
+            })),
+            changed: ArcTrigger::new(),
+        }
+    }
+
+    /// Whether there is a change that can be undone.
+    ///
+    /// This is a reactive read: reading it in an effect or memo subscribes to changes in whether
+    /// it is possible to undo.
+    pub fn can_undo(&self) -> bool {
+        self.changed.track();
+        !self.history.read().or_poisoned().undo.is_empty()
+    }
+
+    /// Whether there is an undone change that can be redone.
+    ///
+    /// This is a reactive read: reading it in an effect or memo subscribes to changes in whether
+    /// it is possible to redo.
+    pub fn can_redo(&self) -> bool {
+        self.changed.track();
+        !self.history.read().or_poisoned().redo.is_empty()
+    }
+
+    /// Restores the value from before the last change, if there was one.
+    ///
+    /// Returns `true` if a change was undone.
+    pub fn undo(&self) -> bool {
+        self.step(
+            |history| history.undo.pop_back(),
+            |history, value| history.redo.push(value),
+        )
+    }
+
+    /// Restores the value from before the last [`undo`](ArcHistorySignal::undo), if there was
+    /// one and the signal has not been written to since.
+    ///
+    /// Returns `true` if a change was redone.
+    pub fn redo(&self) -> bool {
+        self.step(
+            |history| history.redo.pop(),
+            |history, value| history.undo.push_back(value),
+        )
+    }
+
+    /// Forgets all of the changes that could be undone or redone.
+    pub fn clear_history(&self) {
+        let mut history = self.history.write().or_poisoned();
+        history.undo.clear();
+        history.redo.clear();
+        drop(history);
+        self.changed.notify();
+    }
+
+    /// Runs `fun`, recording every write to the signal that it makes as a single change, which can
+    /// be undone all at once.
+    pub fn group<U>(&self, fun: impl FnOnce() -> U) -> U {
+        struct EndGroup<'a, T>(&'a RwLock<History<T>>);
+
+        impl<T> Drop for EndGroup<'_, T> {
+            fn drop(&mut self) {
+                let mut history = self.0.write().or_poisoned();
+                history.grouping -= 1;
+                if history.grouping == 0 {
+                    history.recorded_group = false;
+                }
+            }
+        }
+
+        self.history.write().or_poisoned().grouping += 1;
+        let _end = EndGroup(&self.history);
+        fun()
+    }
+
+    /// Swaps the current value with one taken from the history, and keeps the current value in
+    /// the history on the other side.
+    fn step(
+        &self,
+        take: impl FnOnce(&mut History<T>) -> Option<T>,
+        keep: impl FnOnce(&mut History<T>, T),
+    ) -> bool {
+        let mut history = self.history.write().or_poisoned();
+        let Some(value) = take(&mut history) else {
+            return false;
+        };
+        let current = std::mem::replace(
+            &mut *self.value.value.write().or_poisoned(),
+            value,
+        );
+        keep(&mut history, current);
+        // a later write starts a new entry, even inside the same group or batch
+        history.recorded_group = false;
+        history.recorded_batch = None;
+        drop(history);
+        self.value.notify();
+        self.changed.notify();
+        true
+    }
+}
+
+impl<T: Clone> ArcHistorySignal<T> {
+    /// Wraps a guard for writing to the value, so that the value from before the write is added
+    /// to the history once the guard is dropped, unless the write has been untracked.
+    pub(crate) fn record_write<S, G>(
+        &self,
+        signal: S,
+        guard: G,
+    ) -> WriteGuard<RecordWrite<S, T>, G>
+    where
+        S: Notify,
+        G: Deref<Target = T>,
+    {
+        let previous = Cell::new(Some((*guard).clone()));
+        WriteGuard::new(
+            RecordWrite {
+                signal,
+                history: self.clone(),
+                previous,
+            },
+            guard,
+        )
+    }
+}
+
+impl<T> ArcHistorySignal<T> {
+    /// Adds the value from before a write to the history, unless a value has already been added
+    /// for the current group or batch.
+    fn record(&self, previous: T) {
+        let mut history = self.history.write().or_poisoned();
+        if history.grouping > 0 {
+            if history.recorded_group {
+                return;
+            }
+            history.recorded_group = true;
+        }
+        if let Some(batch) = current_batch() {
+            if history.recorded_batch == Some(batch) {
+                return;
+            }
+            history.recorded_batch = Some(batch);
+        }
+
+        let could_not_undo = history.undo.is_empty();
+        let could_redo = !history.redo.is_empty();
+        if history.capacity > 0 {
+            if history.undo.len() == history.capacity {
+                history.undo.pop_front();
+            }
+            history.undo.push_back(previous);
+        }
+        history.redo.clear();
+        let changed = could_not_undo != history.undo.is_empty() || could_redo;
+        drop(history);
+        if changed {
+            self.changed.notify();
+        }
+    }
+}
+
+/// Notifies a history signal after a write, adding the value from before the write to its
+/// history first.
+pub(crate) struct RecordWrite<S, T> {
+    signal: S,
+    history: ArcHistorySignal<T>,
+    previous: Cell<Option<T>>,
+}
+
+impl<S, T> Notify for RecordWrite<S, T>
+where
+    S: Notify,
+{
+    fn notify(&self) {
+        if let Some(previous) = self.previous.take() {
+            self.history.record(previous);
+        }
+        self.signal.notify();
+    }
+}
+
+impl<T> DefinedAt for ArcHistorySignal<T> {
+    #[inline(always)]
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T> IsDisposed for ArcHistorySignal<T> {
+    #[inline(always)]
+    fn is_disposed(&self) -> bool {
+        false
+    }
+}
+
+impl<T> AsSubscriberSet for ArcHistorySignal<T> {
+    type Output = Arc<RwLock<SubscriberSet>>;
+
+    #[inline(always)]
+    fn as_subscriber_set(&self) -> Option<Self::Output> {
+        self.value.as_subscriber_set()
+    }
+}
+
+impl<T: 'static> ReadUntracked for ArcHistorySignal<T> {
+    type Value = ReadGuard<T, Plain<T>>;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        self.value.try_read_untracked()
+    }
+}
+
+impl<T> Notify for ArcHistorySignal<T> {
+    fn notify(&self) {
+        self.value.notify();
+    }
+}
+
+impl<T: Clone + 'static> Write for ArcHistorySignal<T> {
+    type Value = T;
+
+    fn try_write(&self) -> Option<impl UntrackableGuard<Target = Self::Value>> {
+        let guard = self.value.value.write().ok()?;
+        Some(self.record_write(self.clone(), guard))
+    }
+
+    #[allow(refining_impl_trait)]
+    fn try_write_untracked(&self) -> Option<UntrackedWriteGuard<Self::Value>> {
+        self.value.try_write_untracked()
+    }
+}
+
+impl<T> From<ArcHistorySignal<T>> for ArcRwSignal<T> {
+    /// Returns the signal that holds the current value. Writes to it are not recorded in the
+    /// history.
+    fn from(value: ArcHistorySignal<T>) -> Self {
+        value.value
+    }
+}
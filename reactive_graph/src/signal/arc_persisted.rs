@@ -0,0 +1,284 @@
+use super::{
+    guards::{Plain, ReadGuard, UntrackedWriteGuard},
+    subscriber_traits::AsSubscriberSet,
+    ArcRwSignal, PersistenceBackend, PersistenceError,
+};
+use crate::{
+    computed::{arc_debounced, follow},
+    graph::SubscriberSet,
+    log_warning,
+    owner::Owner,
+    traits::{
+        DefinedAt, IsDisposed, Notify, ReadUntracked, UntrackableGuard, Write,
+    },
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    panic::Location,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+
+/// How long a persisted signal waits for its value to stop changing before saving it, by default.
+pub const DEFAULT_PERSIST_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// A reference-counted signal that loads its initial value from a [`PersistenceBackend`], and
+/// saves its value back whenever it changes.
+///
+/// This can be used to keep state, such as the size of a pane or the filters applied to a list,
+/// when the program is restarted. The value is stored as JSON under a key. If nothing has been
+/// stored under the key, or the stored value cannot be loaded, the signal starts with a default
+/// value.
+///
+/// Changes are saved by a debounced effect, once the value has stopped changing for a short time,
+/// so that quickly repeated changes are only saved once. As with
+/// [`arc_debounced`], this depends on the `effects` feature and on the
+/// [`Timer`](crate::computed::Timer). A change that has not been saved yet is saved when the
+/// [`Owner`] the signal was created in is cleaned up, or when
+/// [`flush`](ArcPersistedSignal::flush) is called. Errors while saving are logged.
+///
+/// This is a reference-counted signal, which is `Clone` but not `Copy`.
+/// For arena-allocated `Copy` signals, use [`PersistedSignal`](super::PersistedSignal).
+///
+/// ## Examples
+///
+/// ```
+/// # use reactive_graph::prelude::*;
+/// # use any_spawner::{Executor, TestExecutor};
+/// # _ = Executor::init_custom_executor(TestExecutor);
+/// # use reactive_graph::signal::*; let owner = reactive_graph::owner::Owner::new(); owner.set();
+/// let backend = MemoryBackend::new();
+///
+/// let width = ArcPersistedSignal::new(backend.clone(), "sidebar-width", 30);
+/// assert_eq!(width.get(), 30);
+/// width.set(42);
+/// width.flush().unwrap();
+/// assert_eq!(backend.get("sidebar-width").as_deref(), Some("42"));
+///
+/// // the next time the signal is created, it starts with the saved value
+/// let width = ArcPersistedSignal::new(backend, "sidebar-width", 30);
+/// assert_eq!(width.get(), 42);
+/// ```
+pub struct ArcPersistedSignal<T> {
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    pub(crate) defined_at: &'static Location<'static>,
+    pub(crate) value: ArcRwSignal<T>,
+    persistence: Arc<Persistence>,
+}
+
+struct Persistence {
+    key: String,
+    backend: Box<dyn PersistenceBackend>,
+    // whether the value has been written to since it was last saved
+    pending: AtomicBool,
+}
+
+impl Persistence {
+    fn save<T: Serialize>(&self, value: &T) -> Result<(), PersistenceError> {
+        self.pending.store(false, Ordering::Relaxed);
+        let json = serde_json::to_string(value)?;
+        self.backend.store(&self.key, &json)
+    }
+
+    fn save_if_pending<T: Serialize>(&self, value: &T) {
+        if self.pending.load(Ordering::Relaxed) {
+            if let Err(e) = self.save(value) {
+                log_warning(format_args!(
+                    "Could not save the persisted value {:?}: {e}",
+                    self.key
+                ));
+            }
+        }
+    }
+}
+
+impl<T> Clone for ArcPersistedSignal<T> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: self.defined_at,
+            value: self.value.clone(),
+            persistence: Arc::clone(&self.persistence),
+        }
+    }
+}
+
+impl<T> Debug for ArcPersistedSignal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArcPersistedSignal")
+            .field("type", &std::any::type_name::<T>())
+            .field("key", &self.persistence.key)
+            .field("value", &Arc::as_ptr(&self.value.value))
+            .finish()
+    }
+}
+
+impl<T> PartialEq for ArcPersistedSignal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for ArcPersistedSignal<T> {}
+
+impl<T> Hash for ArcPersistedSignal<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T> ArcPersistedSignal<T>
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    /// Creates a signal that is stored under `key` in `backend`, starting with `default` if
+    /// nothing has been stored under it yet. Changes are saved once the value has stopped
+    /// changing for [`DEFAULT_PERSIST_DEBOUNCE`].
+    #[track_caller]
+    pub fn new(
+        backend: impl PersistenceBackend,
+        key: impl Into<String>,
+        default: T,
+    ) -> Self {
+        Self::new_with_debounce(backend, key, default, DEFAULT_PERSIST_DEBOUNCE)
+    }
+
+    /// Creates a signal that is stored under `key` in `backend`, starting with `default` if
+    /// nothing has been stored under it yet. Changes are saved once the value has stopped
+    /// changing for `debounce`.
+    #[track_caller]
+    pub fn new_with_debounce(
+        backend: impl PersistenceBackend,
+        key: impl Into<String>,
+        default: T,
+        debounce: Duration,
+    ) -> Self {
+        let persistence = Arc::new(Persistence {
+            key: key.into(),
+            backend: Box::new(backend),
+            pending: AtomicBool::new(false),
+        });
+        let loaded =
+            persistence.backend.load(&persistence.key).and_then(|json| {
+                json.map(|json| serde_json::from_str(&json))
+                    .transpose()
+                    .map_err(PersistenceError::from)
+            });
+        let initial = match loaded {
+            Ok(Some(value)) => value,
+            Ok(None) => default,
+            Err(e) => {
+                log_warning(format_args!(
+                    "Could not load the persisted value {:?}: {e}",
+                    persistence.key
+                ));
+                default
+            }
+        };
+        let value = ArcRwSignal::new(initial);
+
+        follow(arc_debounced(value.clone(), debounce), {
+            let persistence = Arc::clone(&persistence);
+            move |value, _| persistence.save_if_pending(&value)
+        });
+        Owner::on_cleanup({
+            let persistence = Arc::clone(&persistence);
+            let value = value.clone();
+            move || persistence.save_if_pending(&*value.read_untracked())
+        });
+
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+            value,
+            persistence,
+        }
+    }
+}
+
+impl<T> ArcPersistedSignal<T> {
+    /// The key the value is stored under.
+    pub fn key(&self) -> &str {
+        &self.persistence.key
+    }
+
+    /// Saves the current value now, without waiting for it to stop changing.
+    ///
+    /// This can be used to make sure the latest change is saved before the program exits.
+    pub fn flush(&self) -> Result<(), PersistenceError>
+    where
+        T: Serialize + 'static,
+    {
+        self.persistence.save(&*self.value.read_untracked())
+    }
+
+    /// Marks the value as changed since it was last saved.
+    pub(crate) fn mark_pending(&self) {
+        self.persistence.pending.store(true, Ordering::Relaxed);
+    }
+}
+
+impl<T> DefinedAt for ArcPersistedSignal<T> {
+    #[inline(always)]
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T> IsDisposed for ArcPersistedSignal<T> {
+    #[inline(always)]
+    fn is_disposed(&self) -> bool {
+        false
+    }
+}
+
+impl<T> AsSubscriberSet for ArcPersistedSignal<T> {
+    type Output = Arc<RwLock<SubscriberSet>>;
+
+    #[inline(always)]
+    fn as_subscriber_set(&self) -> Option<Self::Output> {
+        self.value.as_subscriber_set()
+    }
+}
+
+impl<T: 'static> ReadUntracked for ArcPersistedSignal<T> {
+    type Value = ReadGuard<T, Plain<T>>;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        self.value.try_read_untracked()
+    }
+}
+
+impl<T> Notify for ArcPersistedSignal<T> {
+    fn notify(&self) {
+        self.value.notify();
+    }
+}
+
+impl<T: 'static> Write for ArcPersistedSignal<T> {
+    type Value = T;
+
+    fn try_write(&self) -> Option<impl UntrackableGuard<Target = Self::Value>> {
+        self.mark_pending();
+        self.value.try_write()
+    }
+
+    #[allow(refining_impl_trait)]
+    fn try_write_untracked(&self) -> Option<UntrackedWriteGuard<Self::Value>> {
+        self.mark_pending();
+        self.value.try_write_untracked()
+    }
+}
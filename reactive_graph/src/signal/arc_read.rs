@@ -84,11 +84,18 @@ impl<T> Debug for ArcReadSignal<T> {
 impl<T: Default> Default for ArcReadSignal<T> {
     #[track_caller]
     fn default() -> Self {
+        let inner = Arc::new(RwLock::new(SubscriberSet::new()));
+        #[cfg(feature = "introspection")]
+        crate::introspection::register(
+            crate::introspection::NodeKind::Signal,
+            Some(Location::caller()),
+            &inner,
+        );
         Self {
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             defined_at: Location::caller(),
             value: Arc::new(RwLock::new(T::default())),
-            inner: Arc::new(RwLock::new(SubscriberSet::new())),
+            inner,
         }
     }
 }
@@ -153,11 +153,18 @@ impl<T> ArcRwSignal<T> {
     )]
     #[track_caller]
     pub fn new(value: T) -> Self {
+        let inner = Arc::new(RwLock::new(SubscriberSet::new()));
+        #[cfg(feature = "introspection")]
+        crate::introspection::register(
+            crate::introspection::NodeKind::Signal,
+            Some(Location::caller()),
+            &inner,
+        );
         Self {
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             defined_at: Location::caller(),
             value: Arc::new(RwLock::new(value)),
-            inner: Arc::new(RwLock::new(SubscriberSet::new())),
+            inner,
         }
     }
 
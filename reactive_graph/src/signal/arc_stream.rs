@@ -0,0 +1,339 @@
+use super::{
+    guards::{Plain, ReadGuard},
+    subscriber_traits::AsSubscriberSet,
+    ArcRwSignal,
+};
+use crate::{
+    computed::suspense::{SuspenseContext, TaskHandle},
+    graph::SubscriberSet,
+    owner::{use_context, Owner},
+    traits::{
+        DefinedAt, Get, GetUntracked, IsDisposed, ReadUntracked, Set,
+        WithUntracked,
+    },
+};
+use futures::{
+    channel::oneshot,
+    future::{AbortHandle, Abortable, Shared},
+    FutureExt, Stream, StreamExt,
+};
+use or_poisoned::OrPoisoned;
+use std::{
+    error::Error,
+    fmt::{self, Debug, Formatter},
+    future::{Future, IntoFuture},
+    hash::Hash,
+    panic::Location,
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// An error yielded by the stream of a [fallible](ArcStreamSignal::new_fallible) stream signal.
+pub type StreamError = Arc<dyn Error + Send + Sync>;
+
+/// How far a stream signal has got through its stream.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamStatus {
+    /// No item has been received yet.
+    #[default]
+    Pending,
+    /// At least one item has been received, and the stream may yield more.
+    Streaming,
+    /// The stream has ended, and will not yield any more items.
+    Ended,
+}
+
+/// A reference-counted signal that holds the latest item yielded by a [`Stream`].
+///
+/// The stream is spawned as a new task on the [`Executor`](any_spawner::Executor) when the signal
+/// is created. The value of the signal is `None` until the first item is received, and is replaced
+/// by each item as it arrives. Whether the stream is still pending or has ended, and the last error
+/// yielded by a [fallible](ArcStreamSignal::new_fallible) stream, can be read reactively.
+///
+/// The stream is cancelled when the [`Owner`] the signal was created in is cleaned up, or when
+/// every reference to the signal has been dropped.
+///
+/// This is a reference-counted signal, which is `Clone` but not `Copy`.
+/// For arena-allocated `Copy` signals, use [`StreamSignal`](super::StreamSignal).
+///
+/// ## Suspense
+/// Reading the signal inside a `Suspense` while the first item has not arrived yet makes the
+/// `Suspense` wait for it, like reading an [`ArcAsyncDerived`](crate::computed::ArcAsyncDerived).
+/// The signal can also be `.await`ed, for example inside `Suspend`: this waits for the first item
+/// and returns the latest one, or `None` if the stream ended without yielding anything.
+///
+/// ## Examples
+/// ```rust
+/// # use reactive_graph::prelude::*;
+/// # use reactive_graph::signal::*;
+/// # tokio_test::block_on(async move {
+/// # any_spawner::Executor::init_tokio(); let owner = reactive_graph::owner::Owner::new(); owner.set();
+/// let (tx, rx) = futures::channel::mpsc::unbounded();
+/// let latest = ArcStreamSignal::new(rx);
+/// assert_eq!(latest.get(), None);
+/// assert_eq!(latest.status(), StreamStatus::Pending);
+///
+/// tx.unbounded_send(1).unwrap();
+/// // waits for the first item
+/// assert_eq!(latest.clone().await, Some(1));
+/// assert_eq!(latest.status(), StreamStatus::Streaming);
+///
+/// drop(tx);
+/// # tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+/// assert_eq!(latest.status(), StreamStatus::Ended);
+/// assert_eq!(latest.get(), Some(1));
+/// # });
+/// ```
+pub struct ArcStreamSignal<T> {
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    pub(crate) defined_at: &'static Location<'static>,
+    pub(crate) value: ArcRwSignal<Option<T>>,
+    status: ArcRwSignal<StreamStatus>,
+    error: ArcRwSignal<Option<StreamError>>,
+    first: Arc<Mutex<FirstItem>>,
+    ready: Shared<oneshot::Receiver<()>>,
+    task: Arc<CancelOnDrop>,
+}
+
+/// Whatever is waiting for the first item.
+struct FirstItem {
+    ready: Option<oneshot::Sender<()>>,
+    suspenses: Vec<TaskHandle>,
+}
+
+impl FirstItem {
+    fn arrived(this: &Mutex<Self>) {
+        let mut this = this.lock().or_poisoned();
+        drop(this.ready.take());
+        // dropping the handles notifies the suspenses
+        drop(std::mem::take(&mut this.suspenses));
+    }
+}
+
+/// Settles whatever is waiting for the first item when the stream task ends, even if it is
+/// cancelled before any item has arrived.
+struct SettleOnDrop(Arc<Mutex<FirstItem>>);
+
+impl Drop for SettleOnDrop {
+    fn drop(&mut self) {
+        FirstItem::arrived(&self.0);
+    }
+}
+
+struct CancelOnDrop(AbortHandle);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl<T> Clone for ArcStreamSignal<T> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: self.defined_at,
+            value: self.value.clone(),
+            status: self.status.clone(),
+            error: self.error.clone(),
+            first: Arc::clone(&self.first),
+            ready: self.ready.clone(),
+            task: Arc::clone(&self.task),
+        }
+    }
+}
+
+impl<T> Debug for ArcStreamSignal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArcStreamSignal")
+            .field("type", &std::any::type_name::<T>())
+            .field("value", &Arc::as_ptr(&self.value.value))
+            .finish()
+    }
+}
+
+impl<T> PartialEq for ArcStreamSignal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for ArcStreamSignal<T> {}
+
+impl<T> Hash for ArcStreamSignal<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T> ArcStreamSignal<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// Creates a signal that holds the latest item yielded by `stream`.
+    #[track_caller]
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self::spawn(stream.map(Ok))
+    }
+
+    /// Creates a signal that holds the latest successful item yielded by `stream`.
+    ///
+    /// An error does not end the stream or replace the value of the signal: it is kept in
+    /// [`error`](ArcStreamSignal::error) until the next successful item arrives.
+    #[track_caller]
+    pub fn new_fallible<E>(
+        stream: impl Stream<Item = Result<T, E>> + Send + 'static,
+    ) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self::spawn(
+            stream.map(|item| item.map_err(|e| Arc::new(e) as StreamError)),
+        )
+    }
+
+    #[track_caller]
+    fn spawn(
+        stream: impl Stream<Item = Result<T, StreamError>> + Send + 'static,
+    ) -> Self {
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let (abort, registration) = AbortHandle::new_pair();
+        let this = Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+            value: ArcRwSignal::new(None),
+            status: ArcRwSignal::new(StreamStatus::Pending),
+            error: ArcRwSignal::new(None),
+            first: Arc::new(Mutex::new(FirstItem {
+                ready: Some(ready_tx),
+                suspenses: Vec::new(),
+            })),
+            ready: ready_rx.shared(),
+            task: Arc::new(CancelOnDrop(abort.clone())),
+        };
+        Owner::on_cleanup(move || abort.abort());
+
+        let task = {
+            let value = this.value.clone();
+            let status = this.status.clone();
+            let error = this.error.clone();
+            let first = SettleOnDrop(Arc::clone(&this.first));
+            async move {
+                let mut stream = std::pin::pin!(stream);
+                while let Some(item) = stream.next().await {
+                    match item {
+                        Ok(item) => {
+                            value.set(Some(item));
+                            if error.with_untracked(Option::is_some) {
+                                error.set(None);
+                            }
+                            if status.get_untracked() == StreamStatus::Pending {
+                                status.set(StreamStatus::Streaming);
+                                FirstItem::arrived(&first.0);
+                            }
+                        }
+                        Err(e) => error.set(Some(e)),
+                    }
+                }
+                status.set(StreamStatus::Ended);
+                drop(first);
+            }
+        };
+        crate::spawn(Abortable::new(task, registration).map(|_| ()));
+        this
+    }
+}
+
+impl<T> ArcStreamSignal<T> {
+    /// How far the signal has got through its stream.
+    ///
+    /// This is a reactive read: reading it in an effect or memo subscribes to changes in status.
+    pub fn status(&self) -> StreamStatus {
+        self.status.get()
+    }
+
+    /// Whether the first item has yet to arrive. This is a reactive read.
+    pub fn is_pending(&self) -> bool {
+        self.status() == StreamStatus::Pending
+    }
+
+    /// Whether the stream has ended. This is a reactive read.
+    pub fn is_ended(&self) -> bool {
+        self.status() == StreamStatus::Ended
+    }
+
+    /// The last error yielded by the stream, if no successful item has arrived since.
+    ///
+    /// This is a reactive read. It is always `None` for a stream signal created with
+    /// [`new`](ArcStreamSignal::new).
+    pub fn error(&self) -> Option<StreamError> {
+        self.error.get()
+    }
+
+    /// Returns a `Future` that is ready when the first item has arrived, or when the stream has
+    /// ended or been cancelled without yielding any items.
+    pub fn ready(&self) -> impl Future<Output = ()> + Send + 'static {
+        self.ready.clone().map(|_| ())
+    }
+}
+
+impl<T> DefinedAt for ArcStreamSignal<T> {
+    #[inline(always)]
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T> IsDisposed for ArcStreamSignal<T> {
+    #[inline(always)]
+    fn is_disposed(&self) -> bool {
+        false
+    }
+}
+
+impl<T> AsSubscriberSet for ArcStreamSignal<T> {
+    type Output = Arc<RwLock<SubscriberSet>>;
+
+    #[inline(always)]
+    fn as_subscriber_set(&self) -> Option<Self::Output> {
+        self.value.as_subscriber_set()
+    }
+}
+
+impl<T: 'static> ReadUntracked for ArcStreamSignal<T> {
+    type Value = ReadGuard<Option<T>, Plain<Option<T>>>;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        if let Some(suspense_context) = use_context::<SuspenseContext>() {
+            let mut first = self.first.lock().or_poisoned();
+            // the sender is only taken once the first item has arrived
+            if first.ready.is_some() {
+                first.suspenses.push(suspense_context.task_id());
+            }
+        }
+        self.value.try_read_untracked()
+    }
+}
+
+impl<T> IntoFuture for ArcStreamSignal<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Output = Option<T>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Option<T>> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            self.ready().await;
+            self.value.get_untracked()
+        })
+    }
+}
@@ -22,10 +22,17 @@ impl ArcTrigger {
     /// Creates a new trigger.
     #[track_caller]
     pub fn new() -> Self {
+        let inner = Arc::default();
+        #[cfg(feature = "introspection")]
+        crate::introspection::register(
+            crate::introspection::NodeKind::Trigger,
+            Some(Location::caller()),
+            &inner,
+        );
         Self {
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             defined_at: Location::caller(),
-            inner: Default::default(),
+            inner,
         }
     }
 }
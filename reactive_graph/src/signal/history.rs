@@ -0,0 +1,301 @@
+use super::{
+    guards::{Plain, ReadGuard},
+    subscriber_traits::AsSubscriberSet,
+    ArcHistorySignal,
+};
+use crate::{
+    graph::{ReactiveNode, SubscriberSet},
+    owner::{ArenaItem, Storage, SyncStorage},
+    signal::guards::UntrackedWriteGuard,
+    traits::{
+        DefinedAt, Dispose, IsDisposed, Notify, ReadUntracked,
+        UntrackableGuard, Write,
+    },
+    unwrap_signal,
+};
+use core::fmt::Debug;
+use guardian::ArcRwLockWriteGuardian;
+use std::{
+    hash::Hash,
+    panic::Location,
+    sync::{Arc, RwLock},
+};
+
+/// An arena-allocated signal that remembers its previous values, so that changes to it can be
+/// undone and redone.
+///
+/// Each time the signal is written to, its value from before the write is added to its history,
+/// up to a maximum number of entries. Several writes can be recorded as a single entry by making
+/// them inside [`group`](HistorySignal::group), or inside a [`batch`](crate::effect::batch).
+///
+/// This is an arena-allocated signal, which is `Copy` and is disposed when its reactive
+/// [`Owner`](crate::owner::Owner) cleans up. For a reference-counted signal that lives as long as
+/// a reference to it is alive, see [`ArcHistorySignal`].
+///
+/// ## Examples
+///
+/// ```
+/// # use reactive_graph::prelude::*;
+/// # use reactive_graph::signal::*; let owner = reactive_graph::owner::Owner::new(); owner.set();
+/// let count = HistorySignal::new(0);
+///
+/// count.set(1);
+/// count.update(|n| *n += 1);
+/// assert_eq!(count.get(), 2);
+///
+/// count.undo();
+/// assert_eq!(count.get(), 1);
+/// assert!(count.can_redo());
+///
+/// // writing to the signal forgets the changes that were undone
+/// count.set(5);
+/// assert!(!count.can_redo());
+/// ```
+pub struct HistorySignal<T, S = SyncStorage> {
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    defined_at: &'static Location<'static>,
+    inner: ArenaItem<ArcHistorySignal<T>, S>,
+}
+
+impl<T, S> Dispose for HistorySignal<T, S> {
+    fn dispose(self) {
+        self.inner.dispose()
+    }
+}
+
+impl<T> HistorySignal<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// Creates a new signal that keeps up to
+    /// [`DEFAULT_HISTORY_CAPACITY`](super::DEFAULT_HISTORY_CAPACITY) entries of history.
+    #[track_caller]
+    pub fn new(value: T) -> Self {
+        Self::new_with_storage(ArcHistorySignal::new(value))
+    }
+
+    /// Creates a new signal that keeps up to `capacity` entries of history.
+    #[track_caller]
+    pub fn with_capacity(value: T, capacity: usize) -> Self {
+        Self::new_with_storage(ArcHistorySignal::with_capacity(value, capacity))
+    }
+}
+
+impl<T, S> HistorySignal<T, S>
+where
+    T: 'static,
+    S: Storage<ArcHistorySignal<T>>,
+{
+    /// Stores a reference-counted history signal with the given arena storage method.
+    #[track_caller]
+    pub fn new_with_storage(inner: ArcHistorySignal<T>) -> Self {
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+            inner: ArenaItem::new_with_storage(inner),
+        }
+    }
+
+    /// Whether there is a change that can be undone.
+    ///
+    /// This is a reactive read: reading it in an effect or memo subscribes to changes in whether
+    /// it is possible to undo.
+    #[track_caller]
+    pub fn can_undo(&self) -> bool {
+        self.inner
+            .try_with_value(|inner| inner.can_undo())
+            .unwrap_or_else(unwrap_signal!(self))
+    }
+
+    /// Whether there is an undone change that can be redone.
+    ///
+    /// This is a reactive read: reading it in an effect or memo subscribes to changes in whether
+    /// it is possible to redo.
+    #[track_caller]
+    pub fn can_redo(&self) -> bool {
+        self.inner
+            .try_with_value(|inner| inner.can_redo())
+            .unwrap_or_else(unwrap_signal!(self))
+    }
+
+    /// Restores the value from before the last change, if there was one.
+    ///
+    /// Returns `true` if a change was undone.
+    #[track_caller]
+    pub fn undo(&self) -> bool {
+        self.inner
+            .try_get_value()
+            .unwrap_or_else(unwrap_signal!(self))
+            .undo()
+    }
+
+    /// Restores the value from before the last [`undo`](HistorySignal::undo), if there was one
+    /// and the signal has not been written to since.
+    ///
+    /// Returns `true` if a change was redone.
+    #[track_caller]
+    pub fn redo(&self) -> bool {
+        self.inner
+            .try_get_value()
+            .unwrap_or_else(unwrap_signal!(self))
+            .redo()
+    }
+
+    /// Forgets all of the changes that could be undone or redone.
+    #[track_caller]
+    pub fn clear_history(&self) {
+        self.inner
+            .try_get_value()
+            .unwrap_or_else(unwrap_signal!(self))
+            .clear_history()
+    }
+
+    /// Runs `fun`, recording every write to the signal that it makes as a single change, which can
+    /// be undone all at once.
+    #[track_caller]
+    pub fn group<U>(&self, fun: impl FnOnce() -> U) -> U {
+        self.inner
+            .try_get_value()
+            .unwrap_or_else(unwrap_signal!(self))
+            .group(fun)
+    }
+}
+
+impl<T, S> Copy for HistorySignal<T, S> {}
+
+impl<T, S> Clone for HistorySignal<T, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, S> Debug for HistorySignal<T, S>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistorySignal")
+            .field("type", &std::any::type_name::<T>())
+            .field("store", &self.inner)
+            .finish()
+    }
+}
+
+impl<T, S> PartialEq for HistorySignal<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T, S> Eq for HistorySignal<T, S> {}
+
+impl<T, S> Hash for HistorySignal<T, S> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+impl<T, S> DefinedAt for HistorySignal<T, S> {
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T: 'static, S> IsDisposed for HistorySignal<T, S> {
+    fn is_disposed(&self) -> bool {
+        self.inner.is_disposed()
+    }
+}
+
+impl<T, S> AsSubscriberSet for HistorySignal<T, S>
+where
+    S: Storage<ArcHistorySignal<T>>,
+{
+    type Output = Arc<RwLock<SubscriberSet>>;
+
+    fn as_subscriber_set(&self) -> Option<Self::Output> {
+        self.inner
+            .try_with_value(|inner| inner.as_subscriber_set())
+            .flatten()
+    }
+}
+
+impl<T, S> ReadUntracked for HistorySignal<T, S>
+where
+    T: 'static,
+    S: Storage<ArcHistorySignal<T>>,
+{
+    type Value = ReadGuard<T, Plain<T>>;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        self.inner
+            .try_get_value()
+            .map(|inner| inner.read_untracked())
+    }
+}
+
+impl<T, S> Notify for HistorySignal<T, S>
+where
+    S: Storage<ArcHistorySignal<T>>,
+{
+    fn notify(&self) {
+        self.mark_dirty();
+    }
+}
+
+impl<T, S> Write for HistorySignal<T, S>
+where
+    T: Clone + 'static,
+    S: Storage<ArcHistorySignal<T>>,
+{
+    type Value = T;
+
+    fn try_write(&self) -> Option<impl UntrackableGuard<Target = Self::Value>> {
+        self.inner
+            .try_with_value(|n| {
+                let guard =
+                    ArcRwLockWriteGuardian::take(Arc::clone(&n.value.value))
+                        .ok()?;
+                Some(n.record_write(*self, guard))
+            })
+            .flatten()
+    }
+
+    #[allow(refining_impl_trait)]
+    fn try_write_untracked(&self) -> Option<UntrackedWriteGuard<Self::Value>> {
+        self.inner
+            .try_with_value(|n| n.try_write_untracked())
+            .flatten()
+    }
+}
+
+impl<T> From<ArcHistorySignal<T>> for HistorySignal<T>
+where
+    T: Send + Sync + 'static,
+{
+    #[track_caller]
+    fn from(value: ArcHistorySignal<T>) -> Self {
+        Self::new_with_storage(value)
+    }
+}
+
+impl<T, S> From<HistorySignal<T, S>> for ArcHistorySignal<T>
+where
+    T: 'static,
+    S: Storage<ArcHistorySignal<T>>,
+{
+    #[track_caller]
+    fn from(value: HistorySignal<T, S>) -> Self {
+        value
+            .inner
+            .try_get_value()
+            .unwrap_or_else(unwrap_signal!(value))
+    }
+}
@@ -0,0 +1,266 @@
+use super::{
+    guards::{Plain, ReadGuard},
+    subscriber_traits::AsSubscriberSet,
+    ArcPersistedSignal, PersistenceBackend, PersistenceError,
+};
+use crate::{
+    graph::{ReactiveNode, SubscriberSet},
+    owner::{ArenaItem, Storage, SyncStorage},
+    signal::guards::{UntrackedWriteGuard, WriteGuard},
+    traits::{
+        DefinedAt, Dispose, IsDisposed, Notify, ReadUntracked,
+        UntrackableGuard, Write,
+    },
+    unwrap_signal,
+};
+use core::fmt::Debug;
+use guardian::ArcRwLockWriteGuardian;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    hash::Hash,
+    panic::Location,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+/// An arena-allocated signal that loads its initial value from a [`PersistenceBackend`], and
+/// saves its value back whenever it changes.
+///
+/// Changes are saved once the value has stopped changing for a short time, when the
+/// [`Owner`](crate::owner::Owner) the signal was created in is cleaned up, or when
+/// [`flush`](PersistedSignal::flush) is called. See [`ArcPersistedSignal`] for details.
+///
+/// This is an arena-allocated signal, which is `Copy` and is disposed when its reactive
+/// [`Owner`](crate::owner::Owner) cleans up. For a reference-counted signal that lives as long as
+/// a reference to it is alive, see [`ArcPersistedSignal`].
+///
+/// ## Examples
+///
+/// ```
+/// # use reactive_graph::prelude::*;
+/// # use any_spawner::{Executor, TestExecutor};
+/// # _ = Executor::init_custom_executor(TestExecutor);
+/// # use reactive_graph::signal::*; let owner = reactive_graph::owner::Owner::new(); owner.set();
+/// let backend = MemoryBackend::new();
+///
+/// let filter = PersistedSignal::new(backend.clone(), "filter", String::new());
+/// filter.set("errors".to_string());
+///
+/// // cleaning up the owner saves the latest change
+/// owner.cleanup();
+/// assert_eq!(backend.get("filter").as_deref(), Some(r#""errors""#));
+/// ```
+pub struct PersistedSignal<T, S = SyncStorage> {
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    defined_at: &'static Location<'static>,
+    inner: ArenaItem<ArcPersistedSignal<T>, S>,
+}
+
+impl<T, S> Dispose for PersistedSignal<T, S> {
+    fn dispose(self) {
+        self.inner.dispose()
+    }
+}
+
+impl<T> PersistedSignal<T>
+where
+    T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    /// Creates a signal that is stored under `key` in `backend`, starting with `default` if
+    /// nothing has been stored under it yet. Changes are saved once the value has stopped
+    /// changing for [`DEFAULT_PERSIST_DEBOUNCE`](super::DEFAULT_PERSIST_DEBOUNCE).
+    #[track_caller]
+    pub fn new(
+        backend: impl PersistenceBackend,
+        key: impl Into<String>,
+        default: T,
+    ) -> Self {
+        Self::new_with_storage(ArcPersistedSignal::new(backend, key, default))
+    }
+
+    /// Creates a signal that is stored under `key` in `backend`, starting with `default` if
+    /// nothing has been stored under it yet. Changes are saved once the value has stopped
+    /// changing for `debounce`.
+    #[track_caller]
+    pub fn new_with_debounce(
+        backend: impl PersistenceBackend,
+        key: impl Into<String>,
+        default: T,
+        debounce: Duration,
+    ) -> Self {
+        Self::new_with_storage(ArcPersistedSignal::new_with_debounce(
+            backend, key, default, debounce,
+        ))
+    }
+}
+
+impl<T, S> PersistedSignal<T, S>
+where
+    T: 'static,
+    S: Storage<ArcPersistedSignal<T>>,
+{
+    /// Stores a reference-counted persisted signal with the given arena storage method.
+    #[track_caller]
+    pub fn new_with_storage(inner: ArcPersistedSignal<T>) -> Self {
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+            inner: ArenaItem::new_with_storage(inner),
+        }
+    }
+
+    /// The key the value is stored under.
+    #[track_caller]
+    pub fn key(&self) -> String {
+        self.inner
+            .try_with_value(|inner| inner.key().to_string())
+            .unwrap_or_else(unwrap_signal!(self))
+    }
+
+    /// Saves the current value now, without waiting for it to stop changing.
+    #[track_caller]
+    pub fn flush(&self) -> Result<(), PersistenceError>
+    where
+        T: Serialize,
+    {
+        self.inner
+            .try_with_value(|inner| inner.flush())
+            .unwrap_or_else(unwrap_signal!(self))
+    }
+}
+
+impl<T, S> Copy for PersistedSignal<T, S> {}
+
+impl<T, S> Clone for PersistedSignal<T, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, S> Debug for PersistedSignal<T, S>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistedSignal")
+            .field("type", &std::any::type_name::<T>())
+            .field("store", &self.inner)
+            .finish()
+    }
+}
+
+impl<T, S> PartialEq for PersistedSignal<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T, S> Eq for PersistedSignal<T, S> {}
+
+impl<T, S> Hash for PersistedSignal<T, S> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+impl<T, S> DefinedAt for PersistedSignal<T, S> {
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T: 'static, S> IsDisposed for PersistedSignal<T, S> {
+    fn is_disposed(&self) -> bool {
+        self.inner.is_disposed()
+    }
+}
+
+impl<T, S> AsSubscriberSet for PersistedSignal<T, S>
+where
+    S: Storage<ArcPersistedSignal<T>>,
+{
+    type Output = Arc<RwLock<SubscriberSet>>;
+
+    fn as_subscriber_set(&self) -> Option<Self::Output> {
+        self.inner
+            .try_with_value(|inner| inner.as_subscriber_set())
+            .flatten()
+    }
+}
+
+impl<T, S> ReadUntracked for PersistedSignal<T, S>
+where
+    T: 'static,
+    S: Storage<ArcPersistedSignal<T>>,
+{
+    type Value = ReadGuard<T, Plain<T>>;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        self.inner
+            .try_get_value()
+            .map(|inner| inner.read_untracked())
+    }
+}
+
+impl<T, S> Notify for PersistedSignal<T, S>
+where
+    S: Storage<ArcPersistedSignal<T>>,
+{
+    fn notify(&self) {
+        self.mark_dirty();
+    }
+}
+
+impl<T, S> Write for PersistedSignal<T, S>
+where
+    T: 'static,
+    S: Storage<ArcPersistedSignal<T>>,
+{
+    type Value = T;
+
+    fn try_write(&self) -> Option<impl UntrackableGuard<Target = Self::Value>> {
+        let guard = self.inner.try_with_value(|n| {
+            n.mark_pending();
+            ArcRwLockWriteGuardian::take(Arc::clone(&n.value.value)).ok()
+        })??;
+        Some(WriteGuard::new(*self, guard))
+    }
+
+    #[allow(refining_impl_trait)]
+    fn try_write_untracked(&self) -> Option<UntrackedWriteGuard<Self::Value>> {
+        self.inner
+            .try_with_value(|n| n.try_write_untracked())
+            .flatten()
+    }
+}
+
+impl<T> From<ArcPersistedSignal<T>> for PersistedSignal<T>
+where
+    T: Send + Sync + 'static,
+{
+    #[track_caller]
+    fn from(value: ArcPersistedSignal<T>) -> Self {
+        Self::new_with_storage(value)
+    }
+}
+
+impl<T, S> From<PersistedSignal<T, S>> for ArcPersistedSignal<T>
+where
+    T: 'static,
+    S: Storage<ArcPersistedSignal<T>>,
+{
+    #[track_caller]
+    fn from(value: PersistedSignal<T, S>) -> Self {
+        value
+            .inner
+            .try_get_value()
+            .unwrap_or_else(unwrap_signal!(value))
+    }
+}
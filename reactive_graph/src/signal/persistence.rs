@@ -0,0 +1,138 @@
+use or_poisoned::OrPoisoned;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+use thiserror::Error;
+
+/// Somewhere that [persisted signals](super::ArcPersistedSignal) load their values from, and
+/// save their values to.
+///
+/// Each value is stored under a key, as a JSON string. [`FileBackend`] keeps each value in its
+/// own file, and [`MemoryBackend`] keeps values in memory, which is useful in tests.
+pub trait PersistenceBackend: Send + Sync + 'static {
+    /// Returns the value stored under `key`, or `None` if nothing has been stored under it.
+    fn load(&self, key: &str) -> Result<Option<String>, PersistenceError>;
+
+    /// Stores `value` under `key`, replacing any value that was stored under it before.
+    fn store(&self, key: &str, value: &str) -> Result<(), PersistenceError>;
+}
+
+impl<B> PersistenceBackend for Arc<B>
+where
+    B: PersistenceBackend + ?Sized,
+{
+    fn load(&self, key: &str) -> Result<Option<String>, PersistenceError> {
+        (**self).load(key)
+    }
+
+    fn store(&self, key: &str, value: &str) -> Result<(), PersistenceError> {
+        (**self).store(key, value)
+    }
+}
+
+/// An error that occurred while loading or saving a persisted value.
+#[derive(Error, Debug)]
+pub enum PersistenceError {
+    /// The backend could not read or write the value.
+    #[error("could not access the persisted value: {0}")]
+    Io(#[from] std::io::Error),
+    /// The value could not be converted to or from JSON.
+    #[error("could not convert the persisted value: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A [`PersistenceBackend`] that keeps values in memory.
+///
+/// Clones of a `MemoryBackend` share the same values, so a test can keep a clone to check what
+/// has been saved, or pass a clone to a second signal to check what it loads.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBackend {
+    values: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl MemoryBackend {
+    /// Creates a backend with no stored values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the JSON stored under `key`, if any.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.values.read().or_poisoned().get(key).cloned()
+    }
+}
+
+impl PersistenceBackend for MemoryBackend {
+    fn load(&self, key: &str) -> Result<Option<String>, PersistenceError> {
+        Ok(self.get(key))
+    }
+
+    fn store(&self, key: &str, value: &str) -> Result<(), PersistenceError> {
+        self.values
+            .write()
+            .or_poisoned()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use file::FileBackend;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+mod file {
+    use super::{PersistenceBackend, PersistenceError};
+    use std::{
+        fs, io,
+        path::{Path, PathBuf},
+    };
+
+    /// A [`PersistenceBackend`] that keeps each value in a JSON file in a directory.
+    ///
+    /// The value for `key` is stored in `<dir>/<key>.json`, so keys should be valid file names.
+    /// The directory is created when the first value is saved. Each file is replaced as a whole,
+    /// so a file is never left half-written if the program stops while a value is saved.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct FileBackend {
+        dir: PathBuf,
+    }
+
+    impl FileBackend {
+        /// Creates a backend that stores values in `dir`.
+        pub fn new(dir: impl Into<PathBuf>) -> Self {
+            Self { dir: dir.into() }
+        }
+
+        /// The directory in which values are stored.
+        pub fn dir(&self) -> &Path {
+            &self.dir
+        }
+
+        fn path(&self, key: &str, extension: &str) -> PathBuf {
+            self.dir.join(format!("{key}.{extension}"))
+        }
+    }
+
+    impl PersistenceBackend for FileBackend {
+        fn load(&self, key: &str) -> Result<Option<String>, PersistenceError> {
+            match fs::read_to_string(self.path(key, "json")) {
+                Ok(value) => Ok(Some(value)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        }
+
+        fn store(
+            &self,
+            key: &str,
+            value: &str,
+        ) -> Result<(), PersistenceError> {
+            fs::create_dir_all(&self.dir)?;
+            let partial = self.path(key, "json.partial");
+            fs::write(&partial, value)?;
+            fs::rename(&partial, self.path(key, "json"))?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use super::{
+    guards::{Plain, ReadGuard},
+    subscriber_traits::AsSubscriberSet,
+    ArcStreamSignal, StreamError, StreamStatus,
+};
+use crate::{
+    graph::SubscriberSet,
+    owner::{ArenaItem, Storage, SyncStorage},
+    traits::{DefinedAt, Dispose, IsDisposed, ReadUntracked},
+    unwrap_signal,
+};
+use core::fmt::Debug;
+use futures::Stream;
+use std::{
+    error::Error,
+    future::{Future, IntoFuture},
+    hash::Hash,
+    panic::Location,
+    sync::{Arc, RwLock},
+};
+
+/// An arena-allocated signal that holds the latest item yielded by a [`Stream`].
+///
+/// The stream is spawned as a new task when the signal is created, and is cancelled when the
+/// [`Owner`](crate::owner::Owner) the signal was created in is cleaned up. See
+/// [`ArcStreamSignal`] for details.
+///
+/// This is an arena-allocated signal, which is `Copy` and is disposed when its reactive
+/// [`Owner`](crate::owner::Owner) cleans up. For a reference-counted signal that lives as long as
+/// a reference to it is alive, see [`ArcStreamSignal`].
+///
+/// ## Examples
+/// ```rust
+/// # use reactive_graph::prelude::*;
+/// # use reactive_graph::signal::*;
+/// # tokio_test::block_on(async move {
+/// # any_spawner::Executor::init_tokio(); let owner = reactive_graph::owner::Owner::new(); owner.set();
+/// let messages = StreamSignal::new(futures::stream::iter(["hello", "world"]));
+/// messages.ready().await;
+/// # tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+/// assert_eq!(messages.get(), Some("world"));
+/// assert!(messages.is_ended());
+/// # });
+/// ```
+pub struct StreamSignal<T, S = SyncStorage> {
+    #[cfg(any(debug_assertions, leptos_debuginfo))]
+    defined_at: &'static Location<'static>,
+    inner: ArenaItem<ArcStreamSignal<T>, S>,
+}
+
+impl<T, S> Dispose for StreamSignal<T, S> {
+    fn dispose(self) {
+        self.inner.dispose()
+    }
+}
+
+impl<T> StreamSignal<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// Creates a signal that holds the latest item yielded by `stream`.
+    #[track_caller]
+    pub fn new(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self::new_with_storage(ArcStreamSignal::new(stream))
+    }
+
+    /// Creates a signal that holds the latest successful item yielded by `stream`, keeping the
+    /// last error in [`error`](StreamSignal::error).
+    #[track_caller]
+    pub fn new_fallible<E>(
+        stream: impl Stream<Item = Result<T, E>> + Send + 'static,
+    ) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self::new_with_storage(ArcStreamSignal::new_fallible(stream))
+    }
+}
+
+impl<T, S> StreamSignal<T, S>
+where
+    T: 'static,
+    S: Storage<ArcStreamSignal<T>>,
+{
+    /// Stores a reference-counted stream signal with the given arena storage method.
+    #[track_caller]
+    pub fn new_with_storage(inner: ArcStreamSignal<T>) -> Self {
+        Self {
+            #[cfg(any(debug_assertions, leptos_debuginfo))]
+            defined_at: Location::caller(),
+            inner: ArenaItem::new_with_storage(inner),
+        }
+    }
+
+    /// How far the signal has got through its stream. This is a reactive read.
+    #[track_caller]
+    pub fn status(&self) -> StreamStatus {
+        self.inner
+            .try_with_value(|inner| inner.status())
+            .unwrap_or_else(unwrap_signal!(self))
+    }
+
+    /// Whether the first item has yet to arrive. This is a reactive read.
+    #[track_caller]
+    pub fn is_pending(&self) -> bool {
+        self.status() == StreamStatus::Pending
+    }
+
+    /// Whether the stream has ended. This is a reactive read.
+    #[track_caller]
+    pub fn is_ended(&self) -> bool {
+        self.status() == StreamStatus::Ended
+    }
+
+    /// The last error yielded by the stream, if no successful item has arrived since. This is a
+    /// reactive read.
+    #[track_caller]
+    pub fn error(&self) -> Option<StreamError> {
+        self.inner
+            .try_with_value(|inner| inner.error())
+            .unwrap_or_else(unwrap_signal!(self))
+    }
+
+    /// Returns a `Future` that is ready when the first item has arrived, or when the stream has
+    /// ended or been cancelled without yielding any items.
+    #[track_caller]
+    pub fn ready(&self) -> impl Future<Output = ()> + Send + 'static {
+        self.inner
+            .try_with_value(|inner| inner.ready())
+            .unwrap_or_else(unwrap_signal!(self))
+    }
+}
+
+impl<T, S> Copy for StreamSignal<T, S> {}
+
+impl<T, S> Clone for StreamSignal<T, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, S> Debug for StreamSignal<T, S>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamSignal")
+            .field("type", &std::any::type_name::<T>())
+            .field("store", &self.inner)
+            .finish()
+    }
+}
+
+impl<T, S> PartialEq for StreamSignal<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T, S> Eq for StreamSignal<T, S> {}
+
+impl<T, S> Hash for StreamSignal<T, S> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.inner.hash(state);
+    }
+}
+
+impl<T, S> DefinedAt for StreamSignal<T, S> {
+    fn defined_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, leptos_debuginfo))]
+        {
+            Some(self.defined_at)
+        }
+        #[cfg(not(any(debug_assertions, leptos_debuginfo)))]
+        {
+            None
+        }
+    }
+}
+
+impl<T: 'static, S> IsDisposed for StreamSignal<T, S> {
+    fn is_disposed(&self) -> bool {
+        self.inner.is_disposed()
+    }
+}
+
+impl<T, S> AsSubscriberSet for StreamSignal<T, S>
+where
+    S: Storage<ArcStreamSignal<T>>,
+{
+    type Output = Arc<RwLock<SubscriberSet>>;
+
+    fn as_subscriber_set(&self) -> Option<Self::Output> {
+        self.inner
+            .try_with_value(|inner| inner.as_subscriber_set())
+            .flatten()
+    }
+}
+
+impl<T, S> ReadUntracked for StreamSignal<T, S>
+where
+    T: 'static,
+    S: Storage<ArcStreamSignal<T>>,
+{
+    type Value = ReadGuard<Option<T>, Plain<Option<T>>>;
+
+    fn try_read_untracked(&self) -> Option<Self::Value> {
+        self.inner
+            .try_with_value(|inner| inner.try_read_untracked())
+            .flatten()
+    }
+}
+
+impl<T, S> IntoFuture for StreamSignal<T, S>
+where
+    T: Clone + Send + Sync + 'static,
+    S: Storage<ArcStreamSignal<T>>,
+{
+    type Output = Option<T>;
+    type IntoFuture = <ArcStreamSignal<T> as IntoFuture>::IntoFuture;
+
+    #[track_caller]
+    fn into_future(self) -> Self::IntoFuture {
+        self.inner
+            .try_get_value()
+            .unwrap_or_else(unwrap_signal!(self))
+            .into_future()
+    }
+}
+
+impl<T> From<ArcStreamSignal<T>> for StreamSignal<T>
+where
+    T: Send + Sync + 'static,
+{
+    #[track_caller]
+    fn from(value: ArcStreamSignal<T>) -> Self {
+        Self::new_with_storage(value)
+    }
+}
+
+impl<T, S> From<StreamSignal<T, S>> for ArcStreamSignal<T>
+where
+    T: 'static,
+    S: Storage<ArcStreamSignal<T>>,
+{
+    #[track_caller]
+    fn from(value: StreamSignal<T, S>) -> Self {
+        value
+            .inner
+            .try_get_value()
+            .unwrap_or_else(unwrap_signal!(value))
+    }
+}
@@ -48,6 +48,17 @@ impl DefinedAt for RwLock<SubscriberSet> {
 // Source>
 impl<T: AsSubscriberSet + DefinedAt> ReactiveNode for T {
     fn mark_dirty(&self) {
+        #[cfg(feature = "introspection")]
+        if let Some(inner) = self.as_subscriber_set() {
+            crate::introspection::marked_dirty(inner.borrow()
+                as *const RwLock<SubscriberSet>
+                as usize);
+        }
+        #[cfg(debug_assertions)]
+        crate::diagnostics::notifying(self.defined_at(), || {
+            self.mark_subscribers_check()
+        });
+        #[cfg(not(debug_assertions))]
         self.mark_subscribers_check();
     }
 
@@ -138,6 +149,17 @@ impl ReactiveNode for RwLock<SubscriberSet> {
     }
 }
 
+#[cfg(feature = "introspection")]
+impl crate::introspection::Inspect for RwLock<SubscriberSet> {
+    fn subscribers(&self) -> Vec<usize> {
+        self.read().or_poisoned().ids()
+    }
+
+    fn hold(&self, registration: crate::introspection::Registration) {
+        self.write().or_poisoned().hold(registration);
+    }
+}
+
 impl Source for RwLock<SubscriberSet> {
     fn clear_subscribers(&self) {
         self.write().or_poisoned().take();
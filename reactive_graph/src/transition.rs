@@ -1,26 +1,55 @@
 //! Utilities to wait for asynchronous primitives to resolve.
 
-use futures::{channel::oneshot, future::join_all};
+use crate::owner::{provide_context, use_context, Owner};
+use futures::{
+    channel::oneshot,
+    future::{self, join_all},
+};
 use or_poisoned::OrPoisoned;
+use pin_project_lite::pin_project;
 use std::{
+    cell::RefCell,
     future::Future,
-    sync::{mpsc, OnceLock, RwLock},
+    pin::{pin, Pin},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex, Weak,
+    },
+    task::{Context, Poll},
 };
 
-static TRANSITION: OnceLock<RwLock<Option<TransitionInner>>> = OnceLock::new();
-
-fn global_transition() -> &'static RwLock<Option<TransitionInner>> {
-    TRANSITION.get_or_init(|| RwLock::new(None))
+thread_local! {
+    static CURRENT: RefCell<Option<AsyncTransition>> = const { RefCell::new(None) };
 }
 
+/// The transition that is running on an [`Owner`], provided as context.
 #[derive(Debug, Clone)]
+struct OwnerTransition(Option<AsyncTransition>);
+
+#[derive(Debug)]
 struct TransitionInner {
     tx: mpsc::Sender<oneshot::Receiver<()>>,
+    parent: Option<AsyncTransition>,
+    children: Mutex<Vec<Weak<TransitionInner>>>,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+    cancel_tx: Mutex<Option<oneshot::Sender<()>>>,
 }
 
 /// Transitions allow you to wait for all asynchronous resources created during them to resolve.
-#[derive(Debug)]
-pub struct AsyncTransition;
+///
+/// A transition is scoped to the task that runs it, and to the [`Owner`] that was current when it
+/// started. Resources only register with the transition that is running in their own task or
+/// under their own owner, so concurrent transitions (for example, one for each request on a
+/// multithreaded server) do not interfere with one another.
+///
+/// Transitions can be nested. A transition that starts while another is running in the same
+/// scope becomes its child: resources register with the innermost transition, and cancelling a
+/// transition also cancels its children.
+#[derive(Debug, Clone)]
+pub struct AsyncTransition {
+    inner: Arc<TransitionInner>,
+}
 
 impl AsyncTransition {
     /// Calls the `action` function, and returns a `Future` that resolves when any
@@ -30,41 +59,175 @@ impl AsyncTransition {
     ///
     /// This allows for an inversion of control: the caller does not need to know when all the
     /// resources created inside the `action` will resolve, but can wait for them to notify it.
+    ///
+    /// If the transition is [cancelled](AsyncTransition::cancel), this stops waiting for
+    /// resources as soon as the `action` has completed.
     pub async fn run<T, U>(action: impl FnOnce() -> T) -> U
     where
         T: Future<Output = U>,
     {
         let (tx, rx) = mpsc::channel();
-        let global_transition = global_transition();
-        let inner = TransitionInner { tx };
-        let prev = Option::replace(
-            &mut *global_transition.write().or_poisoned(),
-            inner.clone(),
-        );
-        let value = action().await;
-        _ = std::mem::replace(
-            &mut *global_transition.write().or_poisoned(),
-            prev,
-        );
-        let mut pending = Vec::new();
-        while let Ok(tx) = rx.try_recv() {
-            pending.push(tx);
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        let parent = Self::current();
+        let transition = AsyncTransition {
+            inner: Arc::new(TransitionInner {
+                tx,
+                parent: parent.clone(),
+                children: Default::default(),
+                cancelled: AtomicBool::new(false),
+                finished: AtomicBool::new(false),
+                cancel_tx: Mutex::new(Some(cancel_tx)),
+            }),
+        };
+        if let Some(parent) = &parent {
+            parent
+                .inner
+                .children
+                .lock()
+                .or_poisoned()
+                .push(Arc::downgrade(&transition.inner));
+            if parent.is_cancelled() {
+                transition.cancel();
+            }
+        }
+
+        // resources that update in their own tasks find the transition through their owner
+        let _owner = Owner::current().map(|owner| {
+            owner.with(|| {
+                provide_context(OwnerTransition(Some(transition.clone())))
+            });
+            ProvidedTransition {
+                owner,
+                transition: transition.clone(),
+            }
+        });
+
+        let value = InTransition {
+            transition: transition.clone(),
+            fut: transition.enter(action),
+        }
+        .await;
+
+        let pending = async move {
+            // resources that resolve can start others, so keep waiting until none are left
+            loop {
+                let pending = rx.try_iter().collect::<Vec<_>>();
+                if pending.is_empty() {
+                    break;
+                }
+                join_all(pending).await;
+            }
+        };
+        if !transition.is_cancelled() {
+            future::select(pin!(pending), cancel_rx).await;
+        }
+
+        value
+    }
+
+    /// Returns the transition that is running in the current task or under the current
+    /// [`Owner`], if any.
+    pub fn current() -> Option<AsyncTransition> {
+        CURRENT
+            .with(|current| current.borrow().clone())
+            .or_else(|| {
+                use_context::<OwnerTransition>().and_then(|current| current.0)
+            })
+    }
+
+    /// Cancels this transition and any transitions nested inside it.
+    ///
+    /// A cancelled transition no longer accepts resources, and stops waiting for the ones that
+    /// have already registered.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Relaxed);
+        if let Some(tx) = self.inner.cancel_tx.lock().or_poisoned().take() {
+            _ = tx.send(());
+        }
+        let children =
+            std::mem::take(&mut *self.inner.children.lock().or_poisoned());
+        for child in children.iter().filter_map(Weak::upgrade) {
+            AsyncTransition { inner: child }.cancel();
         }
-        join_all(pending).await;
+    }
+
+    /// Whether this transition, or a transition it is nested inside, has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Relaxed)
+            || self
+                .inner
+                .parent
+                .as_ref()
+                .map(AsyncTransition::is_cancelled)
+                .unwrap_or(false)
+    }
+
+    /// Runs `fun` with this as the current transition for this thread.
+    fn enter<T>(&self, fun: impl FnOnce() -> T) -> T {
+        let prev = CURRENT.with(|current| current.replace(Some(self.clone())));
+        let value = fun();
+        CURRENT.with(|current| *current.borrow_mut() = prev);
         value
     }
 
     pub(crate) fn register(rx: oneshot::Receiver<()>) {
-        if let Some(tx) = global_transition()
-            .read()
-            .or_poisoned()
-            .as_ref()
-            .map(|n| &n.tx)
-        {
-            // if it's an Err, that just means the Receiver was dropped
-            // i.e., the transition is no longer listening, in which case it doesn't matter if we
-            // successfully register with it or not
-            _ = tx.send(rx);
+        if let Some(transition) = Self::current() {
+            if !transition.is_cancelled() {
+                // if it's an Err, that just means the Receiver was dropped
+                // i.e., the transition is no longer listening, in which case it doesn't matter
+                // if we successfully register with it or not
+                _ = transition.inner.tx.send(rx);
+            }
         }
     }
 }
+
+/// Marks a transition as finished when dropped, and if it is still the transition provided on
+/// its owner, provides the closest enclosing transition that is still running instead.
+///
+/// This also runs if the transition's future is dropped before it completes, or panics.
+struct ProvidedTransition {
+    owner: Owner,
+    transition: AsyncTransition,
+}
+
+impl Drop for ProvidedTransition {
+    fn drop(&mut self) {
+        let inner = &self.transition.inner;
+        inner.finished.store(true, Ordering::Relaxed);
+        self.owner.with(|| {
+            // another transition may have started on the same owner in the meantime
+            let is_current = use_context::<OwnerTransition>()
+                .and_then(|current| current.0)
+                .is_some_and(|current| Arc::ptr_eq(&current.inner, inner));
+            if is_current {
+                let mut parent = inner.parent.clone();
+                while parent.as_ref().is_some_and(|parent| {
+                    parent.inner.finished.load(Ordering::Relaxed)
+                }) {
+                    parent =
+                        parent.and_then(|parent| parent.inner.parent.clone());
+                }
+                provide_context(OwnerTransition(parent));
+            }
+        });
+    }
+}
+
+pin_project! {
+    /// Sets the transition as current each time the inner `Future` is polled.
+    struct InTransition<Fut> {
+        transition: AsyncTransition,
+        #[pin]
+        fut: Fut,
+    }
+}
+
+impl<Fut: Future> Future for InTransition<Fut> {
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        this.transition.enter(|| this.fut.poll(cx))
+    }
+}
@@ -255,3 +255,28 @@ async fn paused_effect_pauses() {
         })
         .await
 }
+
+#[cfg(all(feature = "effects", debug_assertions))]
+#[test]
+#[should_panic(expected = "reads RenderEffect defined at")]
+fn render_effect_in_a_cycle_through_a_memo_panics_with_the_cycle() {
+    use imports::*;
+    use reactive_graph::computed::Memo;
+
+    let owner = Owner::new();
+    owner.set();
+
+    let slot = Arc::new(RwLock::new(None::<Memo<i32>>));
+    let memo = Memo::new({
+        let slot = Arc::clone(&slot);
+        move |_| {
+            let memo = slot.read().unwrap().unwrap();
+            // the render effect runs immediately, and reads the memo that is still running
+            mem::forget(RenderEffect::new(move |_| memo.get()));
+            0
+        }
+    });
+    *slot.write().unwrap() = Some(memo);
+
+    memo.get();
+}
@@ -260,3 +260,27 @@ fn test_batch() {
 
     assert_eq!(values.get_value(), vec![(0, 0), (1, 0), (1, 1), (3, 3)]);
 }
+
+#[cfg(all(feature = "effects", debug_assertions))]
+#[test]
+#[should_panic(expected = "reads ImmediateEffect defined at")]
+fn effect_in_a_cycle_through_a_memo_panics_with_the_cycle() {
+    use imports::*;
+    use reactive_graph::computed::Memo;
+
+    let owner = Owner::new();
+    owner.set();
+
+    let a = RwSignal::new(0);
+    let b = RwSignal::new(0);
+    let memo = Memo::new(move |_| {
+        let value = a.get();
+        b.set(value);
+        value
+    });
+
+    let _guard = ImmediateEffect::new(move || {
+        b.get();
+        memo.get();
+    });
+}
@@ -0,0 +1,187 @@
+#[cfg(feature = "effects")]
+pub mod imports {
+    pub use any_spawner::{Executor, TestExecutor};
+    pub use reactive_graph::{
+        effect::{flush_effects, Effect, EffectPriority},
+        owner::Owner,
+        prelude::*,
+        signal::RwSignal,
+    };
+    pub use std::sync::{Arc, RwLock};
+
+    pub fn log() -> (
+        Arc<RwLock<Vec<&'static str>>>,
+        impl Fn() -> Vec<&'static str>,
+    ) {
+        let log = Arc::new(RwLock::new(Vec::new()));
+        let take = {
+            let log = Arc::clone(&log);
+            move || std::mem::take(&mut *log.write().unwrap())
+        };
+        (log, take)
+    }
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn queued_effects_run_in_priority_order() {
+    use imports::*;
+
+    _ = Executor::init_custom_executor(TestExecutor);
+    let owner = Owner::new();
+    owner.set();
+
+    let a = RwSignal::new(0);
+    let (log, take) = log();
+    for (priority, name) in [
+        (EffectPriority::Idle, "idle"),
+        (EffectPriority::User, "user"),
+        (EffectPriority::Render, "render"),
+    ] {
+        let log = Arc::clone(&log);
+        Effect::new_with_priority(priority, move || {
+            a.track();
+            log.write().unwrap().push(name);
+        });
+    }
+    assert!(take().is_empty());
+
+    TestExecutor::run_until_idle().unwrap();
+    assert_eq!(take(), ["render", "user", "idle"]);
+
+    a.set(1);
+    TestExecutor::run_until_idle().unwrap();
+    assert_eq!(take(), ["render", "user", "idle"]);
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn effects_notified_during_a_flush_run_in_the_same_flush() {
+    use imports::*;
+
+    _ = Executor::init_custom_executor(TestExecutor);
+    let owner = Owner::new();
+    owner.set();
+
+    let a = RwSignal::new(0);
+    let b = RwSignal::new(0);
+    let (log, take) = log();
+    Effect::new_with_priority(EffectPriority::Idle, {
+        let log = Arc::clone(&log);
+        move || {
+            b.track();
+            log.write().unwrap().push("idle");
+        }
+    });
+    Effect::new_with_priority(EffectPriority::User, {
+        let log = Arc::clone(&log);
+        move || {
+            b.set(a.get());
+            log.write().unwrap().push("user");
+        }
+    });
+    Effect::new_with_priority(EffectPriority::Render, {
+        let log = Arc::clone(&log);
+        move || {
+            b.track();
+            log.write().unwrap().push("render");
+        }
+    });
+    TestExecutor::run_until_idle().unwrap();
+    take();
+
+    // the user effect updates `b`, so the render effect runs again before the idle one
+    a.set(1);
+    let polls = TestExecutor::run_until_idle().unwrap();
+    assert_eq!(take(), ["user", "render", "idle"]);
+    assert_eq!(polls, 1);
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn flushing_runs_queued_effects_immediately() {
+    use imports::*;
+
+    _ = Executor::init_custom_executor(TestExecutor);
+    let owner = Owner::new();
+    owner.set();
+
+    let a = RwSignal::new(0);
+    let (log, take) = log();
+    Effect::new_with_priority(EffectPriority::Render, move || {
+        log.write()
+            .unwrap()
+            .push(if a.get() == 0 { "zero" } else { "one" });
+    });
+    flush_effects();
+    assert_eq!(take(), ["zero"]);
+
+    a.set(1);
+    flush_effects();
+    assert_eq!(take(), ["one"]);
+    // there is nothing left to do on the next tick
+    TestExecutor::run_until_idle().unwrap();
+    assert!(take().is_empty());
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn paused_owners_defer_queued_effects() {
+    use imports::*;
+
+    _ = Executor::init_custom_executor(TestExecutor);
+    let owner = Owner::new();
+    owner.set();
+
+    let a = RwSignal::new(0);
+    let (log, take) = log();
+    let child = owner.child();
+    child.with(|| {
+        Effect::new_with_priority(EffectPriority::User, move || {
+            a.track();
+            log.write().unwrap().push("ran");
+        })
+    });
+    TestExecutor::run_until_idle().unwrap();
+    assert_eq!(take(), ["ran"]);
+
+    child.pause();
+    a.set(1);
+    TestExecutor::run_until_idle().unwrap();
+    assert!(take().is_empty());
+
+    child.resume();
+    TestExecutor::run_until_idle().unwrap();
+    assert_eq!(take(), ["ran"]);
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn a_panicking_effect_does_not_stop_later_flushes() {
+    use imports::*;
+    use std::panic::{self, AssertUnwindSafe};
+
+    _ = Executor::init_custom_executor(TestExecutor);
+    let owner = Owner::new();
+    owner.set();
+
+    let a = RwSignal::new(0);
+    let (log, take) = log();
+    Effect::new_with_priority(EffectPriority::Render, move || {
+        if a.get() == 1 {
+            panic!("effect failed");
+        }
+        log.write().unwrap().push("ran");
+    });
+    flush_effects();
+    assert_eq!(take(), ["ran"]);
+
+    a.set(1);
+    let result = panic::catch_unwind(AssertUnwindSafe(flush_effects));
+    assert!(result.is_err());
+
+    // the effect is still registered, and the queue can be flushed again
+    a.set(2);
+    flush_effects();
+    assert_eq!(take(), ["ran"]);
+}
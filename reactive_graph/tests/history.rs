@@ -0,0 +1,160 @@
+use reactive_graph::{
+    computed::ArcMemo,
+    effect::batch,
+    owner::Owner,
+    signal::{ArcHistorySignal, HistorySignal},
+    traits::{Get, Set, Update, UpdateUntracked},
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+#[test]
+fn undo_and_redo() {
+    let a = ArcHistorySignal::new(0);
+    assert!(!a.undo());
+    a.set(1);
+    a.set(2);
+    a.update(|n| *n += 1);
+    assert_eq!(a.get(), 3);
+
+    assert!(a.undo());
+    assert_eq!(a.get(), 2);
+    assert!(a.undo());
+    assert!(a.undo());
+    assert_eq!(a.get(), 0);
+    assert!(!a.undo());
+
+    assert!(a.redo());
+    assert!(a.redo());
+    assert_eq!(a.get(), 2);
+
+    // a new write forgets the changes that were undone
+    a.set(10);
+    assert!(!a.can_redo());
+    assert!(!a.redo());
+    assert!(a.undo());
+    assert_eq!(a.get(), 2);
+}
+
+#[test]
+fn untracked_writes_are_not_recorded() {
+    let a = ArcHistorySignal::new(0);
+    a.set(1);
+    a.update_untracked(|n| *n = 2);
+    assert!(a.undo());
+    assert_eq!(a.get(), 0);
+    assert!(!a.can_undo());
+}
+
+#[test]
+fn writes_that_do_not_notify_are_not_recorded() {
+    let a = ArcHistorySignal::new(0);
+    a.set(1);
+    assert!(a.undo());
+
+    a.maybe_update(|n| {
+        *n = 5;
+        false
+    });
+    assert!(a.can_redo());
+    assert!(!a.can_undo());
+    assert_eq!(a.get(), 5);
+}
+
+#[test]
+fn history_is_limited_to_capacity() {
+    let a = ArcHistorySignal::with_capacity(0, 2);
+    for n in 1..=5 {
+        a.set(n);
+    }
+    assert!(a.undo());
+    assert!(a.undo());
+    assert_eq!(a.get(), 3);
+    assert!(!a.undo());
+
+    let b = ArcHistorySignal::with_capacity(0, 0);
+    b.set(1);
+    assert!(!b.can_undo());
+}
+
+#[test]
+fn can_undo_and_can_redo_are_reactive() {
+    let owner = Owner::new();
+    owner.set();
+
+    let a = HistorySignal::new(0);
+    let runs = Arc::new(AtomicUsize::new(0));
+    let state = ArcMemo::new({
+        let runs = Arc::clone(&runs);
+        move |_| {
+            runs.fetch_add(1, Ordering::Relaxed);
+            (a.can_undo(), a.can_redo())
+        }
+    });
+    assert_eq!(state.get(), (false, false));
+
+    a.set(1);
+    assert_eq!(state.get(), (true, false));
+    a.undo();
+    assert_eq!(state.get(), (false, true));
+    a.redo();
+    assert_eq!(state.get(), (true, false));
+    a.clear_history();
+    assert_eq!(state.get(), (false, false));
+    assert_eq!(runs.load(Ordering::Relaxed), 5);
+
+    // writes that do not change whether it is possible to undo or redo do not notify
+    a.set(2);
+    assert_eq!(state.get(), (true, false));
+    a.set(3);
+    assert_eq!(state.get(), (true, false));
+    assert_eq!(runs.load(Ordering::Relaxed), 6);
+}
+
+#[test]
+fn grouped_writes_are_one_entry() {
+    let owner = Owner::new();
+    owner.set();
+
+    let a = HistorySignal::new(String::new());
+    a.set("a".to_string());
+    a.group(|| {
+        a.update(|s| s.push('b'));
+        a.group(|| a.update(|s| s.push('c')));
+        a.update(|s| s.push('d'));
+    });
+    assert_eq!(a.get(), "abcd");
+
+    assert!(a.undo());
+    assert_eq!(a.get(), "a");
+    assert!(a.redo());
+    assert_eq!(a.get(), "abcd");
+
+    // writes after the group are recorded separately
+    a.update(|s| s.push('e'));
+    assert!(a.undo());
+    assert_eq!(a.get(), "abcd");
+}
+
+#[test]
+fn batched_writes_are_one_entry() {
+    let a = ArcHistorySignal::new(0);
+    let b = ArcHistorySignal::new(0);
+    batch(|| {
+        a.set(1);
+        b.set(1);
+        a.set(2);
+        batch(|| a.set(3));
+    });
+    a.set(4);
+
+    assert!(a.undo());
+    assert_eq!(a.get(), 3);
+    assert!(a.undo());
+    assert_eq!(a.get(), 0);
+    assert!(!a.undo());
+    assert!(b.undo());
+    assert_eq!(b.get(), 0);
+}
@@ -0,0 +1,254 @@
+#[cfg(feature = "introspection")]
+use reactive_graph::{
+    computed::ArcMemo,
+    graph::ToAnySource,
+    introspection::{self, GraphEvent, NodeKind},
+    owner::Owner,
+    prelude::*,
+    signal::ArcRwSignal,
+};
+
+/// Drains the events that have already been sent about the given ids, as `(event, id)` pairs.
+#[cfg(feature = "introspection")]
+fn drain(
+    events: &mut futures::channel::mpsc::UnboundedReceiver<GraphEvent>,
+    ids: &[usize],
+) -> Vec<(&'static str, usize)> {
+    std::iter::from_fn(|| events.try_next().ok().flatten())
+        .filter_map(|event| match event {
+            GraphEvent::OwnerCreated { id, .. } => Some(("owner created", id)),
+            GraphEvent::OwnerDisposed { id } => Some(("owner disposed", id)),
+            GraphEvent::NodeCreated { id, .. } => Some(("created", id)),
+            GraphEvent::NodeDisposed { id } => Some(("disposed", id)),
+            GraphEvent::MarkedDirty { id } => Some(("dirty", id)),
+            GraphEvent::Rerun { id } => Some(("rerun", id)),
+            _ => None,
+        })
+        .filter(|(_, id)| ids.contains(id))
+        .collect()
+}
+
+#[cfg(feature = "introspection")]
+#[test]
+fn snapshot_lists_owners_nodes_and_edges() {
+    let owner = Owner::new();
+    owner.set();
+    let child = owner.child();
+
+    let signal = ArcRwSignal::new(1);
+    let memo = child.with(|| {
+        ArcMemo::new({
+            let signal = signal.clone();
+            move |_| signal.get() * 2
+        })
+    });
+    assert_eq!(memo.get(), 2);
+    let (signal_id, memo_id) = (
+        signal.to_any_source().debug_id(),
+        memo.to_any_source().debug_id(),
+    );
+
+    let snapshot = introspection::snapshot();
+    let root = snapshot.owner(owner.debug_id()).unwrap();
+    assert!(root.children.contains(&child.debug_id()));
+    assert_eq!(root.nodes, [signal_id]);
+    assert_eq!(
+        snapshot.owner(child.debug_id()).unwrap().parent,
+        Some(owner.debug_id())
+    );
+    assert_eq!(snapshot.owner(child.debug_id()).unwrap().nodes, [memo_id]);
+
+    let signal_node = snapshot.node(signal_id).unwrap();
+    assert_eq!(signal_node.kind, NodeKind::Signal);
+    assert_eq!(signal_node.subscribers, [memo_id]);
+    assert!(signal_node
+        .defined_at
+        .unwrap()
+        .file()
+        .ends_with("introspection.rs"));
+    let memo_node = snapshot.node(memo_id).unwrap();
+    assert_eq!(memo_node.kind, NodeKind::Memo);
+    assert_eq!(memo_node.sources, [signal_id]);
+
+    drop((signal, memo));
+    let snapshot = introspection::snapshot();
+    assert!(snapshot.node(signal_id).is_none());
+    assert!(snapshot.node(memo_id).is_none());
+}
+
+#[cfg(feature = "introspection")]
+#[test]
+fn events_follow_a_node_through_its_life() {
+    let owner = Owner::new();
+    owner.set();
+    let mut events = introspection::events();
+
+    let signal = ArcRwSignal::new(1);
+    let memo = ArcMemo::new({
+        let signal = signal.clone();
+        move |_| signal.get() * 2
+    });
+    let ids = [
+        signal.to_any_source().debug_id(),
+        memo.to_any_source().debug_id(),
+    ];
+    let [signal_id, memo_id] = ids;
+
+    assert_eq!(memo.get(), 2);
+    signal.set(2);
+    assert_eq!(memo.get(), 4);
+    drop((signal, memo));
+
+    assert_eq!(
+        drain(&mut events, &ids),
+        [
+            ("created", signal_id),
+            ("created", memo_id),
+            ("rerun", memo_id),
+            ("dirty", signal_id),
+            ("dirty", memo_id),
+            ("rerun", memo_id),
+            // the memo's function holds the last reference to the signal
+            ("disposed", signal_id),
+            ("disposed", memo_id),
+        ]
+    );
+}
+
+#[cfg(feature = "introspection")]
+#[test]
+fn owners_are_reported_until_they_are_dropped() {
+    let owner = Owner::new();
+    let mut events = introspection::events();
+
+    let child = owner.child();
+    let id = child.debug_id();
+    assert!(introspection::snapshot().owner(id).is_some());
+    drop(child);
+    assert!(introspection::snapshot().owner(id).is_none());
+
+    assert_eq!(
+        drain(&mut events, &[id]),
+        [("owner created", id), ("owner disposed", id)]
+    );
+}
+
+#[cfg(all(feature = "introspection", feature = "effects"))]
+#[tokio::test]
+async fn effects_are_listed_with_their_sources() {
+    use any_spawner::Executor;
+    use reactive_graph::effect::Effect;
+
+    _ = Executor::init_tokio();
+    let owner = Owner::new();
+    owner.set();
+
+    let signal = ArcRwSignal::new(0);
+    let effect = Effect::new_sync({
+        let signal = signal.clone();
+        move |_| {
+            signal.track();
+        }
+    });
+    Executor::tick().await;
+
+    let snapshot = introspection::snapshot();
+    let signal_node = snapshot.node(signal.to_any_source().debug_id()).unwrap();
+    let [effect_id] = signal_node.subscribers[..] else {
+        panic!("expected the effect to subscribe to the signal");
+    };
+    let effect_node = snapshot.node(effect_id).unwrap();
+    assert_eq!(effect_node.kind, NodeKind::Effect);
+    assert_eq!(effect_node.sources, [signal_node.id]);
+    assert!(effect_node
+        .defined_at
+        .unwrap()
+        .file()
+        .ends_with("introspection.rs"));
+    assert!(snapshot
+        .owner(owner.debug_id())
+        .unwrap()
+        .nodes
+        .contains(&effect_id));
+
+    effect.stop();
+    assert!(introspection::snapshot().node(effect_id).is_none());
+}
+
+#[cfg(feature = "introspection")]
+#[test]
+fn owner_snapshots_export_as_dot_and_json() {
+    let outside = ArcRwSignal::new(1);
+    let owner = Owner::new();
+    let memo = owner.with(|| {
+        ArcMemo::new({
+            let outside = outside.clone();
+            move |_| outside.get() + 1
+        })
+    });
+    // created under another owner, and unrelated to the memo
+    let _unrelated = Owner::new().with(|| ArcRwSignal::new(0));
+    assert_eq!(memo.get(), 2);
+    let (signal_id, memo_id) = (
+        outside.to_any_source().debug_id(),
+        memo.to_any_source().debug_id(),
+    );
+
+    let snapshot = introspection::snapshot_owner(&owner);
+    assert_eq!(snapshot.owners.len(), 2);
+    assert_eq!(
+        snapshot
+            .nodes
+            .iter()
+            .map(|node| node.id)
+            .collect::<Vec<_>>(),
+        [signal_id, memo_id]
+    );
+    assert_eq!(snapshot.edges(), [(signal_id, memo_id)]);
+
+    let dot = snapshot.to_dot();
+    assert!(dot.starts_with("digraph {\n"));
+    assert!(dot.contains(&format!("subgraph cluster_{} {{", owner.debug_id())));
+    assert!(dot.contains(&format!(
+        "n{memo_id} [label=\"{}\\n",
+        snapshot.node(memo_id).unwrap().label()
+    )));
+    assert!(dot.contains(&format!("n{signal_id} -> n{memo_id};")));
+
+    let json = snapshot.to_json();
+    assert!(json.starts_with("{\"owners\":[{\"id\":"));
+    assert!(json.contains(&format!(
+        "{{\"id\":{memo_id},\"type\":\"Memo\",\"label\":\"Memo \
+         (introspection.rs:"
+    )));
+    assert!(json.ends_with(&format!(
+        "\"edges\":[{{\"source\":{signal_id},\"subscriber\":{memo_id}}}]}}"
+    )));
+}
+
+#[cfg(feature = "introspection")]
+#[test]
+fn diffing_snapshots_shows_changed_dependencies() {
+    let owner = Owner::new();
+    owner.set();
+    let use_a = ArcRwSignal::new(true);
+    let (a, b) = (ArcRwSignal::new(1), ArcRwSignal::new(2));
+    let memo = ArcMemo::new({
+        let (use_a, a, b) = (use_a.clone(), a.clone(), b.clone());
+        move |_| if use_a.get() { a.get() } else { b.get() }
+    });
+    assert_eq!(memo.get(), 1);
+    let before = introspection::snapshot_owner(&owner);
+    assert!(before
+        .diff(&introspection::snapshot_owner(&owner))
+        .is_empty());
+
+    use_a.set(false);
+    assert_eq!(memo.get(), 2);
+    let diff = before.diff(&introspection::snapshot_owner(&owner));
+    let [a_id, b_id] = [a, b].map(|signal| signal.to_any_source().debug_id());
+    let memo_id = memo.to_any_source().debug_id();
+    assert_eq!(diff.added_edges, [(b_id, memo_id)]);
+    assert_eq!(diff.removed_edges, [(a_id, memo_id)]);
+    assert!(diff.added_nodes.is_empty() && diff.removed_nodes.is_empty());
+}
@@ -546,3 +546,41 @@ fn memo_updates_even_if_not_read_until_later() {
     println!("read memo 2");
     assert_eq!(second_memo.get(), false);
 }
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "reads Memo defined at")]
+fn memo_that_reads_itself_panics_with_the_cycle() {
+    let owner = Owner::new();
+    owner.set();
+
+    let a_slot = Arc::new(RwLock::new(None::<ArcMemo<i32>>));
+    let b = ArcMemo::new({
+        let a_slot = Arc::clone(&a_slot);
+        move |_| a_slot.read().unwrap().as_ref().map_or(0, |a| a.get()) + 1
+    });
+    let a = ArcMemo::new({
+        let b = b.clone();
+        move |_| b.get() + 1
+    });
+    *a_slot.write().unwrap() = Some(a.clone());
+
+    a.get();
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "writes Signal defined at")]
+fn memo_that_writes_its_source_panics_with_the_cycle() {
+    let owner = Owner::new();
+    owner.set();
+
+    let a = RwSignal::new(1);
+    let b = Memo::new(move |_| {
+        let value = a.get();
+        a.set(value + 1);
+        value
+    });
+
+    b.get();
+}
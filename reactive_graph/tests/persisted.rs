@@ -0,0 +1,153 @@
+#[cfg(feature = "serde")]
+pub mod imports {
+    pub use any_spawner::{Executor, TestExecutor};
+    pub use reactive_graph::{
+        computed::set_timer,
+        owner::Owner,
+        prelude::*,
+        signal::{
+            ArcPersistedSignal, FileBackend, MemoryBackend, PersistedSignal,
+            PersistenceBackend, PersistenceError,
+        },
+    };
+    pub use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    /// Counts how many times values are stored.
+    #[derive(Clone, Default)]
+    pub struct CountingBackend {
+        pub inner: MemoryBackend,
+        pub stores: Arc<AtomicUsize>,
+    }
+
+    impl PersistenceBackend for CountingBackend {
+        fn load(&self, key: &str) -> Result<Option<String>, PersistenceError> {
+            self.inner.load(key)
+        }
+
+        fn store(
+            &self,
+            key: &str,
+            value: &str,
+        ) -> Result<(), PersistenceError> {
+            self.stores.fetch_add(1, Ordering::Relaxed);
+            self.inner.store(key, value)
+        }
+    }
+
+    pub fn init() -> Owner {
+        _ = Executor::init_custom_executor(TestExecutor);
+        _ = set_timer(TestExecutor::sleep);
+        let owner = Owner::new();
+        owner.set();
+        owner
+    }
+
+    pub fn wait(millis: u64) {
+        TestExecutor::advance(Duration::from_millis(millis)).unwrap();
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn loads_the_stored_value_or_the_default() {
+    use imports::*;
+
+    let _owner = init();
+    let backend = MemoryBackend::new();
+    backend
+        .store("filters", r#"["errors","warnings"]"#)
+        .unwrap();
+    backend.store("broken", "{").unwrap();
+
+    let filters = ArcPersistedSignal::new(
+        backend.clone(),
+        "filters",
+        Vec::<String>::new(),
+    );
+    assert_eq!(filters.get(), ["errors", "warnings"]);
+    let missing = ArcPersistedSignal::new(backend.clone(), "missing", 1);
+    assert_eq!(missing.get(), 1);
+    // a value that cannot be loaded is replaced by the default
+    let broken = ArcPersistedSignal::new(backend, "broken", 2);
+    assert_eq!(broken.get(), 2);
+}
+
+#[cfg(all(feature = "serde", feature = "effects"))]
+#[test]
+fn changes_are_saved_once_they_stop() {
+    use imports::*;
+
+    let _owner = init();
+    let backend = CountingBackend::default();
+    let width = PersistedSignal::new_with_debounce(
+        backend.clone(),
+        "width",
+        30,
+        Duration::from_millis(100),
+    );
+    wait(0);
+
+    width.set(31);
+    wait(60);
+    width.set(32);
+    wait(60);
+    assert_eq!(backend.inner.get("width"), None);
+
+    wait(40);
+    assert_eq!(backend.inner.get("width").as_deref(), Some("32"));
+    assert_eq!(backend.stores.load(Ordering::Relaxed), 1);
+
+    // nothing is saved again if there are no more changes
+    wait(1000);
+    assert_eq!(backend.stores.load(Ordering::Relaxed), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn pending_changes_are_saved_on_cleanup() {
+    use imports::*;
+
+    let owner = init();
+    let backend = CountingBackend::default();
+    let saved = PersistedSignal::new(backend.clone(), "saved", 0);
+    let unchanged = PersistedSignal::new(backend.clone(), "unchanged", 0);
+    saved.set(1);
+    _ = unchanged.get();
+
+    owner.cleanup();
+    assert_eq!(backend.inner.get("saved").as_deref(), Some("1"));
+    assert_eq!(backend.inner.get("unchanged"), None);
+    assert_eq!(backend.stores.load(Ordering::Relaxed), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn file_backend_round_trip() {
+    use imports::*;
+
+    let _owner = init();
+    let dir = std::env::temp_dir()
+        .join(format!("reactive_graph_persisted_{}", std::process::id()));
+    let backend = FileBackend::new(&dir);
+    assert_eq!(backend.load("state").unwrap(), None);
+
+    let state = ArcPersistedSignal::new(
+        backend.clone(),
+        "state",
+        ("tree".to_string(), 40),
+    );
+    state.update(|(_, width)| *width = 25);
+    state.flush().unwrap();
+    assert!(dir.join("state.json").exists());
+
+    let state = ArcPersistedSignal::new(backend, "state", (String::new(), 0));
+    assert_eq!(state.get(), ("tree".to_string(), 25));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,161 @@
+pub mod imports {
+    pub use any_spawner::{Executor, TestExecutor};
+    pub use futures::{channel::mpsc, FutureExt};
+    pub use reactive_graph::{
+        computed::{suspense::SuspenseContext, ArcMemo},
+        owner::{provide_context, Owner},
+        prelude::*,
+        signal::{ArcRwSignal, ArcStreamSignal, StreamSignal, StreamStatus},
+    };
+    pub use slotmap::SlotMap;
+    pub use std::future::IntoFuture;
+
+    pub fn init() -> Owner {
+        _ = Executor::init_custom_executor(TestExecutor);
+        let owner = Owner::new();
+        owner.set();
+        owner
+    }
+
+    pub fn tick() {
+        TestExecutor::run_until_idle().unwrap();
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("lost connection")]
+    pub struct LostConnection;
+}
+
+#[test]
+fn holds_the_latest_item() {
+    use imports::*;
+
+    let _owner = init();
+    let (tx, rx) = mpsc::unbounded();
+    let latest = StreamSignal::new(rx);
+    let status = ArcMemo::new(move |_| latest.status());
+    assert_eq!(latest.get(), None);
+    assert!(latest.is_pending());
+
+    tx.unbounded_send(1).unwrap();
+    tick();
+    assert_eq!(latest.get(), Some(1));
+    assert_eq!(status.get(), StreamStatus::Streaming);
+
+    tx.unbounded_send(2).unwrap();
+    tx.unbounded_send(3).unwrap();
+    tick();
+    assert_eq!(latest.get(), Some(3));
+
+    drop(tx);
+    tick();
+    assert_eq!(status.get(), StreamStatus::Ended);
+    assert!(latest.is_ended());
+    assert_eq!(latest.get(), Some(3));
+}
+
+#[test]
+fn keeps_the_last_error_until_the_next_item() {
+    use imports::*;
+
+    let _owner = init();
+    let (tx, rx) = mpsc::unbounded();
+    let latest = ArcStreamSignal::new_fallible(rx);
+    assert!(latest.error().is_none());
+
+    tx.unbounded_send(Ok(1)).unwrap();
+    tx.unbounded_send(Err(LostConnection)).unwrap();
+    tick();
+    assert_eq!(latest.get(), Some(1));
+    assert_eq!(latest.error().unwrap().to_string(), "lost connection");
+    assert_eq!(latest.status(), StreamStatus::Streaming);
+
+    tx.unbounded_send(Ok(2)).unwrap();
+    tick();
+    assert_eq!(latest.get(), Some(2));
+    assert!(latest.error().is_none());
+}
+
+#[test]
+fn awaiting_waits_for_the_first_item() {
+    use imports::*;
+
+    let _owner = init();
+    let (tx, rx) = mpsc::unbounded();
+    let latest = ArcStreamSignal::new(rx);
+    let mut first = latest.clone().into_future();
+    tick();
+    assert_eq!(first.as_mut().now_or_never(), None);
+
+    tx.unbounded_send("a").unwrap();
+    tick();
+    assert_eq!(first.now_or_never(), Some(Some("a")));
+
+    // a stream that ends without any items resolves to `None`
+    let empty = ArcStreamSignal::new(futures::stream::empty::<()>());
+    tick();
+    assert_eq!(empty.into_future().now_or_never(), Some(None));
+}
+
+#[test]
+fn cancelled_on_cleanup() {
+    use imports::*;
+
+    let owner = init();
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let child = owner.child();
+    let latest = child.with(|| ArcStreamSignal::new(rx));
+    let ready = latest.ready();
+    tick();
+    assert!(!tx.is_closed());
+
+    owner.cleanup();
+    tick();
+    // the stream has been dropped
+    assert!(tx.is_closed());
+    assert!(ready.now_or_never().is_some());
+    assert_eq!(latest.get_untracked(), None);
+}
+
+#[test]
+fn cancelled_when_dropped() {
+    use imports::*;
+
+    let _owner = init();
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let latest = ArcStreamSignal::new(rx);
+    let clone = latest.clone();
+    tick();
+
+    drop(latest);
+    tick();
+    assert!(!tx.is_closed());
+    drop(clone);
+    tick();
+    assert!(tx.is_closed());
+}
+
+#[test]
+fn suspense_waits_for_the_first_item() {
+    use imports::*;
+
+    let owner = init();
+    let tasks = ArcRwSignal::new(SlotMap::new());
+    provide_context(SuspenseContext {
+        tasks: tasks.clone(),
+    });
+
+    let (tx, rx) = mpsc::unbounded();
+    let latest = ArcStreamSignal::new(rx);
+    assert_eq!(latest.get(), None);
+    assert!(!tasks.with_untracked(|tasks| tasks.is_empty()));
+
+    tx.unbounded_send(1).unwrap();
+    tick();
+    assert!(tasks.with_untracked(|tasks| tasks.is_empty()));
+
+    // once the first item has arrived, reading it does not suspend again
+    assert_eq!(latest.get(), Some(1));
+    assert!(tasks.with_untracked(|tasks| tasks.is_empty()));
+    drop(owner);
+}
@@ -0,0 +1,88 @@
+use any_spawner::{Executor, TestExecutor};
+use reactive_graph::{
+    computed::ArcAsyncDerived, owner::Owner, prelude::*, signal::ArcRwSignal,
+};
+use std::time::Duration;
+
+#[cfg(feature = "effects")]
+#[test]
+fn effects_run_when_the_executor_is_driven() {
+    use reactive_graph::effect::Effect;
+    use std::sync::{Arc, RwLock};
+
+    _ = Executor::init_custom_executor(TestExecutor);
+    let owner = Owner::new();
+    owner.set();
+
+    let signal = ArcRwSignal::new(0);
+    let seen = Arc::new(RwLock::new(Vec::new()));
+    let _effect = Effect::new({
+        let (signal, seen) = (signal.clone(), Arc::clone(&seen));
+        move |_| seen.write().unwrap().push(signal.get())
+    });
+    assert!(seen.read().unwrap().is_empty());
+    TestExecutor::run_until_idle().unwrap();
+    assert_eq!(*seen.read().unwrap(), [0]);
+
+    signal.set(1);
+    signal.set(2);
+    assert_eq!(*seen.read().unwrap(), [0]);
+    TestExecutor::run_until_idle().unwrap();
+    assert_eq!(*seen.read().unwrap(), [0, 2]);
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn effects_that_update_each_other_never_settle() {
+    use reactive_graph::effect::Effect;
+
+    _ = Executor::init_custom_executor(TestExecutor);
+    let owner = Owner::new();
+    owner.set();
+    TestExecutor::set_poll_limit(100);
+
+    let (a, b) = (ArcRwSignal::new(0), ArcRwSignal::new(0));
+    let _a_to_b = Effect::new({
+        let (a, b) = (a.clone(), b.clone());
+        move |_| b.set(a.get() + 1)
+    });
+    let _b_to_a = Effect::new({
+        let (a, b) = (a.clone(), b.clone());
+        move |_| a.set(b.get() + 1)
+    });
+
+    let unsettled = TestExecutor::run_until_idle().unwrap_err();
+    assert_eq!(unsettled.polls, 100);
+    assert!(unsettled.ready > 0);
+    assert!(a.get_untracked() > 1);
+}
+
+#[test]
+fn async_derived_resolves_on_the_virtual_clock() {
+    _ = Executor::init_custom_executor(TestExecutor);
+    let owner = Owner::new();
+    owner.set();
+
+    let signal = ArcRwSignal::new(1);
+    let derived = ArcAsyncDerived::new({
+        let signal = signal.clone();
+        move || {
+            let value = signal.get();
+            async move {
+                TestExecutor::sleep(Duration::from_secs(1)).await;
+                value * 10
+            }
+        }
+    });
+    TestExecutor::run_until_idle().unwrap();
+    assert_eq!(derived.get(), None);
+
+    TestExecutor::advance(Duration::from_millis(999)).unwrap();
+    assert_eq!(derived.get(), None);
+    TestExecutor::advance(Duration::from_millis(1)).unwrap();
+    assert_eq!(derived.get(), Some(10));
+
+    signal.set(2);
+    TestExecutor::advance(Duration::from_secs(1)).unwrap();
+    assert_eq!(derived.get(), Some(20));
+}
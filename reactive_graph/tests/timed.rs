@@ -0,0 +1,123 @@
+#[cfg(feature = "effects")]
+pub mod imports {
+    pub use any_spawner::{Executor, TestExecutor};
+    pub use reactive_graph::{
+        computed::{arc_debounced, arc_throttled, debounced, set_timer},
+        owner::Owner,
+        prelude::*,
+        signal::RwSignal,
+    };
+    pub use std::time::Duration;
+
+    pub fn init() -> Owner {
+        _ = Executor::init_custom_executor(TestExecutor);
+        _ = set_timer(TestExecutor::sleep);
+        let owner = Owner::new();
+        owner.set();
+        owner
+    }
+
+    pub fn wait(millis: u64) {
+        TestExecutor::advance(Duration::from_millis(millis)).unwrap();
+    }
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn debounced_signal_waits_for_changes_to_stop() {
+    use imports::*;
+
+    let _owner = init();
+    let source = RwSignal::new(0);
+    let debounced = debounced(source, Duration::from_millis(100));
+    wait(0);
+
+    source.set(1);
+    wait(60);
+    source.set(2);
+    wait(60);
+    assert_eq!(debounced.get(), 0);
+    source.set(3);
+    wait(99);
+    assert_eq!(debounced.get(), 0);
+    wait(1);
+    assert_eq!(debounced.get(), 3);
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn throttled_signal_updates_at_most_once_per_interval() {
+    use imports::*;
+
+    let _owner = init();
+    let source = RwSignal::new(0);
+    let throttled = arc_throttled(source, Duration::from_millis(100));
+    wait(0);
+
+    // the first change is passed on at once
+    source.set(1);
+    wait(0);
+    assert_eq!(throttled.get(), 1);
+
+    // later changes wait for the interval to end
+    source.set(2);
+    wait(10);
+    source.set(3);
+    wait(10);
+    assert_eq!(throttled.get(), 1);
+    wait(80);
+    assert_eq!(throttled.get(), 3);
+
+    // once an interval passes without changes, the next change is passed on at once
+    wait(100);
+    source.set(4);
+    wait(0);
+    assert_eq!(throttled.get(), 4);
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn debounced_signal_stops_when_its_owner_is_cleaned_up() {
+    use imports::*;
+
+    let owner = init();
+    let source = RwSignal::new(0);
+    let child = owner.child();
+    let debounced =
+        child.with(|| arc_debounced(source, Duration::from_millis(100)));
+    wait(0);
+
+    source.set(1);
+    wait(50);
+    owner.cleanup();
+    wait(100);
+    assert_eq!(debounced.get_untracked(), 0);
+}
+
+#[cfg(feature = "effects")]
+#[test]
+fn debounced_signal_keeps_only_one_wait_running() {
+    use imports::*;
+
+    let _owner = init();
+    let source = RwSignal::new(0);
+    let owner = Owner::new();
+    let debounced =
+        owner.with(|| arc_debounced(source, Duration::from_millis(100)));
+    wait(0);
+    // the effect that follows the source
+    let following = TestExecutor::pending();
+
+    // each change aborts the wait for the previous one
+    for value in 1..=5 {
+        source.set(value);
+        wait(10);
+        assert_eq!(TestExecutor::pending(), following + 1);
+    }
+
+    // cleaning up the owner aborts the wait that is still running
+    owner.cleanup();
+    wait(0);
+    assert_eq!(TestExecutor::pending(), 0);
+    assert_eq!(debounced.get_untracked(), 0);
+}
@@ -0,0 +1,30 @@
+#[cfg(all(feature = "effects", feature = "tokio"))]
+pub mod imports {
+    pub use any_spawner::Executor;
+    pub use reactive_graph::{
+        computed::arc_debounced, owner::Owner, prelude::*, signal::ArcRwSignal,
+    };
+    pub use std::time::Duration;
+}
+
+#[cfg(all(feature = "effects", feature = "tokio"))]
+#[tokio::test]
+async fn tokio_is_the_default_timer() {
+    use imports::*;
+
+    _ = Executor::init_tokio();
+    let owner = Owner::new();
+    owner.set();
+
+    // no timer has been set
+    let source = ArcRwSignal::new(0);
+    let debounced = arc_debounced(source.clone(), Duration::from_millis(10));
+    Executor::tick().await;
+
+    source.set(1);
+    Executor::tick().await;
+    assert_eq!(debounced.get_untracked(), 0);
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(debounced.get_untracked(), 1);
+}
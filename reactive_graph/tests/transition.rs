@@ -0,0 +1,175 @@
+use any_spawner::Executor;
+use futures::channel::oneshot;
+use reactive_graph::{
+    computed::{ArcAsyncDerived, ScopedFuture},
+    owner::Owner,
+    traits::WithUntracked,
+    transition::AsyncTransition,
+};
+use std::sync::{Arc, Mutex};
+
+/// Creates an async derived that resolves once the returned sender is used.
+fn gated() -> (oneshot::Sender<()>, ArcAsyncDerived<i32>) {
+    let (tx, rx) = oneshot::channel();
+    let rx = Arc::new(Mutex::new(Some(rx)));
+    let derived = ArcAsyncDerived::new(move || {
+        let rx = rx.lock().unwrap().take();
+        async move {
+            if let Some(rx) = rx {
+                _ = rx.await;
+            }
+            42
+        }
+    });
+    (tx, derived)
+}
+
+async fn settle() {
+    for _ in 0..5 {
+        Executor::tick().await;
+    }
+}
+
+#[tokio::test]
+async fn transition_waits_for_async_derived_created_during_it() {
+    _ = Executor::init_tokio();
+    let owner = Owner::new();
+    owner.set();
+
+    let gate = Arc::new(Mutex::new(None));
+    let run = tokio::spawn(owner.with(|| {
+        ScopedFuture::new(AsyncTransition::run({
+            let gate = Arc::clone(&gate);
+            move || {
+                let (tx, derived) = gated();
+                *gate.lock().unwrap() = Some(tx);
+                async move { derived }
+            }
+        }))
+    }));
+
+    settle().await;
+    assert!(!run.is_finished());
+    gate.lock().unwrap().take().unwrap().send(()).unwrap();
+    let derived = run.await.unwrap();
+    assert_eq!(derived.await, 42);
+}
+
+#[tokio::test]
+async fn concurrent_transitions_do_not_share_resources() {
+    _ = Executor::init_tokio();
+
+    let start = |owner: Owner, gate: Arc<Mutex<Option<_>>>| {
+        tokio::spawn(owner.with(|| {
+            ScopedFuture::new(AsyncTransition::run(move || {
+                let (tx, derived) = gated();
+                *gate.lock().unwrap() = Some(tx);
+                async move {
+                    // give the other transition a chance to run in the meantime
+                    Executor::tick().await;
+                    derived
+                }
+            }))
+        }))
+    };
+    let (gate_a, gate_b) =
+        (Arc::new(Mutex::new(None)), Arc::new(Mutex::new(None)));
+    let run_a = start(Owner::new(), Arc::clone(&gate_a));
+    let run_b = start(Owner::new(), Arc::clone(&gate_b));
+
+    settle().await;
+    gate_b.lock().unwrap().take().unwrap().send(()).unwrap();
+    settle().await;
+    assert!(run_b.is_finished());
+    assert!(!run_a.is_finished());
+
+    gate_a.lock().unwrap().take().unwrap().send(()).unwrap();
+    run_a.await.unwrap();
+}
+
+#[tokio::test]
+async fn cancelled_transition_stops_waiting() {
+    _ = Executor::init_tokio();
+    let owner = Owner::new();
+    owner.set();
+
+    let current = Arc::new(Mutex::new(None));
+    let run = tokio::spawn(owner.with(|| {
+        ScopedFuture::new(AsyncTransition::run({
+            let current = Arc::clone(&current);
+            move || {
+                let (tx, derived) = gated();
+                *current.lock().unwrap() = AsyncTransition::current();
+                async move { (tx, derived) }
+            }
+        }))
+    }));
+
+    settle().await;
+    assert!(!run.is_finished());
+    let transition = current.lock().unwrap().take().unwrap();
+    transition.cancel();
+    let (_tx, derived) = run.await.unwrap();
+    assert!(derived.with_untracked(Option::is_none));
+    assert!(AsyncTransition::current().is_none());
+}
+
+#[tokio::test]
+async fn cancelling_a_transition_cancels_nested_transitions() {
+    _ = Executor::init_tokio();
+    let owner = Owner::new();
+    owner.set();
+
+    let (outer, inner) = owner
+        .with(|| {
+            ScopedFuture::new(AsyncTransition::run(|| async {
+                let outer = AsyncTransition::current().unwrap();
+                let inner = AsyncTransition::run(|| async {
+                    AsyncTransition::current().unwrap()
+                })
+                .await;
+                (outer, inner)
+            }))
+        })
+        .await;
+
+    assert!(!inner.is_cancelled());
+    outer.cancel();
+    assert!(outer.is_cancelled());
+    assert!(inner.is_cancelled());
+}
+
+#[tokio::test]
+async fn overlapping_transitions_on_one_owner_restore_the_running_one() {
+    _ = Executor::init_tokio();
+    let owner = Owner::new();
+
+    let start = |owner: &Owner| {
+        let (tx, rx) = oneshot::channel::<()>();
+        let run = tokio::spawn(owner.with(|| {
+            ScopedFuture::new(AsyncTransition::run(|| async move {
+                _ = rx.await;
+                AsyncTransition::current().unwrap()
+            }))
+        }));
+        (tx, run)
+    };
+    let current = || owner.with(AsyncTransition::current);
+
+    let (gate_a, run_a) = start(&owner);
+    settle().await;
+    let (gate_b, run_b) = start(&owner);
+    settle().await;
+
+    // the first transition finishing does not replace the one that is still running
+    gate_a.send(()).unwrap();
+    let a = run_a.await.unwrap();
+    current().unwrap().cancel();
+    assert!(!a.is_cancelled());
+
+    gate_b.send(()).unwrap();
+    let b = run_b.await.unwrap();
+    assert!(b.is_cancelled());
+    // neither transition is left on the owner once both have finished
+    assert!(current().is_none());
+}
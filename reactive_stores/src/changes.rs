@@ -0,0 +1,492 @@
+use crate::{history::History, ArcStore, Store};
+use or_poisoned::OrPoisoned;
+use reactive_graph::{owner::Storage, signal::ArcTrigger};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fmt::Write as _,
+    mem,
+    sync::{Arc, Mutex, RwLock, Weak},
+};
+
+/// A single value that was added, removed, or replaced by a write to a store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    /// The location of the value in the store, as a JSON Pointer (RFC 6901), such as
+    /// `/todos/0/label`. The empty string refers to the whole store.
+    ///
+    /// Items in sequences are addressed by their index, even in keyed fields, which is also how
+    /// [`PatchDelta`](crate::PatchDelta) addresses them.
+    pub path: String,
+    /// The value before the write, or `None` if it was added.
+    pub old: Option<Value>,
+    /// The value after the write, or `None` if it was removed.
+    pub new: Option<Value>,
+}
+
+impl Change {
+    /// Converts the change into a JSON Patch operation.
+    pub fn to_operation(&self) -> PatchOperation {
+        let path = self.path.clone();
+        match (&self.old, &self.new) {
+            (_, None) => PatchOperation::Remove { path },
+            (None, Some(value)) => PatchOperation::Add {
+                path,
+                value: value.clone(),
+            },
+            (Some(_), Some(value)) => PatchOperation::Replace {
+                path,
+                value: value.clone(),
+            },
+        }
+    }
+}
+
+/// All of the changes made by one write to a store, or by one call to
+/// [`patch`](crate::Patch::patch).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChangeSet {
+    /// The changes, in the order in which they can be applied.
+    pub changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    /// Converts the changes into a JSON Patch (RFC 6902) document.
+    pub fn to_json_patch(&self) -> Vec<PatchOperation> {
+        self.changes.iter().map(Change::to_operation).collect()
+    }
+}
+
+/// An operation in a JSON Patch (RFC 6902) document.
+///
+/// This serializes to the JSON form defined by the RFC, such as
+/// `{"op":"replace","path":"/user","value":"Bob"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Adds a value to an object, or inserts it into an array.
+    Add {
+        /// Where to add the value.
+        path: String,
+        /// The value to add.
+        value: Value,
+    },
+    /// Removes a value from an object or array.
+    Remove {
+        /// The value to remove.
+        path: String,
+    },
+    /// Replaces a value.
+    Replace {
+        /// The value to replace.
+        path: String,
+        /// The new value.
+        value: Value,
+    },
+}
+
+type ChangeBuffer = Mutex<Vec<ChangeSet>>;
+
+/// Collects the changes made to a store, returned by
+/// [`ArcStore::record_changes`] or [`Store::record_changes`].
+///
+/// Changes are recorded for as long as the recorder is alive. Each recorder keeps its own log,
+/// so several recorders can be used for different purposes, such as syncing to a backend and
+/// audit logging, without taking changes from each other.
+#[derive(Debug, Default)]
+pub struct ChangeRecorder {
+    buffer: Arc<ChangeBuffer>,
+}
+
+impl ChangeRecorder {
+    /// Returns the changes recorded since the last call, one [`ChangeSet`] per write.
+    pub fn take(&self) -> Vec<ChangeSet> {
+        mem::take(&mut *self.buffer.lock().or_poisoned())
+    }
+
+    /// Returns the changes recorded since the last call, as a single JSON Patch document.
+    pub fn take_json_patch(&self) -> Vec<PatchOperation> {
+        self.take()
+            .iter()
+            .flat_map(ChangeSet::to_json_patch)
+            .collect()
+    }
+}
+
+/// The recorders attached to a store, shared between its clones.
+pub(crate) struct Recorders<T> {
+    pub(crate) snapshot: Option<fn(&T) -> Option<Value>>,
+    buffers: Vec<Weak<ChangeBuffer>>,
+    pub(crate) history: Option<Arc<Mutex<History>>>,
+}
+
+impl<T> Default for Recorders<T> {
+    fn default() -> Self {
+        Self {
+            snapshot: None,
+            buffers: Vec::new(),
+            history: None,
+        }
+    }
+}
+
+impl<T> Recorders<T> {
+    /// Snapshots the value before a write, if any recorders are alive or the store keeps a
+    /// history.
+    pub(crate) fn start(
+        this: &RwLock<Self>,
+        value: &T,
+    ) -> Option<Recording<T>> {
+        let mut this = this.write().or_poisoned();
+        let snapshot = this.snapshot?;
+        this.buffers.retain(|buffer| buffer.strong_count() > 0);
+        let buffers = this
+            .buffers
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect::<Vec<_>>();
+        let history = this.history.clone();
+        drop(this);
+        if buffers.is_empty() && history.is_none() {
+            return None;
+        }
+        Some(Recording {
+            before: snapshot(value)?,
+            snapshot,
+            buffers,
+            history,
+        })
+    }
+}
+
+/// A write that is being recorded.
+pub(crate) struct Recording<T> {
+    before: Value,
+    snapshot: fn(&T) -> Option<Value>,
+    buffers: Vec<Arc<ChangeBuffer>>,
+    history: Option<Arc<Mutex<History>>>,
+}
+
+impl<T> Recording<T> {
+    /// Compares the value after the write with the snapshot from before it, and records the
+    /// differences.
+    ///
+    /// Returns a trigger that should be notified once the value is no longer locked, if the write
+    /// made it possible or impossible to undo or redo.
+    pub(crate) fn finish(self, value: &T) -> Option<ArcTrigger> {
+        let after = (self.snapshot)(value)?;
+        let mut changes = Vec::new();
+        diff(&mut String::new(), Some(self.before), after, &mut changes);
+        if changes.is_empty() {
+            return None;
+        }
+        let change_set = ChangeSet { changes };
+        for buffer in self.buffers {
+            buffer.lock().or_poisoned().push(change_set.clone());
+        }
+        self.history
+            .and_then(|history| history.lock().or_poisoned().record(change_set))
+    }
+}
+
+/// Finds the changes that turn `old` into `new`, in an order in which they can be applied.
+fn diff(
+    path: &mut String,
+    old: Option<Value>,
+    new: Value,
+    changes: &mut Vec<Change>,
+) {
+    let change = |path: &str, old, new| Change {
+        path: path.to_string(),
+        old,
+        new,
+    };
+    match (old, new) {
+        (None, new) => changes.push(change(path, None, Some(new))),
+        (Some(Value::Object(mut old)), Value::Object(new)) => {
+            let len = path.len();
+            for (key, value) in old.iter() {
+                if !new.contains_key(key) {
+                    push_segment(path, key);
+                    changes.push(change(path, Some(value.clone()), None));
+                    path.truncate(len);
+                }
+            }
+            for (key, value) in new {
+                push_segment(path, &key);
+                diff(path, old.remove(&key), value, changes);
+                path.truncate(len);
+            }
+        }
+        (Some(Value::Array(old)), Value::Array(new)) => {
+            let len = path.len();
+            let new_len = new.len();
+            let mut old = old.into_iter();
+            for (idx, value) in new.into_iter().enumerate() {
+                _ = write!(path, "/{idx}");
+                diff(path, old.next(), value, changes);
+                path.truncate(len);
+            }
+            // remove from the end, so that each index is still valid when it is applied
+            let removed = old.collect::<Vec<_>>();
+            for (idx, value) in removed.into_iter().enumerate().rev() {
+                _ = write!(path, "/{}", new_len + idx);
+                changes.push(change(path, Some(value), None));
+                path.truncate(len);
+            }
+        }
+        (Some(old), new) => {
+            if old != new {
+                changes.push(change(path, Some(old), Some(new)));
+            }
+        }
+    }
+}
+
+/// Adds an object key to a JSON Pointer, escaping it as described in RFC 6901.
+fn push_segment(path: &mut String, key: &str) {
+    path.push('/');
+    path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+}
+
+impl<T> ArcStore<T>
+where
+    T: Serialize + 'static,
+{
+    /// Starts recording the changes made to the store.
+    ///
+    /// Every write to the store or to any of its fields, including calls to
+    /// [`patch`](crate::Patch::patch), is recorded as a [`ChangeSet`] that describes the values
+    /// that were added, removed, or replaced, until the returned [`ChangeRecorder`] is dropped.
+    /// Writes that do not change the value are not recorded.
+    ///
+    /// Changes are found by serializing the whole store to JSON before and after each write, and
+    /// comparing the two, so recording has a cost that grows with the size of the store. Stores
+    /// without a live recorder are not serialized.
+    ///
+    /// ```
+    /// # use reactive_graph::traits::{Set, Update};
+    /// # use reactive_stores::{ArcStore, PatchOperation, Store};
+    /// # use serde::Serialize;
+    /// #[derive(Store, Serialize)]
+    /// struct Todos {
+    ///     user: String,
+    ///     todos: Vec<String>,
+    /// }
+    ///
+    /// let store = ArcStore::new(Todos {
+    ///     user: "Alice".to_string(),
+    ///     todos: vec![],
+    /// });
+    /// let recorder = store.record_changes();
+    ///
+    /// store.clone().user().set("Bob".to_string());
+    /// store.clone().todos().update(|todos| todos.push("Write docs".to_string()));
+    ///
+    /// assert_eq!(
+    ///     recorder.take_json_patch(),
+    ///     vec![
+    ///         PatchOperation::Replace {
+    ///             path: "/user".to_string(),
+    ///             value: "Bob".into()
+    ///         },
+    ///         PatchOperation::Add {
+    ///             path: "/todos/0".to_string(),
+    ///             value: "Write docs".into()
+    ///         },
+    ///     ]
+    /// );
+    /// ```
+    pub fn record_changes(&self) -> ChangeRecorder {
+        let recorder = ChangeRecorder::default();
+        let mut recorders = self.recorders.write().or_poisoned();
+        recorders.snapshot = Some(|value| serde_json::to_value(value).ok());
+        recorders.buffers.push(Arc::downgrade(&recorder.buffer));
+        recorder
+    }
+}
+
+impl<T, S> Store<T, S>
+where
+    T: Serialize + 'static,
+    S: Storage<ArcStore<T>>,
+{
+    /// Starts recording the changes made to the store.
+    ///
+    /// See [`ArcStore::record_changes`]. If the store has been disposed, the recorder never
+    /// records anything.
+    pub fn record_changes(&self) -> ChangeRecorder {
+        self.inner
+            .try_get_value()
+            .map(|inner| inner.record_changes())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Change, ChangeSet, PatchOperation};
+    use crate::{self as reactive_stores, Patch, Store, StoreFieldIterator};
+    use reactive_graph::traits::{Set, Update, UpdateUntracked};
+    use serde::Serialize;
+    use serde_json::json;
+
+    #[derive(Debug, Store, Patch, Serialize, Default)]
+    struct Todos {
+        user: String,
+        todos: Vec<Todo>,
+    }
+
+    #[derive(Debug, Store, Patch, Serialize, Default, Clone)]
+    struct Todo {
+        label: String,
+        completed: bool,
+    }
+
+    fn data() -> Todos {
+        Todos {
+            user: "Alice".to_string(),
+            todos: vec![
+                Todo {
+                    label: "Create reactive store".to_string(),
+                    completed: true,
+                },
+                Todo {
+                    label: "???".to_string(),
+                    completed: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn records_field_writes() {
+        let store = Store::new(data());
+        let recorder = store.record_changes();
+
+        store.user().set("Bob".to_string());
+        store.todos().at_unkeyed(1).completed().set(true);
+
+        assert_eq!(
+            recorder.take(),
+            vec![
+                ChangeSet {
+                    changes: vec![Change {
+                        path: "/user".to_string(),
+                        old: Some(json!("Alice")),
+                        new: Some(json!("Bob")),
+                    }]
+                },
+                ChangeSet {
+                    changes: vec![Change {
+                        path: "/todos/1/completed".to_string(),
+                        old: Some(json!(false)),
+                        new: Some(json!(true)),
+                    }]
+                },
+            ]
+        );
+        assert!(recorder.take().is_empty());
+    }
+
+    #[test]
+    fn records_patches_as_one_change_set() {
+        let store = Store::new(data());
+        let recorder = store.record_changes();
+
+        let mut new = data();
+        new.user = "Carol".to_string();
+        new.todos[0].label = "Create a reactive store".to_string();
+        store.patch(new);
+
+        assert_eq!(
+            recorder.take_json_patch(),
+            vec![
+                PatchOperation::Replace {
+                    path: "/todos/0/label".to_string(),
+                    value: json!("Create a reactive store"),
+                },
+                PatchOperation::Replace {
+                    path: "/user".to_string(),
+                    value: json!("Carol"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn records_items_added_and_removed() {
+        let store = Store::new(data());
+        let recorder = store.record_changes();
+
+        store.todos().update(|todos| {
+            todos.push(Todo {
+                label: "Profit".to_string(),
+                completed: false,
+            })
+        });
+        store.todos().update(|todos| todos.truncate(1));
+
+        let patch = recorder.take_json_patch();
+        assert_eq!(
+            serde_json::to_value(&patch).unwrap(),
+            json!([
+                {
+                    "op": "add",
+                    "path": "/todos/2",
+                    "value": { "label": "Profit", "completed": false }
+                },
+                { "op": "remove", "path": "/todos/2" },
+                { "op": "remove", "path": "/todos/1" },
+            ])
+        );
+    }
+
+    #[test]
+    fn does_not_record_unchanged_values() {
+        let store = Store::new(data());
+        let recorder = store.record_changes();
+
+        store.user().set("Alice".to_string());
+        store.patch(data());
+        store.todos().update_untracked(|_| {});
+
+        assert!(recorder.take().is_empty());
+    }
+
+    #[test]
+    fn recorders_keep_separate_logs() {
+        let store = Store::new(data());
+        let first = store.record_changes();
+        store.user().set("Bob".to_string());
+
+        let second = store.record_changes();
+        store.user().set("Carol".to_string());
+
+        assert_eq!(first.take().len(), 2);
+        assert_eq!(second.take().len(), 1);
+
+        drop(first);
+        store.user().set("Dave".to_string());
+        assert_eq!(second.take().len(), 1);
+    }
+
+    #[test]
+    fn escapes_object_keys() {
+        let mut path = String::new();
+        let mut changes = Vec::new();
+        super::diff(
+            &mut path,
+            Some(json!({ "a/b": 1, "c~d": 2 })),
+            json!({ "a/b": 3 }),
+            &mut changes,
+        );
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| change.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/c~0d", "/a~1b"]
+        );
+    }
+}
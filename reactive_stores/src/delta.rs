@@ -0,0 +1,1060 @@
+use crate::{
+    path::StorePath, KeyMap, PatchField, PatchFieldKeyed, PatchOperation,
+    StoreField,
+};
+use reactive_graph::traits::{Notify, UntrackableGuard};
+use serde::de::{DeserializeOwned, Error as _};
+use serde_json::Value;
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
+use thiserror::Error;
+
+/// Allows applying serialized changes to a store or field, only notifying the fields they touch.
+///
+/// This is implemented for every store field whose value implements [`PatchFieldDelta`], which
+/// can be derived with [`PatchDelta`](macro@crate::PatchDelta).
+///
+/// Changes are addressed with JSON Pointers (RFC 6901), which follow the value as it is
+/// serialized: struct fields are addressed by name, entries in maps by their key, and items in
+/// sequences such as `Vec` by their index, even if the field is keyed with `#[store(key: ...)]`.
+/// This is the scheme that [`ChangeRecorder`](crate::ChangeRecorder) uses, so the changes
+/// recorded on one store can be applied to another. The changes are made in place, so only the
+/// fields under the locations they address are notified, and no other part of the value is
+/// compared or deserialized.
+pub trait PatchDelta {
+    /// Applies a JSON Patch (RFC 6902) document.
+    ///
+    /// The operations are applied in order. If one of them fails, the operations before it stay
+    /// applied, and the ones after it are not applied.
+    ///
+    /// In a keyed map, `add` with the key of an existing entry replaces that entry. In a keyed
+    /// sequence, items are added and removed by index, and the keys are updated to match.
+    fn apply_json_patch(
+        &self,
+        patch: impl IntoIterator<Item = PatchOperation>,
+    ) -> Result<(), DeltaError>;
+
+    /// Applies a JSON Merge Patch (RFC 7386) document.
+    ///
+    /// Each member of the patch is merged into the field with the same name. A `null` member
+    /// sets an `Option` to `None`, or removes the entry from a keyed collection. Arrays are
+    /// replaced as a whole, using a keyed diff for keyed collections.
+    fn apply_merge_patch(&self, patch: Value) -> Result<(), DeltaError>;
+}
+
+impl<T> PatchDelta for T
+where
+    T: StoreField,
+    T::Value: PatchFieldDelta,
+{
+    fn apply_json_patch(
+        &self,
+        patch: impl IntoIterator<Item = PatchOperation>,
+    ) -> Result<(), DeltaError> {
+        apply(
+            self,
+            patch.into_iter().map(|operation| match operation {
+                PatchOperation::Add { path, value } => {
+                    (path, DeltaOperation::Add(value))
+                }
+                PatchOperation::Remove { path } => {
+                    (path, DeltaOperation::Remove)
+                }
+                PatchOperation::Replace { path, value } => {
+                    (path, DeltaOperation::Replace(value))
+                }
+            }),
+        )
+    }
+
+    fn apply_merge_patch(&self, patch: Value) -> Result<(), DeltaError> {
+        apply(self, [(String::new(), DeltaOperation::Merge(patch))])
+    }
+}
+
+fn apply<T>(
+    field: &T,
+    operations: impl IntoIterator<Item = (String, DeltaOperation)>,
+) -> Result<(), DeltaError>
+where
+    T: StoreField,
+    T::Value: PatchFieldDelta,
+{
+    let path = field.path_unkeyed().into_iter().collect::<StorePath>();
+    let keys = field.keys();
+
+    if let Some(mut writer) = field.writer() {
+        // don't track the writer for the whole store
+        writer.untrack();
+        let mut notify = |path: &StorePath| {
+            field.triggers_for_path_unkeyed(path.to_owned()).notify();
+        };
+        for (pointer, operation) in operations {
+            let result = match parse_pointer(&pointer) {
+                Some(tokens) => writer.patch_field_delta(
+                    &tokens,
+                    operation,
+                    &path,
+                    &mut notify,
+                    keys.as_ref(),
+                ),
+                None => Err(DeltaErrorKind::InvalidPointer),
+            };
+            result.map_err(|kind| DeltaError {
+                path: pointer,
+                kind,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+fn parse_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let tokens = pointer.strip_prefix('/')?;
+    Some(
+        tokens
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+/// A change to make to a value, or to a value inside it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeltaOperation {
+    /// Adds a value, or replaces it if it already exists.
+    Add(Value),
+    /// Removes a value.
+    Remove,
+    /// Replaces a value that already exists.
+    Replace(Value),
+    /// Merges a JSON Merge Patch document into a value.
+    Merge(Value),
+}
+
+/// An error that occurred while applying a change to a store.
+#[derive(Error, Debug)]
+#[error("could not apply the change at {path:?}: {kind}")]
+pub struct DeltaError {
+    /// The JSON Pointer of the change that could not be applied.
+    pub path: String,
+    /// Why the change could not be applied.
+    pub kind: DeltaErrorKind,
+}
+
+/// Why a change could not be applied to a store.
+#[derive(Error, Debug)]
+pub enum DeltaErrorKind {
+    /// The path is not a valid JSON Pointer.
+    #[error("the path is not a valid JSON pointer")]
+    InvalidPointer,
+    /// There is no field, item, or entry at the path.
+    #[error("there is no value at this path")]
+    NotFound,
+    /// The value at the path is not an `Option` or an item in a collection, so it cannot be
+    /// removed.
+    #[error("the value at this path cannot be removed")]
+    CannotRemove,
+    /// The new value could not be deserialized.
+    #[error("the new value could not be deserialized: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Allows applying a serialized change to a value in a store field, only notifying the fields
+/// that it touches.
+///
+/// This can be derived for structs with [`PatchDelta`](macro@crate::PatchDelta).
+pub trait PatchFieldDelta: PatchField {
+    /// Applies `operation` to the value at `pointer`, given as unescaped JSON Pointer reference
+    /// tokens relative to this value.
+    fn patch_field_delta(
+        &mut self,
+        pointer: &[String],
+        operation: DeltaOperation,
+        path: &StorePath,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+    ) -> Result<(), DeltaErrorKind>;
+}
+
+/// Allows applying a serialized change to a keyed collection in a store field.
+///
+/// Entries in maps are addressed by key, and items in sequences by index, as they are
+/// serialized.
+///
+/// Adding or removing items updates the keys of the collection, and notifies the collection.
+/// Replacing the whole collection does a keyed diff with [`PatchFieldKeyed`].
+pub trait PatchFieldDeltaKeyed<K>
+where
+    for<'a> &'a Self: IntoIterator,
+{
+    /// Applies `operation` to the value at `pointer`, given as unescaped JSON Pointer reference
+    /// tokens relative to this collection.
+    fn patch_field_delta_keyed(
+        &mut self,
+        pointer: &[String],
+        operation: DeltaOperation,
+        path: &StorePath,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+        key_fn: impl Fn(<&Self as IntoIterator>::Item) -> K,
+    ) -> Result<(), DeltaErrorKind>
+    where
+        K: DeserializeOwned
+            + Clone
+            + Debug
+            + Send
+            + Sync
+            + PartialEq
+            + Eq
+            + Hash
+            + 'static;
+}
+
+/// Replaces a value by deserializing the new value, and patching the old one with it.
+fn replace<T>(
+    this: &mut T,
+    pointer: &[String],
+    operation: DeltaOperation,
+    path: &StorePath,
+    notify: &mut dyn FnMut(&StorePath),
+    keys: Option<&KeyMap>,
+) -> Result<(), DeltaErrorKind>
+where
+    T: PatchField + DeserializeOwned,
+{
+    if !pointer.is_empty() {
+        return Err(DeltaErrorKind::NotFound);
+    }
+    match operation {
+        DeltaOperation::Remove => Err(DeltaErrorKind::CannotRemove),
+        DeltaOperation::Add(value)
+        | DeltaOperation::Replace(value)
+        | DeltaOperation::Merge(value) => {
+            let new = serde_json::from_value(value)?;
+            this.patch_field(new, path, notify, keys);
+            Ok(())
+        }
+    }
+}
+
+/// Applies a change to a struct, given the serialized names of its fields, and a function that
+/// applies a change to the field at some index.
+///
+/// This is used by the [`PatchDelta`](macro@crate::PatchDelta) derive macro.
+#[doc(hidden)]
+pub fn patch_struct_delta(
+    pointer: &[String],
+    operation: DeltaOperation,
+    fields: &[&str],
+    mut patch_field: impl FnMut(
+        usize,
+        &[String],
+        DeltaOperation,
+    ) -> Result<(), DeltaErrorKind>,
+) -> Result<(), DeltaErrorKind> {
+    let index_of = |name: &str| {
+        fields
+            .iter()
+            .position(|field| *field == name)
+            .ok_or(DeltaErrorKind::NotFound)
+    };
+
+    if let Some((name, pointer)) = pointer.split_first() {
+        return patch_field(index_of(name)?, pointer, operation);
+    }
+    match operation {
+        DeltaOperation::Remove => Err(DeltaErrorKind::CannotRemove),
+        DeltaOperation::Merge(Value::Object(patch)) => {
+            for (name, value) in patch {
+                let operation = if value.is_null() {
+                    DeltaOperation::Remove
+                } else {
+                    DeltaOperation::Merge(value)
+                };
+                patch_field(index_of(&name)?, &[], operation)?;
+            }
+            Ok(())
+        }
+        DeltaOperation::Add(Value::Object(mut new))
+        | DeltaOperation::Replace(Value::Object(mut new)) => {
+            for (idx, name) in fields.iter().enumerate() {
+                let value = new.remove(*name).ok_or_else(|| {
+                    serde_json::Error::custom(format!("missing field `{name}`"))
+                })?;
+                patch_field(idx, &[], DeltaOperation::Replace(value))?;
+            }
+            Ok(())
+        }
+        DeltaOperation::Add(value)
+        | DeltaOperation::Replace(value)
+        | DeltaOperation::Merge(value) => Err(serde_json::Error::invalid_type(
+            unexpected(&value),
+            &"an object",
+        )
+        .into()),
+    }
+}
+
+/// Applies a change to a field that uses a custom `#[patch]` closure, which can only be replaced
+/// as a whole.
+///
+/// This is used by the [`PatchDelta`](macro@crate::PatchDelta) derive macro.
+#[doc(hidden)]
+pub fn patch_delta_with<T>(
+    this: &mut T,
+    pointer: &[String],
+    operation: DeltaOperation,
+    path: &StorePath,
+    notify: &mut dyn FnMut(&StorePath),
+    patch: impl FnOnce(&mut T, T),
+) -> Result<(), DeltaErrorKind>
+where
+    T: PartialEq + DeserializeOwned,
+{
+    if !pointer.is_empty() {
+        return Err(DeltaErrorKind::NotFound);
+    }
+    match operation {
+        DeltaOperation::Remove => Err(DeltaErrorKind::CannotRemove),
+        DeltaOperation::Add(value)
+        | DeltaOperation::Replace(value)
+        | DeltaOperation::Merge(value) => {
+            let new = serde_json::from_value(value)?;
+            if new != *this {
+                patch(this, new);
+                notify(path);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn unexpected(value: &Value) -> serde::de::Unexpected<'_> {
+    use serde::de::Unexpected;
+
+    match value {
+        Value::Null => Unexpected::Unit,
+        Value::Bool(value) => Unexpected::Bool(*value),
+        Value::Number(_) => Unexpected::Other("a number"),
+        Value::String(value) => Unexpected::Str(value),
+        Value::Array(_) => Unexpected::Seq,
+        Value::Object(_) => Unexpected::Map,
+    }
+}
+
+/// Parses a JSON Pointer reference token as the key of an item in a keyed collection.
+///
+/// Keys that deserialize from JSON, such as numbers, are parsed as JSON, and other keys are
+/// treated as strings.
+fn parse_key<K: DeserializeOwned>(token: &str) -> Option<K> {
+    serde_json::from_str(token)
+        .or_else(|_| serde_json::from_value(Value::String(token.to_string())))
+        .ok()
+}
+
+/// Brings the keys for a keyed collection up to date after items have been added or removed.
+fn update_keys<K>(keys: Option<&KeyMap>, path: &StorePath, latest: Vec<K>)
+where
+    K: Debug + Hash + PartialEq + Eq + Send + Sync + 'static,
+{
+    // keys that have not been created yet are created from the latest value when first used
+    if let Some(keys) = keys.filter(|keys| keys.contains_key(path)) {
+        keys.with_field_keys(
+            path.clone(),
+            |field_keys| ((), field_keys.update(latest)),
+            Vec::new,
+        );
+    }
+}
+
+/// Replaces a keyed collection as a whole, with a keyed diff.
+fn replace_keyed<K, T>(
+    this: &mut T,
+    value: Value,
+    path: &StorePath,
+    notify: &mut dyn FnMut(&StorePath),
+    keys: Option<&KeyMap>,
+    key_fn: impl Fn(<&T as IntoIterator>::Item) -> K,
+) -> Result<(), DeltaErrorKind>
+where
+    T: PatchFieldKeyed<K> + DeserializeOwned,
+    for<'a> &'a T: IntoIterator,
+    K: Clone + Debug + Send + Sync + PartialEq + Eq + Hash + 'static,
+{
+    let new = serde_json::from_value(value)?;
+    if let Some(keys) = keys {
+        // make sure the keys match the old value, so that its items can be found by key
+        let current = (&*this).into_iter().map(&key_fn).collect::<Vec<_>>();
+        keys.with_field_keys(
+            path.clone(),
+            |field_keys| ((), field_keys.update(current)),
+            Vec::new,
+        );
+    }
+    let path_at_key = |key: &K| {
+        let segment = keys?
+            .with_field_keys(
+                path.clone(),
+                |keys| (keys.get(key), vec![]),
+                Vec::new,
+            )
+            .flatten()
+            .map(|(_, idx)| idx)?;
+        let mut path = path.clone();
+        path.push(segment);
+        Some(path)
+    };
+    let structure_changed =
+        this.patch_field_keyed(new, notify, keys, &key_fn, path_at_key);
+    if structure_changed {
+        notify(path);
+        let latest = (&*this).into_iter().map(&key_fn).collect();
+        update_keys(keys, path, latest);
+    }
+    Ok(())
+}
+
+macro_rules! delta_primitives {
+    ($($ty:ty),*) => {
+        $(impl PatchFieldDelta for $ty {
+            fn patch_field_delta(
+                &mut self,
+                pointer: &[String],
+                operation: DeltaOperation,
+                path: &StorePath,
+                notify: &mut dyn FnMut(&StorePath),
+                keys: Option<&KeyMap>,
+            ) -> Result<(), DeltaErrorKind> {
+                replace(self, pointer, operation, path, notify, keys)
+            }
+        })*
+    };
+}
+
+delta_primitives! {
+    String,
+    usize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    isize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    f32,
+    f64,
+    char,
+    bool,
+    std::net::IpAddr,
+    std::net::SocketAddr,
+    std::net::SocketAddrV4,
+    std::net::SocketAddrV6,
+    std::net::Ipv4Addr,
+    std::net::Ipv6Addr,
+    std::num::NonZeroI8,
+    std::num::NonZeroU8,
+    std::num::NonZeroI16,
+    std::num::NonZeroU16,
+    std::num::NonZeroI32,
+    std::num::NonZeroU32,
+    std::num::NonZeroI64,
+    std::num::NonZeroU64,
+    std::num::NonZeroI128,
+    std::num::NonZeroU128,
+    std::num::NonZeroIsize,
+    std::num::NonZeroUsize
+}
+
+impl<T> PatchFieldDelta for Option<T>
+where
+    T: PatchFieldDelta + DeserializeOwned,
+{
+    fn patch_field_delta(
+        &mut self,
+        pointer: &[String],
+        operation: DeltaOperation,
+        path: &StorePath,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+    ) -> Result<(), DeltaErrorKind> {
+        let mut inner_path = path.to_owned();
+        inner_path.push(0);
+        match (self, operation) {
+            (this, DeltaOperation::Remove) if pointer.is_empty() => {
+                this.patch_field(None, path, notify, keys);
+                Ok(())
+            }
+            (Some(inner), operation @ DeltaOperation::Merge(_))
+                if pointer.is_empty() =>
+            {
+                inner.patch_field_delta(
+                    pointer,
+                    operation,
+                    &inner_path,
+                    notify,
+                    keys,
+                )
+            }
+            (this, operation) if pointer.is_empty() => {
+                replace(this, pointer, operation, path, notify, keys)
+            }
+            (Some(inner), operation) => inner.patch_field_delta(
+                pointer,
+                operation,
+                &inner_path,
+                notify,
+                keys,
+            ),
+            (None, _) => Err(DeltaErrorKind::NotFound),
+        }
+    }
+}
+
+impl<T> PatchFieldDelta for Vec<T>
+where
+    T: PatchFieldDelta + DeserializeOwned,
+{
+    fn patch_field_delta(
+        &mut self,
+        pointer: &[String],
+        operation: DeltaOperation,
+        path: &StorePath,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+    ) -> Result<(), DeltaErrorKind> {
+        let Some((token, pointer)) = pointer.split_first() else {
+            return replace(self, pointer, operation, path, notify, keys);
+        };
+        let index = if token == "-" {
+            Some(self.len())
+        } else {
+            token.parse::<usize>().ok()
+        };
+        let index = index.ok_or(DeltaErrorKind::NotFound)?;
+
+        match operation {
+            DeltaOperation::Add(value)
+                if pointer.is_empty() && index <= self.len() =>
+            {
+                self.insert(index, serde_json::from_value(value)?);
+                notify(path);
+                Ok(())
+            }
+            DeltaOperation::Remove
+                if pointer.is_empty() && index < self.len() =>
+            {
+                self.remove(index);
+                notify(path);
+                Ok(())
+            }
+            operation => {
+                let item =
+                    self.get_mut(index).ok_or(DeltaErrorKind::NotFound)?;
+                let mut item_path = path.to_owned();
+                item_path.push(index);
+                item.patch_field_delta(
+                    pointer, operation, &item_path, notify, keys,
+                )
+            }
+        }
+    }
+}
+
+impl<K, T> PatchFieldDeltaKeyed<K> for Vec<T>
+where
+    T: PatchFieldDelta + DeserializeOwned,
+{
+    fn patch_field_delta_keyed(
+        &mut self,
+        pointer: &[String],
+        operation: DeltaOperation,
+        path: &StorePath,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+        key_fn: impl Fn(<&Self as IntoIterator>::Item) -> K,
+    ) -> Result<(), DeltaErrorKind>
+    where
+        K: DeserializeOwned
+            + Clone
+            + Debug
+            + Send
+            + Sync
+            + PartialEq
+            + Eq
+            + Hash
+            + 'static,
+    {
+        let Some((token, pointer)) = pointer.split_first() else {
+            return match operation {
+                DeltaOperation::Remove => Err(DeltaErrorKind::CannotRemove),
+                DeltaOperation::Add(value)
+                | DeltaOperation::Replace(value)
+                | DeltaOperation::Merge(value) => {
+                    replace_keyed(self, value, path, notify, keys, key_fn)
+                }
+            };
+        };
+        // items are addressed by index, like the serialized array, and not by key
+        let index = if token == "-" {
+            Some(self.len())
+        } else {
+            token.parse::<usize>().ok()
+        };
+        let index = index.ok_or(DeltaErrorKind::NotFound)?;
+
+        match operation {
+            DeltaOperation::Add(value)
+                if pointer.is_empty() && index <= self.len() =>
+            {
+                self.insert(index, serde_json::from_value(value)?);
+            }
+            DeltaOperation::Remove
+                if pointer.is_empty() && index < self.len() =>
+            {
+                self.remove(index);
+            }
+            operation => {
+                let item =
+                    self.get_mut(index).ok_or(DeltaErrorKind::NotFound)?;
+                let mut item_path = path.to_owned();
+                item_path.push(index);
+                return item.patch_field_delta(
+                    pointer, operation, &item_path, notify, keys,
+                );
+            }
+        }
+
+        notify(path);
+        update_keys(keys, path, self.iter().map(key_fn).collect());
+        Ok(())
+    }
+}
+
+impl<K, V> PatchFieldDeltaKeyed<K> for HashMap<K, V>
+where
+    V: PatchFieldDelta + DeserializeOwned,
+    K: Eq + Hash,
+{
+    fn patch_field_delta_keyed(
+        &mut self,
+        pointer: &[String],
+        operation: DeltaOperation,
+        path: &StorePath,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+        key_fn: impl Fn(<&Self as IntoIterator>::Item) -> K,
+    ) -> Result<(), DeltaErrorKind>
+    where
+        K: DeserializeOwned
+            + Clone
+            + Debug
+            + Send
+            + Sync
+            + PartialEq
+            + Eq
+            + Hash
+            + 'static,
+    {
+        let Some((token, pointer)) = pointer.split_first() else {
+            return match operation {
+                DeltaOperation::Remove => Err(DeltaErrorKind::CannotRemove),
+                DeltaOperation::Merge(Value::Object(patch)) => {
+                    let mut result = Ok(());
+                    let mut structure_changed = false;
+                    for (token, value) in patch {
+                        let operation = if value.is_null() {
+                            DeltaOperation::Remove
+                        } else {
+                            DeltaOperation::Merge(value)
+                        };
+                        match patch_entry(
+                            self,
+                            &token,
+                            &[],
+                            operation,
+                            path,
+                            notify,
+                            keys,
+                            &key_fn,
+                        ) {
+                            Ok(changed) => structure_changed |= changed,
+                            Err(e) => {
+                                result = Err(e);
+                                break;
+                            }
+                        }
+                    }
+                    if structure_changed {
+                        notify(path);
+                        update_keys(
+                            keys,
+                            path,
+                            self.iter().map(&key_fn).collect(),
+                        );
+                    }
+                    result
+                }
+                DeltaOperation::Add(value)
+                | DeltaOperation::Replace(value)
+                | DeltaOperation::Merge(value) => {
+                    replace_keyed(self, value, path, notify, keys, key_fn)
+                }
+            };
+        };
+
+        if patch_entry(
+            self, token, pointer, operation, path, notify, keys, &key_fn,
+        )? {
+            notify(path);
+            update_keys(keys, path, self.iter().map(&key_fn).collect());
+        }
+        Ok(())
+    }
+}
+
+/// Applies a change to the entry with the given key in a keyed map.
+///
+/// Returns `true` if an entry was added or removed.
+#[allow(clippy::too_many_arguments)]
+fn patch_entry<K, V>(
+    map: &mut HashMap<K, V>,
+    token: &str,
+    pointer: &[String],
+    operation: DeltaOperation,
+    path: &StorePath,
+    notify: &mut dyn FnMut(&StorePath),
+    keys: Option<&KeyMap>,
+    key_fn: impl Fn(<&HashMap<K, V> as IntoIterator>::Item) -> K,
+) -> Result<bool, DeltaErrorKind>
+where
+    K: DeserializeOwned + Eq + Hash,
+    V: PatchFieldDelta + DeserializeOwned,
+{
+    let key = parse_key::<K>(token).ok_or(DeltaErrorKind::NotFound)?;
+    let index = map.iter().position(|item| key_fn(item) == key);
+
+    match (index, operation) {
+        (Some(_), DeltaOperation::Remove) if pointer.is_empty() => {
+            map.remove(&key);
+            Ok(true)
+        }
+        (Some(index), operation) => {
+            let mut item_path = path.to_owned();
+            item_path.push(index);
+            let item = map.get_mut(&key).ok_or(DeltaErrorKind::NotFound)?;
+            item.patch_field_delta(
+                pointer, operation, &item_path, notify, keys,
+            )?;
+            Ok(false)
+        }
+        (None, DeltaOperation::Add(value) | DeltaOperation::Merge(value))
+            if pointer.is_empty() =>
+        {
+            map.insert(key, serde_json::from_value(value)?);
+            Ok(true)
+        }
+        (None, _) => Err(DeltaErrorKind::NotFound),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeltaErrorKind;
+    use crate::{
+        self as reactive_stores, tests::tick, AtKeyed, Patch, PatchDelta,
+        PatchOperation, Store, StoreFieldIterator,
+    };
+    use reactive_graph::{
+        effect::Effect,
+        traits::{Get, GetUntracked, Read, ReadUntracked, Set, Write},
+    };
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(
+        Debug, Store, Patch, PatchDelta, Serialize, Deserialize, Default,
+    )]
+    struct Todos {
+        user: String,
+        #[serde(rename = "dueDate")]
+        due_date: Option<String>,
+        #[store(key: usize = |todo| todo.id)]
+        todos: Vec<Todo>,
+    }
+
+    #[derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Store,
+        Patch,
+        PatchDelta,
+        Serialize,
+        Deserialize,
+        Default,
+    )]
+    struct Todo {
+        id: usize,
+        label: String,
+        completed: bool,
+    }
+
+    fn data() -> Todos {
+        Todos {
+            user: "Alice".to_string(),
+            due_date: None,
+            todos: vec![
+                Todo {
+                    id: 10,
+                    label: "Create reactive store".to_string(),
+                    completed: true,
+                },
+                Todo {
+                    id: 11,
+                    label: "???".to_string(),
+                    completed: false,
+                },
+            ],
+        }
+    }
+
+    fn count_runs(fun: impl Fn() + Send + Sync + 'static) -> Arc<AtomicUsize> {
+        let count = Arc::new(AtomicUsize::new(0));
+        Effect::new_sync({
+            let count = Arc::clone(&count);
+            move |_| {
+                fun();
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        count
+    }
+
+    #[tokio::test]
+    async fn json_patch_only_notifies_touched_fields() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(data());
+        let user = count_runs(move || _ = store.user().read());
+        let first_label =
+            count_runs(move || _ = store.todos().at_unkeyed(0).label().read());
+        let second_label =
+            count_runs(move || _ = store.todos().at_unkeyed(1).label().read());
+        tick().await;
+
+        store
+            .apply_json_patch([PatchOperation::Replace {
+                path: "/todos/1/label".to_string(),
+                value: json!("Profit"),
+            }])
+            .unwrap();
+        tick().await;
+
+        assert_eq!(store.todos().at_unkeyed(1).label().get(), "Profit");
+        assert_eq!(user.load(Ordering::Relaxed), 1);
+        assert_eq!(first_label.load(Ordering::Relaxed), 1);
+        assert_eq!(second_label.load(Ordering::Relaxed), 2);
+
+        store
+            .apply_json_patch([PatchOperation::Replace {
+                path: "/user".to_string(),
+                value: json!("Bob"),
+            }])
+            .unwrap();
+        tick().await;
+
+        assert_eq!(store.user().get(), "Bob");
+        assert_eq!(user.load(Ordering::Relaxed), 2);
+        assert_eq!(second_label.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn json_patch_adds_and_removes_keyed_items() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(data());
+        let todos = count_runs(move || _ = store.todos().read());
+        tick().await;
+
+        store
+            .apply_json_patch([
+                PatchOperation::Add {
+                    path: "/todos/-".to_string(),
+                    value: json!({ "id": 12, "label": "Profit", "completed": false }),
+                },
+                PatchOperation::Remove {
+                    path: "/todos/0".to_string(),
+                },
+                PatchOperation::Replace {
+                    path: "/todos/1/completed".to_string(),
+                    value: json!(true),
+                },
+            ])
+            .unwrap();
+        tick().await;
+
+        assert_eq!(
+            store
+                .todos()
+                .get_untracked()
+                .iter()
+                .map(|todo| todo.id)
+                .collect::<Vec<_>>(),
+            vec![11, 12]
+        );
+        assert!(AtKeyed::new(store.todos(), 12).completed().get_untracked());
+        assert!(todos.load(Ordering::Relaxed) > 1);
+    }
+
+    #[test]
+    fn recorded_patches_apply_to_another_store() {
+        let store = Store::new(data());
+        let replica = Store::new(data());
+        let recorder = store.record_changes();
+
+        AtKeyed::new(store.todos(), 11).completed().set(true);
+        store.todos().write().push(Todo {
+            id: 12,
+            label: "Profit".to_string(),
+            completed: false,
+        });
+        store.todos().write().retain(|todo| todo.id != 10);
+        AtKeyed::new(store.todos(), 12)
+            .label()
+            .set("Ship it".to_string());
+
+        replica
+            .apply_json_patch(recorder.take_json_patch())
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&*replica.read_untracked()).unwrap(),
+            serde_json::to_value(&*store.read_untracked()).unwrap()
+        );
+        assert!(AtKeyed::new(replica.todos(), 11)
+            .completed()
+            .get_untracked());
+        assert_eq!(
+            AtKeyed::new(replica.todos(), 12).label().get_untracked(),
+            "Ship it"
+        );
+    }
+
+    #[tokio::test]
+    async fn merge_patch_merges_fields() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(data());
+        let user = count_runs(move || _ = store.user().read());
+        tick().await;
+
+        store
+            .apply_merge_patch(json!({
+                "dueDate": "tomorrow",
+                "todos": [
+                    { "id": 11, "label": "???", "completed": true },
+                    { "id": 10, "label": "Create reactive store", "completed": true },
+                ]
+            }))
+            .unwrap();
+        tick().await;
+
+        assert_eq!(
+            store.due_date().get_untracked().as_deref(),
+            Some("tomorrow")
+        );
+        assert_eq!(store.todos().get_untracked()[0].id, 11);
+        assert!(store.todos().get_untracked()[0].completed);
+        assert_eq!(user.load(Ordering::Relaxed), 1);
+
+        store.apply_merge_patch(json!({ "dueDate": null })).unwrap();
+        assert_eq!(store.due_date().get_untracked(), None);
+    }
+
+    #[test]
+    fn pointers_follow_rename_all() {
+        #[derive(Debug, Store, Patch, PatchDelta, Serialize, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Settings {
+            dark_mode: bool,
+            font_size: u32,
+            #[serde(rename = "lang")]
+            language: String,
+        }
+
+        let store = Store::new(Settings {
+            dark_mode: false,
+            font_size: 12,
+            language: "en".to_string(),
+        });
+        store
+            .apply_json_patch([
+                PatchOperation::Replace {
+                    path: "/darkMode".to_string(),
+                    value: json!(true),
+                },
+                PatchOperation::Replace {
+                    path: "/fontSize".to_string(),
+                    value: json!(14),
+                },
+            ])
+            .unwrap();
+        store.apply_merge_patch(json!({ "lang": "fr" })).unwrap();
+        assert_eq!(
+            serde_json::to_value(&*store.read_untracked()).unwrap(),
+            json!({ "darkMode": true, "fontSize": 14, "lang": "fr" })
+        );
+
+        let error = store
+            .apply_json_patch([PatchOperation::Replace {
+                path: "/dark_mode".to_string(),
+                value: json!(false),
+            }])
+            .unwrap_err();
+        assert!(matches!(error.kind, DeltaErrorKind::NotFound));
+    }
+
+    #[test]
+    fn reports_errors() {
+        let store = Store::new(data());
+
+        let error = store
+            .apply_json_patch([PatchOperation::Replace {
+                path: "/todos/99/label".to_string(),
+                value: json!("Missing"),
+            }])
+            .unwrap_err();
+        assert_eq!(error.path, "/todos/99/label");
+        assert!(matches!(error.kind, DeltaErrorKind::NotFound));
+
+        let error = store
+            .apply_json_patch([PatchOperation::Remove {
+                path: "/user".to_string(),
+            }])
+            .unwrap_err();
+        assert!(matches!(error.kind, DeltaErrorKind::CannotRemove));
+
+        let error = store
+            .apply_json_patch([PatchOperation::Replace {
+                path: "user".to_string(),
+                value: json!("Bob"),
+            }])
+            .unwrap_err();
+        assert!(matches!(error.kind, DeltaErrorKind::InvalidPointer));
+
+        let error = store.apply_merge_patch(json!({ "user": 42 })).unwrap_err();
+        assert!(matches!(error.kind, DeltaErrorKind::Json(_)));
+        assert_eq!(store.user().get_untracked(), "Alice");
+    }
+}
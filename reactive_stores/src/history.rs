@@ -0,0 +1,655 @@
+use crate::{
+    changes::{Change, ChangeSet, PatchOperation, Recorders},
+    ArcStore, PatchDelta, PatchFieldDelta, Store,
+};
+use or_poisoned::OrPoisoned;
+use reactive_graph::{
+    owner::Storage,
+    signal::ArcTrigger,
+    traits::{Notify, Track},
+};
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// The changes to a store that can be undone and redone.
+pub(crate) struct History {
+    undo: VecDeque<ChangeSet>,
+    redo: Vec<ChangeSet>,
+    capacity: usize,
+    // how many transactions are currently running
+    grouping: usize,
+    // whether the running transaction has already added an entry
+    recorded_group: bool,
+    // whether an entry is being undone or redone, and so should not be recorded
+    replaying: bool,
+    changed: ArcTrigger,
+}
+
+impl History {
+    fn new(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            capacity,
+            grouping: 0,
+            recorded_group: false,
+            replaying: false,
+            changed: ArcTrigger::new(),
+        }
+    }
+
+    fn can_undo_redo(&self) -> (bool, bool) {
+        (!self.undo.is_empty(), !self.redo.is_empty())
+    }
+
+    /// Adds the changes made by a write.
+    ///
+    /// Returns the trigger to notify if this made it possible or impossible to undo or redo.
+    pub(crate) fn record(
+        &mut self,
+        change_set: ChangeSet,
+    ) -> Option<ArcTrigger> {
+        if self.replaying || self.capacity == 0 {
+            return None;
+        }
+        let before = self.can_undo_redo();
+        match self.undo.back_mut() {
+            Some(last) if self.grouping > 0 && self.recorded_group => {
+                last.changes.extend(change_set.changes);
+            }
+            _ => {
+                if self.undo.len() == self.capacity {
+                    self.undo.pop_front();
+                }
+                self.undo.push_back(change_set);
+                self.recorded_group = self.grouping > 0;
+            }
+        }
+        self.redo.clear();
+        (before != self.can_undo_redo()).then(|| self.changed.clone())
+    }
+}
+
+/// Ends a transaction when dropped, even if the transaction panics.
+pub(crate) struct EndGroup(Arc<Mutex<History>>);
+
+impl EndGroup {
+    /// Starts a transaction, if the store keeps a history.
+    pub(crate) fn start<T>(recorders: &RwLock<Recorders<T>>) -> Option<Self> {
+        let history = recorders.read().or_poisoned().history.clone()?;
+        {
+            let mut history = history.lock().or_poisoned();
+            if history.grouping == 0 {
+                history.recorded_group = false;
+            }
+            history.grouping += 1;
+        }
+        Some(Self(history))
+    }
+}
+
+impl Drop for EndGroup {
+    fn drop(&mut self) {
+        self.0.lock().or_poisoned().grouping -= 1;
+    }
+}
+
+impl<T> ArcStore<T>
+where
+    T: Serialize + 'static,
+{
+    /// Starts keeping a history of the changes made to the store, so that they can be
+    /// [undone](ArcStore::undo) and [redone](ArcStore::redo).
+    ///
+    /// Each write to the store, or to any of its fields, is added to the history as the
+    /// [`ChangeSet`] it made, rather than as a copy of the whole value. All the writes made in a
+    /// [`transaction`](ArcStore::transaction) are undone and redone together. At most `capacity`
+    /// entries are kept: once it is reached, the oldest one is forgotten.
+    ///
+    /// Calling this again clears the history.
+    ///
+    /// ```rust
+    /// # use reactive_graph::traits::{GetUntracked, Set};
+    /// # use reactive_stores::{ArcStore, Patch, PatchDelta, Store};
+    /// # use serde::{Deserialize, Serialize};
+    /// #[derive(Store, Patch, PatchDelta, Serialize, Deserialize)]
+    /// struct Form {
+    ///     name: String,
+    ///     email: String,
+    /// }
+    ///
+    /// let store = ArcStore::new(Form {
+    ///     name: String::new(),
+    ///     email: String::new(),
+    /// });
+    /// store.record_history(100);
+    ///
+    /// store.transaction(|store| {
+    ///     store.clone().name().set("Alice".into());
+    ///     store.clone().email().set("alice@example.com".into());
+    /// });
+    /// store.clone().name().set("Alicia".into());
+    ///
+    /// assert!(store.undo());
+    /// assert_eq!(store.clone().name().get_untracked(), "Alice");
+    /// // both fields were set in the same transaction
+    /// assert!(store.undo());
+    /// assert_eq!(store.clone().name().get_untracked(), "");
+    /// assert_eq!(store.clone().email().get_untracked(), "");
+    /// assert!(!store.can_undo());
+    ///
+    /// assert!(store.redo());
+    /// assert_eq!(store.clone().email().get_untracked(), "alice@example.com");
+    /// ```
+    pub fn record_history(&self, capacity: usize) {
+        let mut recorders = self.recorders.write().or_poisoned();
+        recorders.snapshot = Some(|value| serde_json::to_value(value).ok());
+        let previous = recorders
+            .history
+            .replace(Arc::new(Mutex::new(History::new(capacity))));
+        drop(recorders);
+        if let Some(previous) = previous {
+            previous.lock().or_poisoned().changed.notify();
+        }
+    }
+}
+
+impl<T> ArcStore<T> {
+    fn history(&self) -> Option<Arc<Mutex<History>>> {
+        self.recorders.read().or_poisoned().history.clone()
+    }
+
+    fn track_history(&self, fun: impl FnOnce(&History) -> bool) -> bool {
+        self.history().is_some_and(|history| {
+            let history = history.lock().or_poisoned();
+            history.changed.track();
+            fun(&history)
+        })
+    }
+
+    /// Whether there are any changes that can be undone.
+    ///
+    /// This is a reactive read. It is always `false` if the store does not
+    /// [keep a history](ArcStore::record_history).
+    pub fn can_undo(&self) -> bool {
+        self.track_history(|history| !history.undo.is_empty())
+    }
+
+    /// Whether there are any undone changes that can be redone.
+    ///
+    /// This is a reactive read. It is always `false` if the store does not
+    /// [keep a history](ArcStore::record_history).
+    pub fn can_redo(&self) -> bool {
+        self.track_history(|history| !history.redo.is_empty())
+    }
+
+    /// Forgets all the changes that could be undone or redone, without changing the value.
+    pub fn clear_history(&self) {
+        if let Some(history) = self.history() {
+            let mut history = history.lock().or_poisoned();
+            if history.can_undo_redo() != (false, false) {
+                history.undo.clear();
+                history.redo.clear();
+                let changed = history.changed.clone();
+                drop(history);
+                changed.notify();
+            }
+        }
+    }
+}
+
+impl<T> ArcStore<T>
+where
+    T: Serialize + PatchFieldDelta + 'static,
+{
+    /// Undoes the most recent change in the history, and returns whether there was one.
+    ///
+    /// The inverse of the change is applied with [`PatchDelta::apply_json_patch`], so only the
+    /// fields that it touches are notified. Items in keyed collections are addressed by index,
+    /// which stays valid because every write that moves them is also in the history.
+    ///
+    /// Each change is applied as a whole. If part of it cannot be applied, for example because a
+    /// value does not survive a round trip through JSON, the parts that were applied are reverted,
+    /// the change stays in the history, and this returns `false`.
+    pub fn undo(&self) -> bool {
+        self.step(true)
+    }
+
+    /// Redoes the most recently undone change, and returns whether there was one.
+    ///
+    /// Any write made to the store after undoing a change clears the changes that can be redone.
+    /// Like [`undo`](ArcStore::undo), the change is applied as a whole, or not at all.
+    pub fn redo(&self) -> bool {
+        self.step(false)
+    }
+
+    fn step(&self, undo: bool) -> bool {
+        let Some(history) = self.history() else {
+            return false;
+        };
+        let (entry, before) = {
+            let mut history = history.lock().or_poisoned();
+            let before = history.can_undo_redo();
+            let entry = if undo {
+                history.undo.pop_back()
+            } else {
+                history.redo.pop()
+            };
+            let Some(entry) = entry else {
+                return false;
+            };
+            (entry, before)
+        };
+        let applied = if undo {
+            self.replay(
+                &history,
+                entry
+                    .changes
+                    .iter()
+                    .rev()
+                    .map(|change| (inverse(change), change.to_operation())),
+            )
+        } else {
+            self.replay(
+                &history,
+                entry
+                    .changes
+                    .iter()
+                    .map(|change| (change.to_operation(), inverse(change))),
+            )
+        };
+
+        let mut history = history.lock().or_poisoned();
+        match (applied, undo) {
+            (true, true) | (false, false) => history.redo.push(entry),
+            (true, false) | (false, true) => history.undo.push_back(entry),
+        }
+        history.recorded_group = false;
+        if before != history.can_undo_redo() {
+            let changed = history.changed.clone();
+            drop(history);
+            changed.notify();
+        }
+        applied
+    }
+
+    /// Applies all of the operations, each paired with the operation that reverts it, or none of
+    /// them.
+    fn replay(
+        &self,
+        history: &Mutex<History>,
+        operations: impl Iterator<Item = (PatchOperation, PatchOperation)>,
+    ) -> bool {
+        history.lock().or_poisoned().replaying = true;
+        let mut reverts = Vec::new();
+        let mut applied = true;
+        for (operation, revert) in operations {
+            if self.apply_json_patch([operation]).is_err() {
+                applied = false;
+                break;
+            }
+            reverts.push(revert);
+        }
+        if !applied {
+            // each revert restores the value that its operation replaced
+            _ = self.apply_json_patch(reverts.into_iter().rev());
+        }
+        history.lock().or_poisoned().replaying = false;
+        applied
+    }
+}
+
+impl<T, S> Store<T, S>
+where
+    T: Serialize + 'static,
+    S: Storage<ArcStore<T>>,
+{
+    /// Starts keeping a history of the changes made to the store.
+    ///
+    /// See [`ArcStore::record_history`].
+    pub fn record_history(&self, capacity: usize) {
+        if let Some(inner) = self.inner.try_get_value() {
+            inner.record_history(capacity);
+        }
+    }
+}
+
+impl<T, S> Store<T, S>
+where
+    T: 'static,
+    S: Storage<ArcStore<T>>,
+{
+    /// Whether there are any changes that can be undone. This is a reactive read.
+    pub fn can_undo(&self) -> bool {
+        self.inner
+            .try_get_value()
+            .is_some_and(|inner| inner.can_undo())
+    }
+
+    /// Whether there are any undone changes that can be redone. This is a reactive read.
+    pub fn can_redo(&self) -> bool {
+        self.inner
+            .try_get_value()
+            .is_some_and(|inner| inner.can_redo())
+    }
+
+    /// Forgets all the changes that could be undone or redone, without changing the value.
+    pub fn clear_history(&self) {
+        if let Some(inner) = self.inner.try_get_value() {
+            inner.clear_history();
+        }
+    }
+}
+
+impl<T, S> Store<T, S>
+where
+    T: Serialize + PatchFieldDelta + 'static,
+    S: Storage<ArcStore<T>>,
+{
+    /// Undoes the most recent change in the history, and returns whether there was one.
+    ///
+    /// See [`ArcStore::undo`].
+    pub fn undo(&self) -> bool {
+        self.inner.try_get_value().is_some_and(|inner| inner.undo())
+    }
+
+    /// Redoes the most recently undone change, and returns whether there was one.
+    ///
+    /// See [`ArcStore::redo`].
+    pub fn redo(&self) -> bool {
+        self.inner.try_get_value().is_some_and(|inner| inner.redo())
+    }
+}
+
+/// The operation that reverts a change.
+fn inverse(change: &Change) -> PatchOperation {
+    let path = change.path.clone();
+    match (&change.old, &change.new) {
+        (None, _) => PatchOperation::Remove { path },
+        (Some(old), None) => PatchOperation::Add {
+            path,
+            value: old.clone(),
+        },
+        (Some(old), Some(_)) => PatchOperation::Replace {
+            path,
+            value: old.clone(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as reactive_stores, AtKeyed, Patch, PatchDelta, Store,
+        StoreFieldIterator,
+    };
+    use reactive_graph::{
+        effect::Effect,
+        traits::{GetUntracked, Read, Set, Track, Update},
+    };
+    use serde::{Deserialize, Serialize};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(
+        Debug, Store, Patch, PatchDelta, Serialize, Deserialize, Default,
+    )]
+    struct Form {
+        name: String,
+        email: String,
+        tags: Vec<String>,
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_field_writes() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(Form::default());
+        store.record_history(10);
+        assert!(!store.can_undo());
+
+        store.name().set("Alice".into());
+        store.tags().update(|tags| tags.push("admin".into()));
+        store.tags().update(|tags| tags.push("editor".into()));
+        assert!(store.can_undo());
+
+        assert!(store.undo());
+        assert_eq!(store.tags().read().as_slice(), ["admin"]);
+        assert!(store.undo());
+        assert!(store.tags().read().is_empty());
+        assert!(store.undo());
+        assert_eq!(store.name().read().as_str(), "");
+        assert!(!store.undo());
+        assert!(store.can_redo());
+
+        assert!(store.redo());
+        assert!(store.redo());
+        assert_eq!(store.name().read().as_str(), "Alice");
+        assert_eq!(store.tags().read().as_slice(), ["admin"]);
+
+        // a new write clears the changes that can be redone
+        store.email().set("alice@example.com".into());
+        assert!(!store.can_redo());
+        assert!(!store.redo());
+    }
+
+    #[derive(
+        Debug, Store, Patch, PatchDelta, Serialize, Deserialize, Default,
+    )]
+    struct Todos {
+        #[store(key: usize = |todo| todo.id)]
+        todos: Vec<Todo>,
+    }
+
+    #[derive(
+        Debug,
+        Clone,
+        PartialEq,
+        Store,
+        Patch,
+        PatchDelta,
+        Serialize,
+        Deserialize,
+        Default,
+    )]
+    struct Todo {
+        id: usize,
+        done: bool,
+    }
+
+    fn todos(ids: &[usize]) -> Todos {
+        Todos {
+            todos: ids.iter().map(|&id| Todo { id, done: false }).collect(),
+        }
+    }
+
+    fn done(store: Store<Todos>) -> Vec<(usize, bool)> {
+        store
+            .todos()
+            .read()
+            .iter()
+            .map(|todo| (todo.id, todo.done))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_keyed_item_writes() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(todos(&[10, 11]));
+        store.record_history(10);
+
+        store.todos().at_unkeyed(1).done().set(true);
+        assert!(store.undo());
+        assert_eq!(done(store), [(10, false), (11, false)]);
+        assert!(store.redo());
+        assert_eq!(done(store), [(10, false), (11, true)]);
+        assert!(AtKeyed::new(store.todos(), 11).done().get_untracked());
+    }
+
+    #[tokio::test]
+    async fn undo_keyed_writes_after_items_are_moved() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(todos(&[1, 0]));
+        store.record_history(10);
+
+        AtKeyed::new(store.todos(), 0).done().set(true);
+        store
+            .todos()
+            .update(|todos| todos.sort_by_key(|todo| todo.id));
+        store
+            .todos()
+            .update(|todos| todos.push(Todo { id: 2, done: false }));
+        AtKeyed::new(store.todos(), 1).done().set(true);
+        assert_eq!(done(store), [(0, true), (1, true), (2, false)]);
+
+        assert!(store.undo());
+        assert_eq!(done(store), [(0, true), (1, false), (2, false)]);
+        assert!(store.undo());
+        assert_eq!(done(store), [(0, true), (1, false)]);
+        assert!(store.undo());
+        assert_eq!(done(store), [(1, false), (0, true)]);
+        assert!(store.undo());
+        assert_eq!(done(store), [(1, false), (0, false)]);
+        assert!(!store.can_undo());
+
+        while store.redo() {}
+        assert_eq!(done(store), [(0, true), (1, true), (2, false)]);
+        assert!(AtKeyed::new(store.todos(), 1).done().get_untracked());
+    }
+
+    #[tokio::test]
+    async fn changes_are_replayed_all_or_nothing() {
+        _ = any_spawner::Executor::init_tokio();
+
+        #[derive(Debug, Store, Patch, PatchDelta, Serialize, Deserialize)]
+        struct Scores {
+            name: String,
+            score: f64,
+        }
+
+        let store = Store::new(Scores {
+            name: String::new(),
+            score: 0.0,
+        });
+        store.record_history(10);
+        store.transaction(|store| {
+            store.name().set("Alice".into());
+            // serialized as `null`, which cannot be deserialized as an `f64`
+            store.score().set(f64::NAN);
+        });
+
+        assert!(store.undo());
+        assert_eq!(store.name().get_untracked(), "");
+        assert_eq!(store.score().get_untracked(), 0.0);
+
+        // setting the name again is reverted, because the score cannot be set
+        assert!(!store.redo());
+        assert_eq!(store.name().get_untracked(), "");
+        assert_eq!(store.score().get_untracked(), 0.0);
+        assert!(store.can_redo());
+    }
+
+    #[tokio::test]
+    async fn transaction_is_one_undoable_unit() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(Form::default());
+        store.record_history(10);
+
+        store.transaction(|store| {
+            store.name().set("Alice".into());
+            store.email().set("alice@example.com".into());
+            store.transaction(|store| {
+                store.tags().update(|tags| tags.push("admin".into()))
+            });
+        });
+        store.name().set("Alicia".into());
+
+        assert!(store.undo());
+        assert_eq!(store.name().read().as_str(), "Alice");
+        assert!(store.undo());
+        assert_eq!(store.name().read().as_str(), "");
+        assert_eq!(store.email().read().as_str(), "");
+        assert!(store.tags().read().is_empty());
+        assert!(!store.can_undo());
+    }
+
+    #[tokio::test]
+    async fn undo_only_notifies_changed_fields() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(Form::default());
+        store.record_history(10);
+        store.name().set("Alice".into());
+
+        let name_runs = Arc::new(AtomicUsize::new(0));
+        let email_runs = Arc::new(AtomicUsize::new(0));
+        Effect::new_sync({
+            let name_runs = Arc::clone(&name_runs);
+            move |_| {
+                store.name().track();
+                name_runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let email_runs = Arc::clone(&email_runs);
+            move |_| {
+                store.email().track();
+                email_runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        crate::tests::tick().await;
+
+        assert!(store.undo());
+        crate::tests::tick().await;
+        assert_eq!(name_runs.load(Ordering::Relaxed), 2);
+        assert_eq!(email_runs.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn can_undo_is_reactive() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(Form::default());
+        store.record_history(1);
+        let runs = Arc::new(AtomicUsize::new(0));
+        Effect::new_sync({
+            let runs = Arc::clone(&runs);
+            move |_| {
+                store.can_undo();
+                store.can_redo();
+                runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        crate::tests::tick().await;
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+
+        store.name().set("Alice".into());
+        crate::tests::tick().await;
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+
+        // still possible to undo, and not possible to redo
+        store.name().set("Alicia".into());
+        crate::tests::tick().await;
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+
+        // only one entry is kept
+        assert!(store.undo());
+        assert!(!store.can_undo());
+        crate::tests::tick().await;
+        assert_eq!(runs.load(Ordering::Relaxed), 3);
+        assert_eq!(store.name().read().as_str(), "Alice");
+
+        store.clear_history();
+        crate::tests::tick().await;
+        assert_eq!(runs.load(Ordering::Relaxed), 4);
+        assert!(!store.can_redo());
+    }
+}
@@ -81,6 +81,15 @@ impl<K: Hash + Eq, V> KeyedAccess<K> for std::collections::HashMap<K, V> {
         self.get_mut(key).expect("key does not exist")
     }
 }
+impl<K: Hash + Eq, V> KeyedAccess<K> for indexmap::IndexMap<K, V> {
+    type Value = V;
+    fn keyed(&self, _index: usize, key: &K) -> &Self::Value {
+        self.get(key).expect("key does not exist")
+    }
+    fn keyed_mut(&mut self, _index: usize, key: &K) -> &mut Self::Value {
+        self.get_mut(key).expect("key does not exist")
+    }
+}
 
 /// Provides access to a subfield that contains some kind of keyed collection.
 #[derive(Debug)]
@@ -90,8 +99,8 @@ where
 {
     #[cfg(any(debug_assertions, leptos_debuginfo))]
     defined_at: &'static Location<'static>,
-    path_segment: StorePathSegment,
-    inner: Inner,
+    pub(crate) path_segment: StorePathSegment,
+    pub(crate) inner: Inner,
     read: fn(&Prev) -> &T,
     write: fn(&mut Prev) -> &mut T,
     pub(crate) key_fn: fn(<&T as IntoIterator>::Item) -> K,
@@ -635,9 +644,10 @@ where
     }
 
     fn writer(&self) -> Option<Self::Writer> {
+        // resolving the index can read the collection, so do it before locking it for writing
+        let index = self.resolve_index()?;
         let mut inner = self.inner.writer()?;
         inner.untrack();
-        let index = self.resolve_index()?;
         let triggers = self.triggers_for_current_path();
         Some(WriteGuard::new(
             triggers,
@@ -885,14 +895,19 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::{self as reactive_stores, tests::tick, AtKeyed, Store};
+    use crate::{
+        self as reactive_stores,
+        tests::{count_runs, tick},
+        AtKeyed, Store,
+    };
+    use indexmap::IndexMap;
     use reactive_graph::{
         effect::Effect,
         traits::{Get, GetUntracked, ReadUntracked, Set, Track, Write},
     };
     use reactive_stores::Patch;
     use std::{
-        collections::{BTreeMap, BTreeSet, HashMap},
+        collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
         sync::{
             atomic::{AtomicUsize, Ordering},
             Arc,
@@ -925,7 +940,7 @@ mod tests {
         }
     }
 
-    #[derive(Debug, Store, Default)]
+    #[derive(Debug, Store, Default, Patch)]
     struct TodoBTreeMap {
         #[store(key: usize = |(key, _)| *key)]
         todos: BTreeMap<usize, Todo>,
@@ -983,6 +998,18 @@ mod tests {
         }
     }
 
+    #[derive(Debug, Store, Default)]
+    struct TodoVecDeque {
+        #[store(key: usize = |todo| todo.id)]
+        todos: VecDeque<Todo>,
+    }
+
+    #[derive(Debug, Store, Default, Patch)]
+    struct TodoIndexMap {
+        #[store(key: usize = |(key, _)| *key)]
+        todos: IndexMap<usize, Todo>,
+    }
+
     #[derive(
         Debug, Store, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Patch,
     )]
@@ -1456,4 +1483,235 @@ mod tests {
         assert_eq!(b_count.load(Ordering::Relaxed), 2);
         assert_eq!(c_count.load(Ordering::Relaxed), 2);
     }
+
+    #[tokio::test]
+    async fn patching_items_in_place_before_keys_are_updated() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(TodoVec::test_data());
+
+        let a_count = Arc::new(AtomicUsize::new(0));
+        let b_count = Arc::new(AtomicUsize::new(0));
+
+        let a = AtKeyed::new(store.todos(), 10);
+        let b = AtKeyed::new(store.todos(), 11);
+
+        Effect::new_sync({
+            let a_count = Arc::clone(&a_count);
+            move || {
+                a.track();
+                a_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let b_count = Arc::clone(&b_count);
+            move || {
+                b.track();
+                b_count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        tick().await;
+
+        // the keys have only been created from the initial value, and not updated since, but
+        // the changed item can still be notified by its index
+        let mut new_data = store.todos().get_untracked();
+        new_data[0].label = "Foo".into();
+        store.todos().patch(new_data);
+        tick().await;
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+        assert_eq!(a.label().get_untracked(), "Foo");
+    }
+
+    #[tokio::test]
+    async fn patching_vec_deques_detects_changes() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(TodoVecDeque {
+            todos: TodoVec::test_data().todos.into(),
+        });
+
+        let a = AtKeyed::new(store.todos(), 10);
+        let whole_count = count_runs(store.todos());
+        let a_count = count_runs(a);
+        let b_count = count_runs(AtKeyed::new(store.todos(), 11));
+        tick().await;
+
+        // changing an item in place only notifies that key, and the collection's children
+        let mut new_data = store.todos().get_untracked();
+        new_data[0].label = "Foo".into();
+        store.todos().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 2);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // moving items notifies the collection, but not the moved items
+        new_data.rotate_left(1);
+        store.todos().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 3);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // and the moved items can still be updated by key
+        a.label().set("Bar".into());
+        assert_eq!(store.todos().get_untracked()[2], Todo::new(10, "Bar"));
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 4);
+        assert_eq!(a_count.load(Ordering::Relaxed), 3);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // adding an item notifies the collection
+        let mut new_data = store.todos().get_untracked();
+        new_data.push_front(Todo::new(13, "D"));
+        store.todos().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 5);
+        assert_eq!(a_count.load(Ordering::Relaxed), 3);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // removing an item notifies the collection
+        new_data.retain(|todo| todo.id != 12);
+        store.todos().patch(new_data);
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 6);
+        assert_eq!(a_count.load(Ordering::Relaxed), 3);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            store.todos().get_untracked(),
+            [Todo::new(13, "D"), Todo::new(11, "B"), Todo::new(10, "Bar")]
+        );
+    }
+
+    #[tokio::test]
+    async fn patching_btree_maps_detects_changes() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(TodoBTreeMap::test_data());
+
+        let a = AtKeyed::new(store.todos(), 10);
+        let whole_count = count_runs(store.todos());
+        let a_count = count_runs(a);
+        let b_count = count_runs(AtKeyed::new(store.todos(), 11));
+        tick().await;
+
+        // changing an entry in place only notifies that key, and the collection's children
+        let mut new_data = store.todos().get_untracked();
+        new_data.get_mut(&10).unwrap().label = "Foo".into();
+        store.todos().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 2);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // entries are always kept in key order, so inserting them in another order is not a change
+        let reordered = new_data
+            .clone()
+            .into_iter()
+            .rev()
+            .collect::<BTreeMap<_, _>>();
+        store.todos().patch(reordered);
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 2);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // adding an entry notifies the collection
+        new_data.insert(13, Todo::new(13, "D"));
+        store.todos().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 3);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // removing an entry notifies the collection
+        new_data.remove(&12);
+        store.todos().patch(new_data);
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 4);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            store
+                .todos()
+                .get_untracked()
+                .into_values()
+                .collect::<Vec<_>>(),
+            [Todo::new(10, "Foo"), Todo::new(11, "B"), Todo::new(13, "D")]
+        );
+    }
+
+    #[tokio::test]
+    async fn patching_index_maps_detects_changes() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(TodoIndexMap {
+            todos: TodoVec::test_data()
+                .todos
+                .into_iter()
+                .map(|todo| (todo.id, todo))
+                .collect(),
+        });
+
+        let a = AtKeyed::new(store.todos(), 10);
+        let whole_count = count_runs(store.todos());
+        let a_count = count_runs(a);
+        let b_count = count_runs(AtKeyed::new(store.todos(), 11));
+        tick().await;
+
+        // changing an entry in place only notifies that key, and the collection's children
+        let mut new_data = store.todos().get_untracked();
+        new_data[0].label = "Foo".into();
+        store.todos().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 2);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // moving entries notifies the collection, but not the moved entries
+        new_data.swap_indices(0, 2);
+        store.todos().patch(new_data);
+        assert_eq!(
+            store.todos().get_untracked().keys().collect::<Vec<_>>(),
+            [&12, &11, &10]
+        );
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 3);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // and the moved entries can still be updated by key
+        a.label().set("Bar".into());
+        assert_eq!(store.todos().get_untracked()[2], Todo::new(10, "Bar"));
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 4);
+        assert_eq!(a_count.load(Ordering::Relaxed), 3);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // adding an entry notifies the collection
+        let mut new_data = store.todos().get_untracked();
+        new_data.insert(13, Todo::new(13, "D"));
+        store.todos().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 5);
+        assert_eq!(a_count.load(Ordering::Relaxed), 3);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // removing an entry notifies the collection
+        new_data.shift_remove(&12);
+        store.todos().patch(new_data);
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 6);
+        assert_eq!(a_count.load(Ordering::Relaxed), 3);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            store
+                .todos()
+                .get_untracked()
+                .into_values()
+                .collect::<Vec<_>>(),
+            [Todo::new(11, "B"), Todo::new(10, "Bar"), Todo::new(13, "D")]
+        );
+    }
 }
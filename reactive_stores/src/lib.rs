@@ -22,6 +22,16 @@
 //! [`.patch()`](Patch::patch) method, which allows you to provide an entirely new value, but only
 //! notify fields that have changed.
 //!
+//! With the `serde` feature, the [`PatchDelta`](macro@PatchDelta) macro allows stores and fields
+//! to apply serialized changes, such as JSON Patch documents received from a server, with
+//! [`PatchDelta`](trait@PatchDelta). Only the fields that the changes touch are notified. Fields
+//! are addressed by their serialized names, following `#[serde(rename)]` and
+//! `#[serde(rename_all)]`; fields that use `#[serde(flatten)]` or `#[serde(skip)]` are rejected.
+//!
+//! Fields can be given validators with `#[store(...)]` attributes, as described in [`Validate`].
+//! Each validated field has an [`.errors()`](Subfield::errors) memo that only checks the field
+//! again when it changes, and [`.is_valid()`](StoreValidation::is_valid) checks a whole store.
+//!
 //! Updating a field will notify its parents and children, but not its siblings.
 //!
 //! Stores can therefore
@@ -241,7 +251,7 @@
 
 use or_poisoned::OrPoisoned;
 use reactive_graph::{
-    owner::{ArenaItem, LocalStorage, Storage, SyncStorage},
+    owner::{ArenaItem, LocalStorage, Owner, Storage, SyncStorage},
     signal::{
         guards::{Plain, ReadGuard, WriteGuard},
         ArcTrigger,
@@ -251,7 +261,7 @@ use reactive_graph::{
         UntrackableGuard, Write,
     },
 };
-pub use reactive_stores_macro::{Patch, Store};
+pub use reactive_stores_macro::{Patch, PatchDelta, Store};
 use rustc_hash::FxHashMap;
 use std::{
     any::Any,
@@ -263,8 +273,14 @@ use std::{
 };
 
 mod arc_field;
+#[cfg(feature = "serde")]
+mod changes;
+#[cfg(feature = "serde")]
+mod delta;
 mod deref;
 mod field;
+#[cfg(feature = "serde")]
+mod history;
 mod iter;
 mod keyed;
 mod len;
@@ -277,8 +293,13 @@ mod serde;
 mod slotmap;
 mod store_field;
 mod subfield;
+mod validate;
 
 pub use arc_field::ArcField;
+#[cfg(feature = "serde")]
+pub use changes::*;
+#[cfg(feature = "serde")]
+pub use delta::*;
 pub use deref::*;
 pub use field::Field;
 pub use iter::*;
@@ -287,17 +308,23 @@ pub use len::Len;
 pub use option::*;
 pub use patch::*;
 pub use path::{StorePath, StorePathSegment};
-pub use store_field::StoreField;
+pub use store_field::{StoreField, StoreWriteGuard};
 pub use subfield::Subfield;
+pub use validate::*;
 
-#[derive(Debug, Default)]
-struct TriggerMap(FxHashMap<StorePath, StoreFieldTrigger>);
+#[derive(Debug)]
+struct TriggerMap {
+    triggers: FxHashMap<StorePath, StoreFieldTrigger>,
+    // owns the memos that are shared by all the users of a field, which live as long as the store
+    owner: Owner,
+}
 
 /// The reactive trigger that can be used to track updates to a store field.
 #[derive(Debug, Clone, Default)]
 pub struct StoreFieldTrigger {
     pub(crate) this: ArcTrigger,
     pub(crate) children: ArcTrigger,
+    pub(crate) errors: validate::ErrorsMemo,
 }
 
 impl StoreFieldTrigger {
@@ -308,19 +335,29 @@ impl StoreFieldTrigger {
 }
 
 impl TriggerMap {
+    fn new() -> Self {
+        Self {
+            triggers: FxHashMap::default(),
+            owner: Owner::new(),
+        }
+    }
+
     fn get_or_insert(&mut self, key: StorePath) -> StoreFieldTrigger {
-        if let Some(trigger) = self.0.get(&key) {
+        if let Some(trigger) = self.triggers.get(&key) {
             trigger.clone()
         } else {
-            let new = StoreFieldTrigger::new();
-            self.0.insert(key, new.clone());
+            let new = StoreFieldTrigger {
+                errors: validate::ErrorsMemo::new(self.owner.clone()),
+                ..StoreFieldTrigger::new()
+            };
+            self.triggers.insert(key, new.clone());
             new
         }
     }
 
     #[allow(unused)]
     fn remove(&mut self, key: &StorePath) -> Option<StoreFieldTrigger> {
-        self.0.remove(key)
+        self.triggers.remove(key)
     }
 }
 
@@ -441,9 +478,15 @@ impl KeyMap {
         let initial_keys = initialize();
 
         let mut guard = self.0.write().or_poisoned();
-        let entry = guard
-            .entry(path.clone())
-            .or_insert_with(|| Box::new(FieldKeys::new(initial_keys)));
+        let entry = guard.entry(path.clone()).or_insert_with(|| {
+            // new keys use their index as their segment, so they must also be found by index
+            // before they are first updated (e.g., when patching an item notifies it by index)
+            let mut index_keys = self.1.write().or_poisoned();
+            for idx in 0..initial_keys.len() {
+                index_keys.insert((path.clone(), idx), idx.into());
+            }
+            Box::new(FieldKeys::new(initial_keys))
+        });
 
         let entry = entry.downcast_mut::<FieldKeys<K>>()?;
         let (result, new_keys) = fun(entry);
@@ -482,6 +525,8 @@ pub struct ArcStore<T> {
     pub(crate) value: Arc<RwLock<T>>,
     signals: Arc<RwLock<TriggerMap>>,
     keys: KeyMap,
+    #[cfg(feature = "serde")]
+    pub(crate) recorders: Arc<RwLock<changes::Recorders<T>>>,
 }
 
 impl<T> ArcStore<T> {
@@ -491,10 +536,30 @@ impl<T> ArcStore<T> {
             #[cfg(any(debug_assertions, leptos_debuginfo))]
             defined_at: Location::caller(),
             value: Arc::new(RwLock::new(value)),
-            signals: Default::default(),
+            signals: Arc::new(RwLock::new(TriggerMap::new())),
             keys: Default::default(),
+            #[cfg(feature = "serde")]
+            recorders: Default::default(),
         }
     }
+
+    /// Runs `fun` as a single transaction on the store.
+    ///
+    /// `fun` runs in a [`batch`](reactive_graph::effect::batch), so
+    /// [`ImmediateEffect`](reactive_graph::effect::ImmediateEffect)s that depend on the fields
+    /// written in the transaction run once it has finished, rather than after each write. Other
+    /// effects are already queued until the next tick, and are not affected. If the store
+    /// [keeps a history](ArcStore::record_history), all the writes are also added to it as one
+    /// entry, which is undone and redone as a whole.
+    ///
+    /// Transactions can be nested: the writes in an inner transaction belong to the outermost one.
+    pub fn transaction<U>(&self, fun: impl FnOnce(&Self) -> U) -> U {
+        reactive_graph::effect::batch(|| {
+            #[cfg(feature = "serde")]
+            let _group = history::EndGroup::start(&self.recorders);
+            fun(self)
+        })
+    }
 }
 
 impl<T: Default> Default for ArcStore<T> {
@@ -522,6 +587,8 @@ impl<T> Clone for ArcStore<T> {
             value: Arc::clone(&self.value),
             signals: Arc::clone(&self.signals),
             keys: self.keys.clone(),
+            #[cfg(feature = "serde")]
+            recorders: Arc::clone(&self.recorders),
         }
     }
 }
@@ -644,6 +711,25 @@ where
     }
 }
 
+impl<T, S> Store<T, S>
+where
+    T: 'static,
+    S: Storage<ArcStore<T>>,
+{
+    /// Runs `fun` as a single transaction on the store.
+    ///
+    /// See [`ArcStore::transaction`].
+    pub fn transaction<U>(&self, fun: impl FnOnce(&Self) -> U) -> U {
+        reactive_graph::effect::batch(|| {
+            #[cfg(feature = "serde")]
+            let _group = self.inner.try_with_value(|inner| {
+                history::EndGroup::start(&inner.recorders)
+            });
+            fun(self)
+        })
+    }
+}
+
 impl<T> Default for Store<T>
 where
     T: Default + Send + Sync + 'static,
@@ -803,6 +889,21 @@ mod tests {
         tokio::time::sleep(std::time::Duration::from_micros(1)).await;
     }
 
+    /// Creates an effect that tracks `field`, and returns the number of times it has run.
+    pub fn count_runs(
+        field: impl Track + Send + Sync + 'static,
+    ) -> Arc<AtomicUsize> {
+        let count = Arc::new(AtomicUsize::new(0));
+        Effect::new_sync({
+            let count = Arc::clone(&count);
+            move || {
+                field.track();
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        count
+    }
+
     #[derive(Debug, Store, Patch, Default)]
     struct Todos {
         user: String,
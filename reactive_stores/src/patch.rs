@@ -4,7 +4,7 @@ use itertools::{EitherOrBoth, Itertools};
 use reactive_graph::traits::{Notify, UntrackableGuard};
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, VecDeque},
     fmt::Debug,
     hash::Hash,
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
@@ -469,6 +469,163 @@ where
     }
 }
 
+impl<K, T> PatchFieldKeyed<K> for VecDeque<T>
+where
+    T: PatchField,
+{
+    fn patch_field_keyed(
+        &mut self,
+        mut new: Self,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+        key_fn: impl Fn(<&Self as IntoIterator>::Item) -> K,
+        path_at_key: impl Fn(&K) -> Option<StorePath>,
+    ) -> bool
+    where
+        K: Clone + Debug + Send + Sync + PartialEq + Eq + Hash + 'static,
+    {
+        let old = std::mem::take(self)
+            .into_iter()
+            .map(|item| (key_fn(&item), item));
+        let new_entries = new.iter_mut().map(|item| (key_fn(item), item));
+        let has_changed = patch_keyed_values(
+            old,
+            new_entries,
+            true,
+            notify,
+            keys,
+            path_at_key,
+        );
+        *self = new;
+        has_changed
+    }
+}
+
+impl<K, V> PatchFieldKeyed<K> for BTreeMap<K, V>
+where
+    V: PatchField,
+    K: Ord,
+{
+    fn patch_field_keyed(
+        &mut self,
+        mut new: Self,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+        key_fn: impl Fn(<&Self as IntoIterator>::Item) -> K,
+        path_at_key: impl Fn(&K) -> Option<StorePath>,
+    ) -> bool
+    where
+        K: Clone + Debug + Send + Sync + PartialEq + Eq + Hash + 'static,
+    {
+        let old = std::mem::take(self)
+            .into_iter()
+            .map(|(key, value)| (key_fn((&key, &value)), value));
+        let new_entries = new
+            .iter_mut()
+            .map(|(key, value)| (key_fn((key, value)), value));
+        let has_changed = patch_keyed_values(
+            old,
+            new_entries,
+            true,
+            notify,
+            keys,
+            path_at_key,
+        );
+        *self = new;
+        has_changed
+    }
+}
+
+impl<K, V> PatchFieldKeyed<K> for IndexMap<K, V>
+where
+    V: PatchField,
+    K: Eq + Hash,
+{
+    fn patch_field_keyed(
+        &mut self,
+        mut new: Self,
+        notify: &mut dyn FnMut(&StorePath),
+        keys: Option<&KeyMap>,
+        key_fn: impl Fn(<&Self as IntoIterator>::Item) -> K,
+        path_at_key: impl Fn(&K) -> Option<StorePath>,
+    ) -> bool
+    where
+        K: Clone + Debug + Send + Sync + PartialEq + Eq + Hash + 'static,
+    {
+        let old = std::mem::take(self)
+            .into_iter()
+            .map(|(key, value)| (key_fn((&key, &value)), value));
+        let new_entries = new
+            .iter_mut()
+            .map(|(key, value)| (key_fn((key, value)), value));
+        let has_changed = patch_keyed_values(
+            old,
+            new_entries,
+            true,
+            notify,
+            keys,
+            path_at_key,
+        );
+        *self = new;
+        has_changed
+    }
+}
+
+/// Patches the values of a new keyed collection with the values of the old one.
+///
+/// `old` yields the key and value of each old entry, and `new` the key of each new entry with a
+/// mutable reference to its value. Each new value that has the same key as an old value is
+/// swapped for the old value, which is then patched with it, so that the new collection keeps
+/// the old values (and their paths) wherever possible.
+///
+/// Returns `true` if entries were added or removed, or, if `ordered` is set, if any entry has
+/// moved to a different position.
+pub(crate) fn patch_keyed_values<'a, K, V>(
+    old: impl IntoIterator<Item = (K, V)>,
+    new: impl IntoIterator<Item = (K, &'a mut V)>,
+    ordered: bool,
+    notify: &mut dyn FnMut(&StorePath),
+    keys: Option<&KeyMap>,
+    path_at_key: impl Fn(&K) -> Option<StorePath>,
+) -> bool
+where
+    K: Eq + Hash,
+    V: PatchField + 'a,
+{
+    let mut has_changed = false;
+
+    let mut old_keyed = old
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (key, value))| (key, (idx, value)))
+        .collect::<HashMap<_, _>>();
+
+    for (new_idx, (key, new_value)) in new.into_iter().enumerate() {
+        match old_keyed.remove(&key) {
+            // not found in old map, collection has changed and will trigger
+            None => has_changed = true,
+            Some((old_idx, old_value)) => {
+                if ordered && old_idx != new_idx {
+                    has_changed = true;
+                }
+
+                // patch the old value at this key, if we know its path; otherwise keep the new
+                // value, and notify the whole collection
+                match path_at_key(&key) {
+                    Some(path) => {
+                        let new = std::mem::replace(new_value, old_value);
+                        new_value.patch_field(new, &path, notify, keys);
+                    }
+                    None => has_changed = true,
+                }
+            }
+        }
+    }
+
+    // any old keys not included in the new keys have been removed
+    has_changed || !old_keyed.is_empty()
+}
+
 macro_rules! patch_tuple {
 	($($ty:ident),*) => {
 		impl<$($ty),*> PatchField for ($($ty,)*)
@@ -1,5 +1,9 @@
 //! SlotMap support for keyed fields based on their map types.
-use crate::KeyedAccess;
+use crate::{
+    patch::patch_keyed_values, path::StorePath, KeyMap, KeyedAccess,
+    PatchField, PatchFieldKeyed,
+};
+use std::{fmt::Debug, hash::Hash};
 
 impl<K: slotmap::Key, V> KeyedAccess<K> for slotmap::SlotMap<K, V> {
     type Value = V;
@@ -48,9 +52,66 @@ impl<K: slotmap::Key, V> KeyedAccess<K> for slotmap::SparseSecondaryMap<K, V> {
     }
 }
 
+macro_rules! patch_slotmaps {
+    ($($(#[$attr:meta])* $ty:ident),*) => {
+        $(
+            $(#[$attr])*
+            impl<K: slotmap::Key, V> PatchFieldKeyed<K> for slotmap::$ty<K, V>
+            where
+                K: 'static,
+                V: PatchField + 'static,
+            {
+                fn patch_field_keyed(
+                    &mut self,
+                    mut new: Self,
+                    notify: &mut dyn FnMut(&StorePath),
+                    keys: Option<&KeyMap>,
+                    key_fn: impl Fn(<&Self as IntoIterator>::Item) -> K,
+                    path_at_key: impl Fn(&K) -> Option<StorePath>,
+                ) -> bool
+                where
+                    K: Clone + Debug + Send + Sync + PartialEq + Eq + Hash + 'static,
+                {
+                    // slot keys cannot be chosen on insertion, so the new map is kept, and the
+                    // old values are moved into it
+                    let old = std::mem::take(self)
+                        .into_iter()
+                        .map(|(key, value)| (key_fn((key, &value)), value));
+                    let new_entries = new
+                        .iter_mut()
+                        .map(|(key, value)| (key_fn((key, value)), value));
+                    let has_changed = patch_keyed_values(
+                        old,
+                        new_entries,
+                        false,
+                        notify,
+                        keys,
+                        path_at_key,
+                    );
+                    *self = new;
+                    has_changed
+                }
+            }
+        )*
+    };
+}
+
+patch_slotmaps! {
+    SlotMap,
+    DenseSlotMap,
+    #[allow(deprecated)]
+    HopSlotMap,
+    SecondaryMap,
+    SparseSecondaryMap
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{self as reactive_stores, tests::tick, AtKeyed, Store};
+    use crate::{
+        self as reactive_stores,
+        tests::{count_runs, tick},
+        AtKeyed, Store,
+    };
     use reactive_graph::{
         effect::Effect,
         traits::{GetUntracked, ReadUntracked, Set, Track, Write},
@@ -83,6 +144,12 @@ mod tests {
         }
     }
 
+    #[derive(Debug, Default, Store)]
+    struct LabelSlotMap {
+        #[store(key: DefaultKey = |(k, _)| k)]
+        labels: SlotMap<DefaultKey, String>,
+    }
+
     #[derive(Debug, Store, Default, Clone, PartialEq, Eq)]
     struct Todo {
         id: DefaultKey,
@@ -193,4 +260,65 @@ mod tests {
         assert_eq!(b_count.load(Ordering::Relaxed), 2);
         assert_eq!(c_count.load(Ordering::Relaxed), 2);
     }
+
+    #[tokio::test]
+    async fn patching_slotmaps_detects_changes() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let mut labels = SlotMap::new();
+        let ids = ["A", "B", "C"].map(|label| labels.insert(label.to_string()));
+        let store = Store::new(LabelSlotMap { labels });
+
+        let a = AtKeyed::new(store.labels(), ids[0]);
+        let whole_count = count_runs(store.labels());
+        let a_count = count_runs(a);
+        let b_count = count_runs(AtKeyed::new(store.labels(), ids[1]));
+        tick().await;
+
+        // changing a value in place only notifies that key, and the collection's children
+        let mut new_data = store.labels().get_untracked();
+        new_data[ids[0]] = "Foo".into();
+        store.labels().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 2);
+        assert_eq!(a_count.load(Ordering::Relaxed), 2);
+        assert_eq!(b_count.load(Ordering::Relaxed), 1);
+
+        // slot maps are ordered by their keys, so moving values to other slots changes the
+        // values at those keys, but not the collection
+        let (a_label, b_label) =
+            (new_data[ids[0]].clone(), new_data[ids[1]].clone());
+        new_data[ids[0]] = b_label;
+        new_data[ids[1]] = a_label;
+        store.labels().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 3);
+        assert_eq!(a_count.load(Ordering::Relaxed), 3);
+        assert_eq!(b_count.load(Ordering::Relaxed), 2);
+
+        // adding a value notifies the collection
+        let new_id = new_data.insert("D".into());
+        store.labels().patch(new_data.clone());
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 4);
+        assert_eq!(a_count.load(Ordering::Relaxed), 3);
+        assert_eq!(b_count.load(Ordering::Relaxed), 2);
+
+        // removing a value notifies the collection
+        new_data.remove(ids[2]);
+        store.labels().patch(new_data);
+        tick().await;
+        assert_eq!(whole_count.load(Ordering::Relaxed), 5);
+        assert_eq!(a_count.load(Ordering::Relaxed), 3);
+        assert_eq!(b_count.load(Ordering::Relaxed), 2);
+        let after = store.labels().get_untracked();
+        assert_eq!(
+            after.iter().collect::<Vec<_>>(),
+            [
+                (ids[0], &"B".to_string()),
+                (ids[1], &"Foo".to_string()),
+                (new_id, &"D".to_string())
+            ]
+        );
+    }
 }
@@ -11,7 +11,11 @@ use reactive_graph::{
     },
     traits::{Track, UntrackableGuard},
 };
-use std::{iter, ops::Deref, sync::Arc};
+use std::{
+    iter,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
 
 /// Describes a type that can be accessed as a reactive store field.
 pub trait StoreField: Sized {
@@ -127,7 +131,7 @@ where
 {
     type Value = T;
     type Reader = Plain<T>;
-    type Writer = WriteGuard<ArcTrigger, UntrackedWriteGuard<T>>;
+    type Writer = WriteGuard<ArcTrigger, StoreWriteGuard<T>>;
 
     #[track_caller]
     fn get_trigger(&self, path: StorePath) -> StoreFieldTrigger {
@@ -183,6 +187,14 @@ where
     fn writer(&self) -> Option<Self::Writer> {
         let trigger = self.get_trigger(Default::default());
         let guard = UntrackedWriteGuard::try_new(Arc::clone(&self.value))?;
+        let guard = StoreWriteGuard {
+            #[cfg(feature = "serde")]
+            recording: crate::changes::Recorders::start(
+                &self.recorders,
+                &guard,
+            ),
+            guard: Some(guard),
+        };
         Some(WriteGuard::new(trigger.children, guard))
     }
 
@@ -199,7 +211,7 @@ where
 {
     type Value = T;
     type Reader = Plain<T>;
-    type Writer = WriteGuard<ArcTrigger, UntrackedWriteGuard<T>>;
+    type Writer = WriteGuard<ArcTrigger, StoreWriteGuard<T>>;
 
     #[track_caller]
     fn get_trigger(&self, path: StorePath) -> StoreFieldTrigger {
@@ -248,3 +260,51 @@ where
         self.inner.try_get_value().and_then(|inner| inner.keys())
     }
 }
+
+/// A guard that gives mutable access to the whole value of a store.
+///
+/// Every write to a store or one of its fields goes through this guard. With the `serde` feature,
+/// if the store is recording changes (see `ArcStore::record_changes`), the changes are recorded
+/// when it is dropped.
+pub struct StoreWriteGuard<T: 'static> {
+    guard: Option<UntrackedWriteGuard<T>>,
+    #[cfg(feature = "serde")]
+    recording: Option<crate::changes::Recording<T>>,
+}
+
+impl<T> Deref for StoreWriteGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.guard.as_ref().expect(
+            "the guard should always be in place until the Drop \
+             implementation",
+        )
+    }
+}
+
+impl<T> DerefMut for StoreWriteGuard<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.guard.as_mut().expect(
+            "the guard should always be in place until the Drop \
+             implementation",
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> Drop for StoreWriteGuard<T> {
+    fn drop(&mut self) {
+        use reactive_graph::traits::Notify;
+
+        let Some(recording) = self.recording.take() else {
+            return;
+        };
+        // record before the lock is released, so that no other write can come in between
+        let changed = recording.finish(self);
+        drop(self.guard.take());
+        if let Some(changed) = changed {
+            changed.notify();
+        }
+    }
+}
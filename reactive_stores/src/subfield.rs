@@ -21,8 +21,8 @@ use std::{iter, marker::PhantomData, ops::DerefMut, panic::Location};
 pub struct Subfield<Inner, Prev, T> {
     #[cfg(any(debug_assertions, leptos_debuginfo))]
     defined_at: &'static Location<'static>,
-    path_segment: StorePathSegment,
-    inner: Inner,
+    pub(crate) path_segment: StorePathSegment,
+    pub(crate) inner: Inner,
     read: fn(&Prev) -> &T,
     write: fn(&mut Prev) -> &mut T,
     ty: PhantomData<T>,
@@ -0,0 +1,448 @@
+use crate::{path::StorePath, KeyedSubfield, Len, StoreField, Subfield};
+use or_poisoned::OrPoisoned;
+use reactive_graph::{
+    computed::Memo,
+    owner::Owner,
+    traits::{IsDisposed, Track, With},
+};
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    ops::RangeBounds,
+    sync::{Arc, Mutex},
+};
+
+/// A reason why the value of a field is not valid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidationError(Cow<'static, str>);
+
+impl ValidationError {
+    /// Creates an error with the given message.
+    pub fn new(message: impl Into<Cow<'static, str>>) -> Self {
+        Self(message.into())
+    }
+
+    /// The message describing the error.
+    pub fn message(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for ValidationError {}
+
+impl From<String> for ValidationError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<&'static str> for ValidationError {
+    fn from(message: &'static str) -> Self {
+        Self::new(message)
+    }
+}
+
+/// Validates the fields of a struct.
+///
+/// This is implemented by `#[derive(Store)]` for a struct with at least one field that has a
+/// validator:
+/// - `#[store(validate = path::to_fn)]` calls a function that takes a reference to the field and
+///   returns a `Result<(), E>`, where `E` can be converted into a [`ValidationError`].
+/// - `#[store(range = 1..=10)]` checks that the value is in a range.
+/// - `#[store(length = 1..=64)]` checks that the [`Len`] of the value is in a range. For strings,
+///   this is the length in bytes.
+/// - `#[store(regex = "^[a-z]+$")]` checks that the value matches a regular expression. This
+///   requires the `regex` feature, and using it without the feature is a compile error.
+///
+/// A field can have any number of validators, and all of them are checked.
+///
+/// # Panics
+///
+/// A `regex` pattern is compiled the first time a value is validated, and
+/// [`validate_field`](Validate::validate_field) panics if it is not a valid regular expression.
+/// The derive macro compiles each pattern when it is expanded, so an invalid pattern is reported
+/// as a compile error instead.
+pub trait Validate {
+    /// The indices of the fields that have validators.
+    const VALIDATED_FIELDS: &'static [usize];
+
+    /// Checks the value of the field at `index`, and returns every error found.
+    fn validate_field(&self, index: usize) -> Vec<ValidationError>;
+}
+
+/// Checks whether all the fields of a store, or of a field, are valid.
+pub trait StoreValidation {
+    /// Whether every validated field is valid.
+    ///
+    /// This is a reactive read of the [`errors`](Subfield::errors) of each validated field, so a
+    /// change to one field only checks that field again, and this only notifies its subscribers
+    /// when a field becomes valid or invalid.
+    fn is_valid(&self) -> bool;
+}
+
+impl<T> StoreValidation for T
+where
+    T: StoreField + Clone + Send + Sync + 'static,
+    T::Value: Validate,
+{
+    fn is_valid(&self) -> bool {
+        T::Value::VALIDATED_FIELDS
+            .iter()
+            .all(|idx| field_errors(self, *idx).with(Vec::is_empty))
+    }
+}
+
+/// The memo of the errors in a field, which is created when it is first needed, and shared by
+/// every caller after that.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ErrorsMemo {
+    // the owner of the store, or `None` for a trigger that does not belong to one
+    owner: Option<Owner>,
+    memo: Arc<Mutex<Option<Memo<Vec<ValidationError>>>>>,
+}
+
+impl ErrorsMemo {
+    pub(crate) fn new(owner: Owner) -> Self {
+        Self {
+            owner: Some(owner),
+            memo: Default::default(),
+        }
+    }
+}
+
+/// Returns the memo of the errors in the field at `index` in the value of `parent`.
+///
+/// The memo belongs to the store, so it is shared by every caller and lives as long as the store.
+fn field_errors<F>(parent: &F, index: usize) -> Memo<Vec<ValidationError>>
+where
+    F: StoreField + Clone + Send + Sync + 'static,
+    F::Value: Validate,
+{
+    let mut path = parent.path().into_iter().collect::<StorePath>();
+    path.push(index);
+    let ErrorsMemo { owner, memo } = parent.get_trigger(path).errors;
+    let mut memo = memo.lock().or_poisoned();
+    // the store's owner is cleaned up along with it, but can be reused if the store is not
+    if let Some(memo) = memo.filter(|memo| !memo.is_disposed()) {
+        return memo;
+    }
+
+    let parent = parent.clone();
+    let create = move || {
+        Memo::new(move |_| {
+            // tracks the field in the same way as the field itself would be tracked
+            let mut path = parent.path().into_iter().collect::<StorePath>();
+            let mut ancestor_path = path.clone();
+            loop {
+                parent.get_trigger(ancestor_path.clone()).this.track();
+                if ancestor_path.pop().is_none() {
+                    break;
+                }
+            }
+            path.push(index);
+            let trigger = parent.get_trigger(path);
+            trigger.this.track();
+            trigger.children.track();
+
+            parent
+                .reader()
+                .map(|value| value.validate_field(index))
+                .unwrap_or_default()
+        })
+    };
+    let new = match owner {
+        Some(owner) => owner.with(create),
+        None => create(),
+    };
+    *memo = Some(new);
+    new
+}
+
+impl<Inner, Prev, T> Subfield<Inner, Prev, T>
+where
+    Inner: StoreField<Value = Prev> + Clone + Send + Sync + 'static,
+    Prev: Validate + 'static,
+{
+    /// Returns a memo of the errors in the value of this field.
+    ///
+    /// The memo only checks the field again when the field itself changes, and only notifies its
+    /// subscribers when the errors change. It belongs to the store: it is created the first time
+    /// it is needed, and later calls, as well as [`is_valid`](StoreValidation::is_valid), return
+    /// the same memo until the store is disposed.
+    pub fn errors(&self) -> Memo<Vec<ValidationError>> {
+        field_errors(&self.inner, self.path_segment.0)
+    }
+}
+
+impl<Inner, Prev, K, T> KeyedSubfield<Inner, Prev, K, T>
+where
+    for<'a> &'a T: IntoIterator,
+    Inner: StoreField<Value = Prev> + Clone + Send + Sync + 'static,
+    Prev: Validate + 'static,
+    K: Debug + Send + Sync + PartialEq + Eq + Hash + 'static,
+{
+    /// Returns a memo of the errors in the value of this field.
+    ///
+    /// See [`Subfield::errors`].
+    pub fn errors(&self) -> Memo<Vec<ValidationError>> {
+        field_errors(&self.inner, self.path_segment.0)
+    }
+}
+
+#[doc(hidden)]
+pub fn validate_result<E>(
+    result: Result<(), E>,
+    errors: &mut Vec<ValidationError>,
+) where
+    E: Into<ValidationError>,
+{
+    if let Err(e) = result {
+        errors.push(e.into());
+    }
+}
+
+#[doc(hidden)]
+pub fn validate_range<T>(
+    value: &T,
+    range: impl RangeBounds<T> + Debug,
+    errors: &mut Vec<ValidationError>,
+) where
+    T: PartialOrd,
+{
+    if !range.contains(value) {
+        errors.push(ValidationError::new(format!(
+            "must be in the range {range:?}"
+        )));
+    }
+}
+
+#[doc(hidden)]
+pub fn validate_length(
+    value: &impl Len,
+    range: impl RangeBounds<usize> + Debug,
+    errors: &mut Vec<ValidationError>,
+) {
+    if !range.contains(&value.len()) {
+        errors.push(ValidationError::new(format!(
+            "length must be in the range {range:?}"
+        )));
+    }
+}
+
+#[cfg(feature = "regex")]
+#[doc(hidden)]
+pub struct RegexValidator {
+    pattern: &'static str,
+    regex: std::sync::OnceLock<regex::Regex>,
+}
+
+#[cfg(feature = "regex")]
+impl RegexValidator {
+    pub const fn new(pattern: &'static str) -> Self {
+        Self {
+            pattern,
+            regex: std::sync::OnceLock::new(),
+        }
+    }
+
+    pub fn validate(
+        &self,
+        value: &(impl AsRef<str> + ?Sized),
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let regex = self.regex.get_or_init(|| {
+            regex::Regex::new(self.pattern).unwrap_or_else(|e| {
+                panic!("invalid regex in `#[store(regex = ...)]`: {e}")
+            })
+        });
+        if !regex.is_match(value.as_ref()) {
+            errors.push(ValidationError::new(format!(
+                "must match the pattern {:?}",
+                self.pattern
+            )));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as reactive_stores, Store, StoreValidation, ValidationError,
+    };
+    use reactive_graph::{
+        effect::Effect,
+        traits::{Read, Set},
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    fn not_admin(name: &str) -> Result<(), &'static str> {
+        if name == "admin" {
+            Err("is reserved")
+        } else {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Store)]
+    struct Signup {
+        #[store(length = 3..=16, validate = not_admin)]
+        name: String,
+        #[store(range = 18..)]
+        age: u32,
+        newsletter: bool,
+    }
+
+    #[tokio::test]
+    async fn fields_are_validated_separately() {
+        _ = any_spawner::Executor::init_tokio();
+
+        let store = Store::new(Signup {
+            name: "al".into(),
+            age: 30,
+            newsletter: false,
+        });
+        let name_errors = store.name().errors();
+        let age_errors = store.age().errors();
+        assert_eq!(
+            *name_errors.read(),
+            [ValidationError::new("length must be in the range 3..=16")]
+        );
+        assert!(age_errors.read().is_empty());
+        assert!(!store.is_valid());
+
+        let age_runs = Arc::new(AtomicUsize::new(0));
+        let valid_runs = Arc::new(AtomicUsize::new(0));
+        Effect::new_sync({
+            let age_runs = Arc::clone(&age_runs);
+            move |_| {
+                age_errors.read();
+                age_runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Effect::new_sync({
+            let valid_runs = Arc::clone(&valid_runs);
+            move |_| {
+                store.is_valid();
+                valid_runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        crate::tests::tick().await;
+
+        store.name().set("admin".into());
+        crate::tests::tick().await;
+        assert_eq!(*name_errors.read(), [ValidationError::new("is reserved")]);
+        assert_eq!(age_runs.load(Ordering::Relaxed), 1);
+        assert_eq!(valid_runs.load(Ordering::Relaxed), 2);
+
+        // fields without validators are not tracked
+        store.newsletter().set(true);
+        crate::tests::tick().await;
+        assert_eq!(valid_runs.load(Ordering::Relaxed), 2);
+
+        store.name().set("alice".into());
+        store.age().set(12);
+        crate::tests::tick().await;
+        assert!(name_errors.read().is_empty());
+        assert_eq!(
+            *age_errors.read(),
+            [ValidationError::new("must be in the range 18..")]
+        );
+        assert_eq!(age_runs.load(Ordering::Relaxed), 2);
+        assert!(!store.is_valid());
+
+        store.age().set(18);
+        assert!(store.is_valid());
+    }
+
+    #[tokio::test]
+    async fn is_valid_only_checks_changed_fields() {
+        _ = any_spawner::Executor::init_tokio();
+
+        static NAME_CHECKS: AtomicUsize = AtomicUsize::new(0);
+        static AGE_CHECKS: AtomicUsize = AtomicUsize::new(0);
+
+        fn check_name(_: &str) -> Result<(), &'static str> {
+            NAME_CHECKS.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn check_age(age: &u32) -> Result<(), &'static str> {
+            AGE_CHECKS.fetch_add(1, Ordering::Relaxed);
+            if *age < 18 {
+                Err("too young")
+            } else {
+                Ok(())
+            }
+        }
+
+        #[derive(Debug, Store)]
+        struct Profile {
+            #[store(validate = check_name)]
+            name: String,
+            #[store(validate = check_age)]
+            age: u32,
+        }
+
+        let store = Store::new(Profile {
+            name: "Alice".into(),
+            age: 30,
+        });
+        let valid_runs = Arc::new(AtomicUsize::new(0));
+        Effect::new_sync({
+            let valid_runs = Arc::clone(&valid_runs);
+            move |_| {
+                store.is_valid();
+                valid_runs.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        crate::tests::tick().await;
+        assert_eq!(NAME_CHECKS.load(Ordering::Relaxed), 1);
+        assert_eq!(AGE_CHECKS.load(Ordering::Relaxed), 1);
+
+        // the memo that `is_valid` reads is the one returned by `errors`
+        assert_eq!(store.age().errors(), store.age().errors());
+        assert!(store.age().errors().read().is_empty());
+        assert_eq!(AGE_CHECKS.load(Ordering::Relaxed), 1);
+
+        // only the changed field is checked, and validity did not change
+        store.age().set(31);
+        crate::tests::tick().await;
+        assert_eq!(NAME_CHECKS.load(Ordering::Relaxed), 1);
+        assert_eq!(AGE_CHECKS.load(Ordering::Relaxed), 2);
+        assert_eq!(valid_runs.load(Ordering::Relaxed), 1);
+
+        store.age().set(12);
+        crate::tests::tick().await;
+        assert_eq!(NAME_CHECKS.load(Ordering::Relaxed), 1);
+        assert_eq!(valid_runs.load(Ordering::Relaxed), 2);
+        assert!(!store.is_valid());
+    }
+
+    #[cfg(feature = "regex")]
+    #[tokio::test]
+    async fn regex_validator() {
+        _ = any_spawner::Executor::init_tokio();
+
+        #[derive(Debug, Store)]
+        struct Account(#[store(regex = "^[a-z]+$")] String);
+
+        let store = Store::new(Account("Alice".into()));
+        assert_eq!(
+            *store.field0().errors().read(),
+            [ValidationError::new("must match the pattern \"^[a-z]+$\"")]
+        );
+        store.field0().set("alice".into());
+        assert!(store.is_valid());
+    }
+}
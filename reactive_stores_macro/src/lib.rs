@@ -3,11 +3,13 @@ use proc_macro2::{Span, TokenStream};
 use proc_macro_error2::{abort, abort_call_site, proc_macro_error, OptionExt};
 use quote::{quote, ToTokens};
 use syn::{
+    ext::IdentExt,
     parse::{Parse, ParseStream, Parser},
     punctuated::Punctuated,
     token::Comma,
-    ExprClosure, Field, Fields, GenericParam, Generics, Ident, Index, Meta,
-    Result, Token, Type, TypeParam, Variant, Visibility, WhereClause,
+    Attribute, Expr, ExprClosure, ExprLit, Field, Fields, GenericParam,
+    Generics, Ident, Index, Lit, LitStr, Meta, Result, Token, Type, TypeParam,
+    Variant, Visibility, WhereClause,
 };
 
 #[proc_macro_error]
@@ -26,6 +28,16 @@ pub fn derive_patch(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         .into()
 }
 
+#[proc_macro_error]
+#[proc_macro_derive(PatchDelta, attributes(store, patch))]
+pub fn derive_patch_delta(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    syn::parse_macro_input!(input as PatchDeltaModel)
+        .into_token_stream()
+        .into()
+}
+
 /// Removes all constraints from generics arguments list.
 ///
 /// # Example
@@ -178,6 +190,21 @@ impl Parse for Model {
 enum SubfieldMode {
     Keyed(Box<ExprClosure>, Box<Type>),
     Skip,
+    Validate(Validator),
+}
+
+/// A check on the value of a field, which is run by the generated `Validate` implementation.
+#[derive(Clone)]
+enum Validator {
+    /// `validate = path::to_fn`
+    With(Box<Expr>),
+    /// `range = 1..=10`
+    Range(Box<Expr>),
+    /// `length = 1..=64`
+    Length(Box<Expr>),
+    /// `regex = "^[a-z]+$"`
+    #[cfg(feature = "regex")]
+    Regex(LitStr),
 }
 
 impl Parse for SubfieldMode {
@@ -191,12 +218,98 @@ impl Parse for SubfieldMode {
             Ok(SubfieldMode::Keyed(Box::new(closure), Box::new(ty)))
         } else if mode == "skip" {
             Ok(SubfieldMode::Skip)
+        } else if mode == "validate" {
+            let _eq: Token![=] = input.parse()?;
+            Ok(SubfieldMode::Validate(Validator::With(input.parse()?)))
+        } else if mode == "range" {
+            let _eq: Token![=] = input.parse()?;
+            Ok(SubfieldMode::Validate(Validator::Range(input.parse()?)))
+        } else if mode == "length" {
+            let _eq: Token![=] = input.parse()?;
+            Ok(SubfieldMode::Validate(Validator::Length(input.parse()?)))
+        } else if mode == "regex" {
+            let _eq: Token![=] = input.parse()?;
+            Validator::regex(&mode, input.parse()?).map(SubfieldMode::Validate)
         } else {
-            Err(input.error("expected `key: <Type> = <closure>`"))
+            Err(input.error(
+                "expected `key: <Type> = <closure>`, `skip`, `validate = \
+                 <function>`, `range = <range>`, `length = <range>` or \
+                 `regex = \"<pattern>\"`",
+            ))
         }
     }
 }
 
+impl Validator {
+    /// Checks that a pattern is a valid regular expression when the macro is expanded, rather
+    /// than the first time a value is validated.
+    #[cfg(feature = "regex")]
+    fn regex(_mode: &Ident, pattern: LitStr) -> syn::Result<Self> {
+        match regex::Regex::new(&pattern.value()) {
+            Ok(_) => Ok(Validator::Regex(pattern)),
+            Err(e) => Err(syn::Error::new(
+                pattern.span(),
+                format!("invalid regex: {e}"),
+            )),
+        }
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn regex(mode: &Ident, _pattern: LitStr) -> syn::Result<Self> {
+        Err(syn::Error::new(
+            mode.span(),
+            "`regex` validators require the `regex` feature of \
+             `reactive_stores`",
+        ))
+    }
+
+    fn to_tokens(
+        &self,
+        library_path: &TokenStream,
+        value: &TokenStream,
+    ) -> TokenStream {
+        match self {
+            Validator::With(fun) => quote! {
+                #library_path::validate_result((#fun)(#value), &mut errors);
+            },
+            Validator::Range(range) => quote! {
+                #library_path::validate_range(#value, #range, &mut errors);
+            },
+            Validator::Length(range) => quote! {
+                #library_path::validate_length(#value, #range, &mut errors);
+            },
+            #[cfg(feature = "regex")]
+            Validator::Regex(pattern) => quote! {
+                {
+                    static REGEX: #library_path::RegexValidator =
+                        #library_path::RegexValidator::new(#pattern);
+                    REGEX.validate(#value, &mut errors);
+                }
+            },
+        }
+    }
+}
+
+/// Parses the modes in the `#[store(...)]` attribute of a field, if it has one.
+fn store_modes(attrs: &[Attribute]) -> Option<Vec<SubfieldMode>> {
+    attrs
+        .iter()
+        .find_map(|attr| {
+            attr.meta.path().is_ident("store").then(|| match &attr.meta {
+                Meta::List(list) => {
+                    match Punctuated::<SubfieldMode, Comma>::parse_terminated
+                        .parse2(list.tokens.clone())
+                    {
+                        Ok(modes) => Some(modes.into_iter().collect()),
+                        Err(e) => abort!(list, e),
+                    }
+                }
+                _ => None,
+            })
+        })
+        .flatten()
+}
+
 impl ToTokens for Model {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let library_path = quote! { reactive_stores };
@@ -254,9 +367,76 @@ impl ToTokens for Model {
                #(#read_fields)*
             }
         });
+
+        if let ModelTy::Struct { fields } = ty {
+            tokens.extend(validate_impl(
+                &library_path,
+                name,
+                generics,
+                &clear_generics,
+                fields,
+            ));
+        }
     }
 }
 
+/// Implements `Validate` for a struct, if any of its fields have validators.
+fn validate_impl(
+    library_path: &TokenStream,
+    name: &Ident,
+    generics: &Generics,
+    clear_generics: &Generics,
+    fields: &[Field],
+) -> Option<TokenStream> {
+    let (indices, checks): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, field)| {
+            let validators = store_modes(&field.attrs)?
+                .into_iter()
+                .filter_map(|mode| match mode {
+                    SubfieldMode::Validate(validator) => Some(validator),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if validators.is_empty() {
+                return None;
+            }
+            let locator = match &field.ident {
+                Some(ident) => Either::Left(ident),
+                None => Either::Right(Index::from(idx)),
+            };
+            let value = quote! { &self.#locator };
+            let checks = validators
+                .iter()
+                .map(|validator| validator.to_tokens(library_path, &value));
+            Some((idx, quote! { #(#checks)* }))
+        })
+        .unzip();
+    if indices.is_empty() {
+        return None;
+    }
+
+    let params = &clear_generics.params;
+    let where_clause = &generics.where_clause;
+    Some(quote! {
+        impl #generics #library_path::Validate for #name <#params>
+            #where_clause
+        {
+            const VALIDATED_FIELDS: &'static [usize] = &[#(#indices),*];
+
+            fn validate_field(&self, index: usize) -> Vec<#library_path::ValidationError> {
+                let mut errors = Vec::new();
+                match index {
+                    #(#indices => { #checks })*
+                    _ => {}
+                }
+                errors
+            }
+        }
+    })
+}
+
 impl ModelTy {
     fn to_field_data(
         &self,
@@ -274,32 +454,7 @@ impl ModelTy {
                     let Field {
                         ident, ty, attrs, ..
                     } = &field;
-                    let modes = attrs
-                        .iter()
-                        .find_map(|attr| {
-                            attr.meta.path().is_ident("store").then(|| {
-                                match &attr.meta {
-                                    Meta::List(list) => {
-                                        match Punctuated::<
-                                                SubfieldMode,
-                                                Comma,
-                                            >::parse_terminated
-                                                .parse2(list.tokens.clone())
-                                            {
-                                                Ok(modes) => Some(
-                                                    modes
-                                                        .iter()
-                                                        .cloned()
-                                                        .collect::<Vec<_>>(),
-                                                ),
-                                                Err(e) => abort!(list, e),
-                                            }
-                                    }
-                                    _ => None,
-                                }
-                            })
-                        })
-                        .flatten();
+                    let modes = store_modes(attrs);
 
                     (
                         field_to_tokens(
@@ -388,9 +543,15 @@ fn field_to_tokens(
         quote! { #ident }
     };
 
-    if let Some(modes) = modes {
+    // validators do not change how the field is accessed
+    let modes = modes
+        .unwrap_or_default()
+        .iter()
+        .filter(|mode| !matches!(mode, SubfieldMode::Validate(_)))
+        .collect::<Vec<_>>();
+    if !modes.is_empty() {
         if modes.len() == 1 {
-            let mode = &modes[0];
+            let mode = modes[0];
             match mode {
                 SubfieldMode::Keyed(keyed_by, key_ty) => {
                     let signature = quote! {
@@ -414,6 +575,7 @@ fn field_to_tokens(
                     };
                 }
                 SubfieldMode::Skip => return quote! {},
+                SubfieldMode::Validate(_) => {}
             }
         } else {
             abort!(
@@ -645,6 +807,7 @@ fn variant_to_tokens(
 }
 
 struct PatchModel {
+    pub attrs: Vec<Attribute>,
     pub name: Ident,
     pub generics: Generics,
     pub ty: PatchModelTy,
@@ -696,6 +859,7 @@ impl Parse for PatchModel {
         };
 
         Ok(Self {
+            attrs: input.attrs,
             name: input.ident,
             generics: input.generics,
             ty,
@@ -706,7 +870,9 @@ impl Parse for PatchModel {
 impl ToTokens for PatchModel {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let library_path = quote! { reactive_stores };
-        let PatchModel { name, generics, ty } = &self;
+        let PatchModel {
+            name, generics, ty, ..
+        } = &self;
 
         let fields = match ty {
             PatchModelTy::Struct { fields } => {
@@ -718,61 +884,11 @@ impl ToTokens for PatchModel {
                         Some(ident) => Either::Left(ident),
                         None => Either::Right(Index::from(idx)),
                     };
-                    let closure = attrs
-                        .iter()
-                        .find_map(|attr| {
-                            attr.meta.path().is_ident("patch").then(
-                                || match &attr.meta {
-                                    Meta::List(list) => {
-                                        match Punctuated::<
-                                                ExprClosure,
-                                                Comma,
-                                            >::parse_terminated
-                                                .parse2(list.tokens.clone())
-                                            {
-                                                Ok(closures) => {
-                                                    let closure = closures.iter().next().cloned().expect_or_abort("should have ONE closure");
-                                                    if closure.inputs.len() != 2 {
-                                                        abort!(closure.inputs, "patch closure should have TWO params as in #[patch(|this, new| ...)]");
-                                                    }
-                                                    closure
-                                                },
-                                                Err(e) => abort!(list, e),
-                                            }
-                                    }
-                                    _ => abort!(attr.meta, "needs to be as `#[patch(|this, new| ...)]`"),
-                                },
-                            )
-                        });
-                        let keyed = attrs
-                        .iter()
-                        .find_map(|attr| {
-                            attr.meta.path().is_ident("store").then(|| {
-                                match &attr.meta {
-                                    Meta::List(list) => {
-                                        let subfields = match Punctuated::<
-                                                SubfieldMode,
-                                                Comma,
-                                            >::parse_terminated
-                                                .parse2(list.tokens.clone())
-                                            {
-                                                Ok(modes) => Some(
-                                                    modes
-                                                        .iter()
-                                                        .cloned()
-                                                        .collect::<Vec<_>>(),
-                                                ),
-                                                Err(e) => abort!(list, e),
-                                            }.unwrap_or_default();
-                                            subfields.into_iter().find_map(|subfield| match subfield {
-                                                SubfieldMode::Keyed(closure, _ty) => Some(closure),
-                                                SubfieldMode::Skip => None,
-                                            })
-                                    }
-                                    _ => None,
-                                }
-                            })
-                        }).flatten();
+                    let (closure, keyed) = match patch_field_mode(attrs) {
+                        PatchFieldMode::Custom(closure) => (Some(closure), None),
+                        PatchFieldMode::Keyed(closure) => (None, Some(closure)),
+                        PatchFieldMode::Plain => (None, None),
+                    };
 
                     if let Some(closure) = closure {
                         let params = closure.inputs;
@@ -856,6 +972,350 @@ impl ToTokens for PatchModel {
     }
 }
 
+struct PatchDeltaModel(PatchModel);
+
+impl Parse for PatchDeltaModel {
+    fn parse(input: ParseStream) -> Result<Self> {
+        PatchModel::parse(input).map(Self)
+    }
+}
+
+impl ToTokens for PatchDeltaModel {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let library_path = quote! { reactive_stores };
+        let PatchModel {
+            attrs,
+            name,
+            generics,
+            ty,
+        } = &self.0;
+
+        let PatchModelTy::Struct { fields } = ty else {
+            unreachable!("not implemented currently")
+        };
+        let rename_all = serde_rename_all(attrs);
+        let names = fields.iter().enumerate().map(|(idx, field)| {
+            reject_unsupported_serde_field(&field.attrs);
+            serde_rename(&field.attrs).unwrap_or_else(|| match &field.ident {
+                Some(ident) => {
+                    let name = ident.unraw().to_string();
+                    match &rename_all {
+                        Some(rule) => rule.apply_to_field(&name),
+                        None => name,
+                    }
+                }
+                None => idx.to_string(),
+            })
+        });
+        let patches = fields.iter().enumerate().map(|(idx, field)| {
+            let locator = match &field.ident {
+                Some(ident) => Either::Left(ident),
+                None => Either::Right(Index::from(idx)),
+            };
+            let patch = match patch_field_mode(&field.attrs) {
+                PatchFieldMode::Custom(closure) => {
+                    let params = closure.inputs;
+                    let body = closure.body;
+                    quote! {
+                        #library_path::patch_delta_with(
+                            &mut self.#locator,
+                            pointer,
+                            operation,
+                            &new_path,
+                            notify,
+                            |this, new| {
+                                let (#params) = (this, new);
+                                _ = { #body };
+                            }
+                        )
+                    }
+                }
+                PatchFieldMode::Keyed(closure) => quote! {
+                    #library_path::PatchFieldDeltaKeyed::patch_field_delta_keyed(
+                        &mut self.#locator,
+                        pointer,
+                        operation,
+                        &new_path,
+                        notify,
+                        keys,
+                        #closure
+                    )
+                },
+                PatchFieldMode::Plain => quote! {
+                    #library_path::PatchFieldDelta::patch_field_delta(
+                        &mut self.#locator,
+                        pointer,
+                        operation,
+                        &new_path,
+                        notify,
+                        keys
+                    )
+                },
+            };
+            quote! { #idx => #patch, }
+        });
+
+        let clear_generics = remove_constraint_from_generics(generics);
+        let params = clear_generics.params;
+        let where_clause = &generics.where_clause;
+
+        tokens.extend(quote! {
+            impl #generics #library_path::PatchFieldDelta for #name <#params>
+               #where_clause
+            {
+                fn patch_field_delta(
+                    &mut self,
+                    pointer: &[::std::string::String],
+                    operation: #library_path::DeltaOperation,
+                    path: &#library_path::StorePath,
+                    notify: &mut dyn FnMut(&#library_path::StorePath),
+                    keys: Option<&#library_path::KeyMap>,
+                ) -> ::std::result::Result<(), #library_path::DeltaErrorKind> {
+                    #library_path::patch_struct_delta(
+                        pointer,
+                        operation,
+                        &[#(#names),*],
+                        |idx, pointer, operation| {
+                            let mut new_path = path.clone();
+                            new_path.push(idx);
+                            match idx {
+                                #(#patches)*
+                                _ => unreachable!(),
+                            }
+                        }
+                    )
+                }
+            }
+        });
+    }
+}
+
+/// Returns the items inside every `#[serde(...)]` attribute.
+fn serde_metas(attrs: &[Attribute]) -> Vec<Meta> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| {
+            let Meta::List(list) = &attr.meta else {
+                return None;
+            };
+            Punctuated::<Meta, Comma>::parse_terminated
+                .parse2(list.tokens.clone())
+                .ok()
+        })
+        .flatten()
+        .collect()
+}
+
+/// Returns the string given to the `#[serde(...)]` item with this name, and aborts if the item
+/// has separate values for serializing and deserializing.
+fn serde_str(meta: &Meta, name: &str) -> Option<LitStr> {
+    match meta {
+        Meta::NameValue(meta) if meta.path.is_ident(name) => {
+            match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(value),
+                    ..
+                }) => Some(value.clone()),
+                _ => None,
+            }
+        }
+        Meta::List(list) if list.path.is_ident(name) => abort!(
+            list,
+            "`PatchDelta` does not support different names for serializing \
+             and deserializing"
+        ),
+        _ => None,
+    }
+}
+
+/// Returns the name given to a field with `#[serde(rename = "...")]`, if any.
+fn serde_rename(attrs: &[Attribute]) -> Option<String> {
+    serde_metas(attrs)
+        .iter()
+        .find_map(|meta| serde_str(meta, "rename"))
+        .map(|name| name.value())
+}
+
+/// Returns the rule given to a struct with `#[serde(rename_all = "...")]`, if any.
+fn serde_rename_all(attrs: &[Attribute]) -> Option<RenameRule> {
+    serde_metas(attrs)
+        .iter()
+        .find_map(|meta| serde_str(meta, "rename_all"))
+        .map(|rule| {
+            RenameRule::from_str(&rule.value()).unwrap_or_else(|| {
+                abort!(rule, "unknown `rename_all` rule `{}`", rule.value())
+            })
+        })
+}
+
+/// Aborts if a field uses a `#[serde(...)]` item that changes where it appears in the
+/// serialized struct in a way that `PatchDelta` cannot follow.
+fn reject_unsupported_serde_field(attrs: &[Attribute]) {
+    for meta in serde_metas(attrs) {
+        let path = meta.path();
+        if ["flatten", "skip", "skip_serializing", "skip_deserializing"]
+            .iter()
+            .any(|name| path.is_ident(name))
+        {
+            abort!(
+                path,
+                "`#[serde({})]` is not supported by `PatchDelta`",
+                path.to_token_stream()
+            );
+        }
+    }
+}
+
+/// A `#[serde(rename_all = "...")]` rule, which renames fields in the same way that serde does.
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Renames a field, which is assumed to be in `snake_case`.
+    fn apply_to_field(&self, field: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => field.to_string(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            Self::Camel => {
+                let pascal = Self::Pascal.apply_to_field(field);
+                let mut chars = pascal.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_lowercase().to_string())
+                    .unwrap_or_default()
+                    + chars.as_str()
+            }
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => {
+                field.to_ascii_uppercase().replace('_', "-")
+            }
+        }
+    }
+}
+
+/// How a field of a struct that derives `Patch` or `PatchDelta` is patched.
+enum PatchFieldMode {
+    /// Patched with a custom `#[patch(|this, new| ...)]` closure.
+    Custom(ExprClosure),
+    /// A collection that is patched with a keyed diff, using its `#[store(key: ...)]` closure.
+    Keyed(Box<ExprClosure>),
+    /// Patched with its `PatchField` implementation.
+    Plain,
+}
+
+fn patch_field_mode(attrs: &[Attribute]) -> PatchFieldMode {
+    let closure = attrs.iter().find_map(|attr| {
+        attr.meta
+            .path()
+            .is_ident("patch")
+            .then(|| match &attr.meta {
+                Meta::List(list) => {
+                    match Punctuated::<ExprClosure, Comma>::parse_terminated
+                        .parse2(list.tokens.clone())
+                    {
+                        Ok(closures) => {
+                            let closure = closures
+                                .iter()
+                                .next()
+                                .cloned()
+                                .expect_or_abort("should have ONE closure");
+                            if closure.inputs.len() != 2 {
+                                abort!(
+                                    closure.inputs,
+                                    "patch closure should have TWO params as \
+                                     in #[patch(|this, new| ...)]"
+                                );
+                            }
+                            closure
+                        }
+                        Err(e) => abort!(list, e),
+                    }
+                }
+                _ => abort!(
+                    attr.meta,
+                    "needs to be as `#[patch(|this, new| ...)]`"
+                ),
+            })
+    });
+    let keyed = attrs
+        .iter()
+        .find_map(|attr| {
+            attr.meta
+                .path()
+                .is_ident("store")
+                .then(|| match &attr.meta {
+                    Meta::List(list) => {
+                        let subfields = match Punctuated::<
+                                SubfieldMode,
+                                Comma,
+                            >::parse_terminated
+                                .parse2(list.tokens.clone())
+                            {
+                                Ok(modes) => {
+                                    modes.into_iter().collect::<Vec<_>>()
+                                }
+                                Err(e) => abort!(list, e),
+                            };
+                        subfields.into_iter().find_map(
+                            |subfield| match subfield {
+                                SubfieldMode::Keyed(closure, _ty) => {
+                                    Some(closure)
+                                }
+                                SubfieldMode::Skip
+                                | SubfieldMode::Validate(_) => None,
+                            },
+                        )
+                    }
+                    _ => None,
+                })
+        })
+        .flatten();
+
+    match (closure, keyed) {
+        (Some(closure), _) => PatchFieldMode::Custom(closure),
+        (None, Some(closure)) => PatchFieldMode::Keyed(closure),
+        (None, None) => PatchFieldMode::Plain,
+    }
+}
+
 enum Either<A, B> {
     Left(A),
     Right(B),
@@ -0,0 +1,531 @@
+use crate::renderer::Renderer;
+use std::{fmt::Debug, marker::PhantomData};
+
+mod value;
+
+pub use value::*;
+
+/// Something that can be applied to an element: an attribute, class, style, or property, or a
+/// tuple of them.
+pub trait Attribute<R: Renderer>: Send {
+    /// The state that should be retained between building and rebuilding.
+    type State;
+
+    /// An approximation of the length of this attribute when rendered as HTML.
+    fn html_len(&self) -> usize;
+
+    /// Renders the attribute as HTML.
+    ///
+    /// Attributes are written into `buf`. Classes and styles are collected into `class` and
+    /// `style`, so that the element can write them as a single attribute each.
+    fn to_html(self, buf: &mut String, class: &mut String, style: &mut String);
+
+    /// Adds interactivity to an element that has already been rendered as HTML.
+    fn hydrate<const FROM_SERVER: bool>(self, el: &R::Element) -> Self::State;
+
+    /// Applies the attribute to an element.
+    fn build(self, el: &R::Element) -> Self::State;
+
+    /// Updates the element with the new value of the attribute.
+    fn rebuild(self, state: &mut Self::State);
+
+    /// Called when the element this attribute is applied to is removed from the tree.
+    ///
+    /// Most attributes do nothing here; event listeners use it to remove themselves.
+    fn unmount(_state: &mut Self::State) {}
+
+    /// Called when the element this attribute is applied to is inserted into the tree.
+    fn mount(_state: &mut Self::State) {}
+}
+
+/// Determines how the value of an [`Attr`] is applied to an element.
+pub trait AttributeKind: Send + 'static {
+    /// Converts a boolean into a value, where `None` means the value is absent.
+    fn from_bool(value: bool) -> Option<&'static str>;
+
+    /// Applies the value to an element, or removes it if the value is `None`.
+    fn apply<R: Renderer>(el: &R::Element, key: &str, value: Option<&str>);
+
+    /// Renders the value as HTML.
+    fn to_html(
+        key: &str,
+        value: Option<&str>,
+        buf: &mut String,
+        class: &mut String,
+        style: &mut String,
+    );
+}
+
+/// An HTML attribute, set with [`Renderer::set_attribute`].
+///
+/// A boolean value adds or removes the attribute.
+#[derive(Debug, Clone, Copy)]
+pub struct AttrKind;
+
+impl AttributeKind for AttrKind {
+    fn from_bool(value: bool) -> Option<&'static str> {
+        value.then_some("")
+    }
+
+    fn apply<R: Renderer>(el: &R::Element, key: &str, value: Option<&str>) {
+        match value {
+            Some(value) => R::set_attribute(el, key, value),
+            None => R::remove_attribute(el, key),
+        }
+    }
+
+    fn to_html(
+        key: &str,
+        value: Option<&str>,
+        buf: &mut String,
+        _class: &mut String,
+        _style: &mut String,
+    ) {
+        if let Some(value) = value {
+            buf.push(' ');
+            buf.push_str(key);
+            if !value.is_empty() {
+                buf.push_str("=\"");
+                buf.push_str(&html_escape::encode_double_quoted_attribute(
+                    value,
+                ));
+                buf.push('"');
+            }
+        }
+    }
+}
+
+/// A single class, which is added to the element when its value is present.
+#[derive(Debug, Clone, Copy)]
+pub struct ClassKind;
+
+impl AttributeKind for ClassKind {
+    fn from_bool(value: bool) -> Option<&'static str> {
+        value.then_some("")
+    }
+
+    fn apply<R: Renderer>(el: &R::Element, key: &str, value: Option<&str>) {
+        match value {
+            Some(_) => R::add_class(el, key),
+            None => R::remove_class(el, key),
+        }
+    }
+
+    fn to_html(
+        key: &str,
+        value: Option<&str>,
+        _buf: &mut String,
+        class: &mut String,
+        _style: &mut String,
+    ) {
+        if value.is_some() {
+            if !class.is_empty() {
+                class.push(' ');
+            }
+            class.push_str(&html_escape::encode_double_quoted_attribute(key));
+        }
+    }
+}
+
+/// A single property in the element's inline style.
+#[derive(Debug, Clone, Copy)]
+pub struct StyleKind;
+
+impl AttributeKind for StyleKind {
+    fn from_bool(_value: bool) -> Option<&'static str> {
+        None
+    }
+
+    fn apply<R: Renderer>(el: &R::Element, key: &str, value: Option<&str>) {
+        match value {
+            Some(value) => R::set_style_property(el, key, value),
+            None => R::remove_style_property(el, key),
+        }
+    }
+
+    fn to_html(
+        key: &str,
+        value: Option<&str>,
+        _buf: &mut String,
+        _class: &mut String,
+        style: &mut String,
+    ) {
+        if let Some(value) = value {
+            if !style.is_empty() {
+                style.push(' ');
+            }
+            style.push_str(&html_escape::encode_double_quoted_attribute(key));
+            style.push_str(": ");
+            style.push_str(&html_escape::encode_double_quoted_attribute(value));
+            style.push(';');
+        }
+    }
+}
+
+/// A property of the element, set with [`Renderer::set_property`].
+///
+/// Properties are not rendered as HTML. A boolean value is passed as `"true"` or `"false"`.
+#[derive(Debug, Clone, Copy)]
+pub struct PropKind;
+
+impl AttributeKind for PropKind {
+    fn from_bool(value: bool) -> Option<&'static str> {
+        Some(if value { "true" } else { "false" })
+    }
+
+    fn apply<R: Renderer>(el: &R::Element, key: &str, value: Option<&str>) {
+        match value {
+            Some(value) => R::set_property(el, key, value),
+            None => R::remove_property(el, key),
+        }
+    }
+
+    fn to_html(
+        _key: &str,
+        _value: Option<&str>,
+        _buf: &mut String,
+        _class: &mut String,
+        _style: &mut String,
+    ) {
+    }
+}
+
+/// A key and an [`AttributeValue`], applied to an element according to its [`AttributeKind`].
+pub struct Attr<K, V> {
+    key: &'static str,
+    value: V,
+    kind: PhantomData<K>,
+}
+
+impl<K, V> Debug for Attr<K, V>
+where
+    V: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Attr")
+            .field("kind", &std::any::type_name::<K>())
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<K, V> Clone for Attr<K, V>
+where
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key,
+            value: self.value.clone(),
+            kind: PhantomData,
+        }
+    }
+}
+
+impl<K, V> Attr<K, V> {
+    /// Creates a new attribute with the given key and value.
+    pub fn new(key: &'static str, value: V) -> Self {
+        Self {
+            key,
+            value,
+            kind: PhantomData,
+        }
+    }
+
+    /// The key of the attribute.
+    pub fn key(&self) -> &'static str {
+        self.key
+    }
+}
+
+/// Sets an HTML attribute.
+pub fn attr<V>(key: &'static str, value: V) -> Attr<AttrKind, V> {
+    Attr::new(key, value)
+}
+
+/// Adds a class when the value is `true` (or present).
+pub fn class<V>(name: &'static str, value: V) -> Attr<ClassKind, V> {
+    Attr::new(name, value)
+}
+
+/// Sets a property in the element's inline style.
+pub fn style<V>(name: &'static str, value: V) -> Attr<StyleKind, V> {
+    Attr::new(name, value)
+}
+
+/// Sets a property on the element.
+pub fn prop<V>(name: &'static str, value: V) -> Attr<PropKind, V> {
+    Attr::new(name, value)
+}
+
+impl<K, V, R> Attribute<R> for Attr<K, V>
+where
+    K: AttributeKind,
+    V: AttributeValue<R>,
+    R: Renderer,
+{
+    type State = V::State;
+
+    fn html_len(&self) -> usize {
+        self.key.len() + 4 + self.value.html_len()
+    }
+
+    fn to_html(self, buf: &mut String, class: &mut String, style: &mut String) {
+        self.value.to_html::<K>(self.key, buf, class, style);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(self, el: &R::Element) -> Self::State {
+        self.value.hydrate::<K, FROM_SERVER>(self.key, el)
+    }
+
+    fn build(self, el: &R::Element) -> Self::State {
+        self.value.build::<K>(self.key, el)
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        self.value.rebuild::<K>(self.key, state);
+    }
+}
+
+impl<R> Attribute<R> for ()
+where
+    R: Renderer,
+{
+    type State = ();
+
+    fn html_len(&self) -> usize {
+        0
+    }
+
+    fn to_html(
+        self,
+        _buf: &mut String,
+        _class: &mut String,
+        _style: &mut String,
+    ) {
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(self, _el: &R::Element) {}
+
+    fn build(self, _el: &R::Element) {}
+
+    fn rebuild(self, _state: &mut Self::State) {}
+}
+
+impl<A, R> Attribute<R> for (A,)
+where
+    A: Attribute<R>,
+    R: Renderer,
+{
+    type State = A::State;
+
+    fn html_len(&self) -> usize {
+        self.0.html_len()
+    }
+
+    fn to_html(self, buf: &mut String, class: &mut String, style: &mut String) {
+        self.0.to_html(buf, class, style);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(self, el: &R::Element) -> Self::State {
+        self.0.hydrate::<FROM_SERVER>(el)
+    }
+
+    fn build(self, el: &R::Element) -> Self::State {
+        self.0.build(el)
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        self.0.rebuild(state);
+    }
+
+    fn unmount(state: &mut Self::State) {
+        A::unmount(state);
+    }
+
+    fn mount(state: &mut Self::State) {
+        A::mount(state);
+    }
+}
+
+macro_rules! impl_attr_for_tuples {
+	($first:ident, $($ty:ident),* $(,)?) => {
+		impl<$first, $($ty),*, Rndr> Attribute<Rndr> for ($first, $($ty,)*)
+		where
+			$first: Attribute<Rndr>,
+			$($ty: Attribute<Rndr>),*,
+			Rndr: Renderer,
+		{
+			type State = ($first::State, $($ty::State,)*);
+
+			fn html_len(&self) -> usize {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				$first.html_len() $(+ $ty.html_len())*
+			}
+
+			fn to_html(
+				self,
+				buf: &mut String,
+				class: &mut String,
+				style: &mut String,
+			) {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				$first.to_html(buf, class, style);
+				$($ty.to_html(buf, class, style));*
+			}
+
+			fn hydrate<const FROM_SERVER: bool>(
+				self,
+				el: &Rndr::Element,
+			) -> Self::State {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				(
+					$first.hydrate::<FROM_SERVER>(el),
+					$($ty.hydrate::<FROM_SERVER>(el)),*
+				)
+			}
+
+			fn build(self, el: &Rndr::Element) -> Self::State {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				(
+					$first.build(el),
+					$($ty.build(el)),*
+				)
+			}
+
+			fn rebuild(self, state: &mut Self::State) {
+				paste::paste! {
+					let ([<$first:lower>], $([<$ty:lower>],)*) = self;
+					let ([<state_ $first:lower>], $([<state_ $ty:lower>],)*) = state;
+					[<$first:lower>].rebuild([<state_ $first:lower>]);
+					$([<$ty:lower>].rebuild([<state_ $ty:lower>]));*
+				}
+			}
+
+			fn unmount(state: &mut Self::State) {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = state;
+				$first::unmount($first);
+				$($ty::unmount($ty));*
+			}
+
+			fn mount(state: &mut Self::State) {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = state;
+				$first::mount($first);
+				$($ty::mount($ty));*
+			}
+		}
+	};
+}
+
+impl_attr_for_tuples!(A, B);
+impl_attr_for_tuples!(A, B, C);
+impl_attr_for_tuples!(A, B, C, D);
+impl_attr_for_tuples!(A, B, C, D, E);
+impl_attr_for_tuples!(A, B, C, D, E, F);
+impl_attr_for_tuples!(A, B, C, D, E, F, G);
+impl_attr_for_tuples!(A, B, C, D, E, F, G, H);
+impl_attr_for_tuples!(A, B, C, D, E, F, G, H, I);
+impl_attr_for_tuples!(A, B, C, D, E, F, G, H, I, J);
+impl_attr_for_tuples!(A, B, C, D, E, F, G, H, I, J, K);
+impl_attr_for_tuples!(A, B, C, D, E, F, G, H, I, J, K, L);
+impl_attr_for_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+impl_attr_for_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+impl_attr_for_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+impl_attr_for_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+#[cfg(test)]
+mod tests {
+    use super::{attr, class, prop, style, Attribute};
+    use crate::renderer::mock_dom::{Document, MockDom};
+
+    fn to_html(attrs: impl Attribute<MockDom>) -> (String, String, String) {
+        let (mut buf, mut class, mut style) =
+            (String::new(), String::new(), String::new());
+        attrs.to_html(&mut buf, &mut class, &mut style);
+        (buf, class, style)
+    }
+
+    #[test]
+    fn builds_and_rebuilds_static_attributes() {
+        let el = Document::create_element("input");
+        let mut state = Attribute::<MockDom>::build(
+            (attr("id", "a"), attr("disabled", true), prop("value", 1)),
+            &el,
+        );
+        assert_eq!(el.to_debug_html(), r#"<input id="a" disabled=""></input>"#);
+        assert_eq!(el.property("value").as_deref(), Some("1"));
+
+        Attribute::<MockDom>::rebuild(
+            (attr("id", "b"), attr("disabled", false), prop("value", 2)),
+            &mut state,
+        );
+        assert_eq!(el.to_debug_html(), r#"<input id="b"></input>"#);
+        assert_eq!(el.property("value").as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn optional_values_add_and_remove_attributes() {
+        let el = Document::create_element("a");
+        let mut state =
+            Attribute::<MockDom>::build(attr("href", None::<String>), &el);
+        assert_eq!(el.attribute("href"), None);
+
+        Attribute::<MockDom>::rebuild(
+            attr("href", Some(String::from("/"))),
+            &mut state,
+        );
+        assert_eq!(el.attribute("href").as_deref(), Some("/"));
+
+        Attribute::<MockDom>::rebuild(attr("href", None::<String>), &mut state);
+        assert_eq!(el.attribute("href"), None);
+    }
+
+    #[test]
+    fn toggles_classes_and_styles() {
+        let el = Document::create_element("div");
+        let mut state = Attribute::<MockDom>::build(
+            (
+                class("a", true),
+                class("b", true),
+                style("color", Some("red")),
+            ),
+            &el,
+        );
+        assert_eq!(el.attribute("class").as_deref(), Some("a b"));
+        assert_eq!(el.attribute("style").as_deref(), Some("color: red;"));
+
+        Attribute::<MockDom>::rebuild(
+            (
+                class("a", false),
+                class("b", true),
+                style("color", None::<&str>),
+            ),
+            &mut state,
+        );
+        assert_eq!(el.attribute("class").as_deref(), Some("b"));
+        assert_eq!(el.attribute("style"), None);
+    }
+
+    #[test]
+    fn renders_attributes_as_html() {
+        let (buf, class, style) = to_html((
+            attr("title", "a \"quote\""),
+            attr("hidden", true),
+            attr("lang", None::<&str>),
+            class("a", true),
+            class("b", false),
+            style("color", "red"),
+            prop("value", "x"),
+        ));
+        assert_eq!(buf, r#" title="a &quot;quote&quot;" hidden"#);
+        assert_eq!(class, "a");
+        assert_eq!(style, "color: red;");
+    }
+}
@@ -0,0 +1,217 @@
+use super::AttributeKind;
+use crate::renderer::Renderer;
+use std::{borrow::Cow, sync::Arc};
+
+/// A value that can be applied to an element as part of an [`Attr`](super::Attr).
+///
+/// The value is generic over the [`AttributeKind`], so the same value types can be used for
+/// attributes, classes, styles, and properties.
+pub trait AttributeValue<R: Renderer>: Send {
+    /// The state that should be retained between building and rebuilding.
+    type State;
+
+    /// An approximation of the length of this value when rendered as HTML.
+    fn html_len(&self) -> usize;
+
+    /// Renders the value as HTML.
+    fn to_html<K: AttributeKind>(
+        self,
+        key: &str,
+        buf: &mut String,
+        class: &mut String,
+        style: &mut String,
+    );
+
+    /// Adds interactivity to an element that has already been rendered as HTML.
+    ///
+    /// If `FROM_SERVER` is `true`, the value is assumed to already be present on the element.
+    fn hydrate<K: AttributeKind, const FROM_SERVER: bool>(
+        self,
+        key: &'static str,
+        el: &R::Element,
+    ) -> Self::State;
+
+    /// Applies the value to the element.
+    fn build<K: AttributeKind>(
+        self,
+        key: &'static str,
+        el: &R::Element,
+    ) -> Self::State;
+
+    /// Updates the element with the new value.
+    fn rebuild<K: AttributeKind>(
+        self,
+        key: &'static str,
+        state: &mut Self::State,
+    );
+}
+
+/// Implements [`AttributeValue`] for a type that is not reactive, by converting it to a string.
+macro_rules! static_value {
+    ($ty:ty, |$this:ident| $value:expr, $len:expr) => {
+        impl<R> $crate::html::attribute::AttributeValue<R> for $ty
+        where
+            R: $crate::renderer::Renderer,
+        {
+            type State = (R::Element, Self);
+
+            fn html_len(&self) -> usize {
+                #[allow(unused_variables)]
+                let $this = self;
+                $len
+            }
+
+            fn to_html<K: $crate::html::attribute::AttributeKind>(
+                self,
+                key: &str,
+                buf: &mut String,
+                class: &mut String,
+                style: &mut String,
+            ) {
+                let $this = &self;
+                let value = $value;
+                K::to_html(
+                    key,
+                    value.as_ref().map(AsRef::<str>::as_ref),
+                    buf,
+                    class,
+                    style,
+                );
+            }
+
+            fn hydrate<
+                K: $crate::html::attribute::AttributeKind,
+                const FROM_SERVER: bool,
+            >(
+                self,
+                key: &'static str,
+                el: &R::Element,
+            ) -> Self::State {
+                if !FROM_SERVER {
+                    let $this = &self;
+                    let value = $value;
+                    K::apply::<R>(
+                        el,
+                        key,
+                        value.as_ref().map(AsRef::<str>::as_ref),
+                    );
+                }
+                (el.clone(), self)
+            }
+
+            fn build<K: $crate::html::attribute::AttributeKind>(
+                self,
+                key: &'static str,
+                el: &R::Element,
+            ) -> Self::State {
+                let $this = &self;
+                let value = $value;
+                K::apply::<R>(
+                    el,
+                    key,
+                    value.as_ref().map(AsRef::<str>::as_ref),
+                );
+                (el.clone(), self)
+            }
+
+            fn rebuild<K: $crate::html::attribute::AttributeKind>(
+                self,
+                key: &'static str,
+                state: &mut Self::State,
+            ) {
+                let (el, prev) = state;
+                if &self != prev {
+                    let $this = &self;
+                    let value = $value;
+                    K::apply::<R>(
+                        el,
+                        key,
+                        value.as_ref().map(AsRef::<str>::as_ref),
+                    );
+                    *prev = self;
+                }
+            }
+        }
+    };
+}
+
+#[allow(unused_imports)] // only used with the `oco` feature
+pub(crate) use static_value;
+
+static_value!(&'static str, |this| Some(*this), this.len());
+static_value!(String, |this| Some(this), this.len());
+static_value!(Arc<str>, |this| Some(this), this.len());
+static_value!(Cow<'static, str>, |this| Some(this), this.len());
+static_value!(bool, |this| K::from_bool(*this), 0);
+
+macro_rules! static_primitive_value {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            static_value!($ty, |this| Some(this.to_string()), 0);
+        )*
+    };
+}
+
+static_primitive_value![
+    char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32,
+    f64,
+];
+
+impl<V, R> AttributeValue<R> for Option<V>
+where
+    V: AttributeValue<R>,
+    R: Renderer,
+{
+    type State = (R::Element, Option<V::State>);
+
+    fn html_len(&self) -> usize {
+        self.as_ref().map(V::html_len).unwrap_or(0)
+    }
+
+    fn to_html<K: AttributeKind>(
+        self,
+        key: &str,
+        buf: &mut String,
+        class: &mut String,
+        style: &mut String,
+    ) {
+        if let Some(value) = self {
+            value.to_html::<K>(key, buf, class, style);
+        }
+    }
+
+    fn hydrate<K: AttributeKind, const FROM_SERVER: bool>(
+        self,
+        key: &'static str,
+        el: &R::Element,
+    ) -> Self::State {
+        let state = self.map(|value| value.hydrate::<K, FROM_SERVER>(key, el));
+        (el.clone(), state)
+    }
+
+    fn build<K: AttributeKind>(
+        self,
+        key: &'static str,
+        el: &R::Element,
+    ) -> Self::State {
+        let state = self.map(|value| value.build::<K>(key, el));
+        (el.clone(), state)
+    }
+
+    fn rebuild<K: AttributeKind>(
+        self,
+        key: &'static str,
+        state: &mut Self::State,
+    ) {
+        let (el, prev) = state;
+        match (self, prev.as_mut()) {
+            (Some(value), Some(prev)) => value.rebuild::<K>(key, prev),
+            (Some(value), None) => *prev = Some(value.build::<K>(key, el)),
+            (None, Some(_)) => {
+                K::apply::<R>(el, key, None);
+                *prev = None;
+            }
+            (None, None) => {}
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! Event delegation adds a single listener for each type of event to a root element, rather
+//! than one listener per element. When an event reaches the root, it is dispatched to the
+//! handlers registered on its target and each of the target's ancestors, in that order.
+//!
+//! This is an optional alternative to [`on`](crate::html::event::on): it trades a lookup during
+//! dispatch for fewer listeners, which can be useful for large lists.
+
+use crate::{
+    html::{attribute::Attribute, event::SharedHandler},
+    renderer::EventRenderer,
+};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    rc::Rc,
+};
+
+struct Handler<R>
+where
+    R: EventRenderer,
+{
+    id: usize,
+    name: &'static str,
+    node: R::Node,
+    cb: SharedHandler<R::Event>,
+}
+
+struct Registry<R>
+where
+    R: EventRenderer,
+{
+    next_id: usize,
+    handlers: Vec<Handler<R>>,
+}
+
+impl<R> Default for Registry<R>
+where
+    R: EventRenderer,
+{
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            handlers: Vec::new(),
+        }
+    }
+}
+
+thread_local! {
+    static REGISTRIES: RefCell<HashMap<TypeId, Box<dyn Any>>> = Default::default();
+}
+
+fn with_registry<R, T>(fun: impl FnOnce(&mut Registry<R>) -> T) -> T
+where
+    R: EventRenderer,
+{
+    REGISTRIES.with_borrow_mut(|registries| {
+        let registry = registries
+            .entry(TypeId::of::<R>())
+            .or_insert_with(|| Box::new(Registry::<R>::default()));
+        fun(registry
+            .downcast_mut()
+            .expect("delegation registry should match its renderer"))
+    })
+}
+
+/// Adds a single listener for the event to `root`, which dispatches it to the handlers added
+/// with [`on_delegated`] on the event's target and its ancestors.
+///
+/// This should only be called once for each event. It returns the handle for the root listener,
+/// which can be passed to [`EventRenderer::remove_event_listener`] to stop delegating the event.
+pub fn delegate_event<R>(
+    root: &R::Element,
+    name: &'static str,
+) -> R::ListenerHandle
+where
+    R: EventRenderer,
+    R::Node: PartialEq,
+    R::Event: Clone,
+{
+    R::add_event_listener(
+        root,
+        name,
+        Box::new(move |ev| dispatch::<R>(name, ev)),
+    )
+}
+
+fn dispatch<R>(name: &'static str, ev: R::Event)
+where
+    R: EventRenderer,
+    R::Node: PartialEq,
+    R::Event: Clone,
+{
+    let mut node = R::event_target(&ev);
+    while let Some(current) = node {
+        // collect the handlers first, so that they can add or remove handlers themselves
+        let handlers = with_registry::<R, _>(|registry| {
+            registry
+                .handlers
+                .iter()
+                .filter(|handler| {
+                    handler.name == name && handler.node == current
+                })
+                .map(|handler| Rc::clone(&handler.cb))
+                .collect::<Vec<_>>()
+        });
+        for handler in handlers {
+            (handler.borrow_mut())(ev.clone());
+        }
+        node = R::get_parent(&current);
+    }
+}
+
+/// An event handler that is called by a delegated listener (see [`delegate_event`]), rather
+/// than being added to the element itself.
+pub struct OnDelegated<E, F> {
+    name: &'static str,
+    handler: F,
+    event: PhantomData<fn(E)>,
+}
+
+impl<E, F> Debug for OnDelegated<E, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnDelegated")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Handles the event with the given name, using the delegated listener for that event.
+pub fn on_delegated<E, F>(name: &'static str, handler: F) -> OnDelegated<E, F>
+where
+    F: FnMut(E) + Send + 'static,
+{
+    OnDelegated {
+        name,
+        handler,
+        event: PhantomData,
+    }
+}
+
+/// Retained state for an [`OnDelegated`] event handler.
+pub struct OnDelegatedState<R>
+where
+    R: EventRenderer,
+{
+    node: R::Node,
+    name: &'static str,
+    handler: SharedHandler<R::Event>,
+    id: Option<usize>,
+}
+
+impl<R> Debug for OnDelegatedState<R>
+where
+    R: EventRenderer,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnDelegatedState")
+            .field("name", &self.name)
+            .field("registered", &self.id.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R> OnDelegatedState<R>
+where
+    R: EventRenderer,
+{
+    fn register(&mut self) {
+        if self.id.is_none() {
+            let handler = Handler {
+                id: 0,
+                name: self.name,
+                node: self.node.clone(),
+                cb: Rc::clone(&self.handler),
+            };
+            self.id = Some(with_registry::<R, _>(|registry| {
+                let id = registry.next_id;
+                registry.next_id += 1;
+                registry.handlers.push(Handler { id, ..handler });
+                id
+            }));
+        }
+    }
+
+    fn unregister(&mut self) {
+        if let Some(id) = self.id.take() {
+            with_registry::<R, _>(|registry| {
+                registry.handlers.retain(|handler| handler.id != id)
+            });
+        }
+    }
+}
+
+impl<R> Drop for OnDelegatedState<R>
+where
+    R: EventRenderer,
+{
+    fn drop(&mut self) {
+        self.unregister();
+    }
+}
+
+impl<F, R> Attribute<R> for OnDelegated<R::Event, F>
+where
+    F: FnMut(R::Event) + Send + 'static,
+    R: EventRenderer,
+{
+    type State = OnDelegatedState<R>;
+
+    fn html_len(&self) -> usize {
+        0
+    }
+
+    fn to_html(
+        self,
+        _buf: &mut String,
+        _class: &mut String,
+        _style: &mut String,
+    ) {
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(self, el: &R::Element) -> Self::State {
+        self.build(el)
+    }
+
+    fn build(self, el: &R::Element) -> Self::State {
+        let mut state = OnDelegatedState {
+            node: el.as_ref().clone(),
+            name: self.name,
+            handler: Rc::new(RefCell::new(Box::new(self.handler))),
+            id: None,
+        };
+        state.register();
+        state
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        *state.handler.borrow_mut() = Box::new(self.handler);
+        if state.name != self.name {
+            let registered = state.id.is_some();
+            state.unregister();
+            state.name = self.name;
+            if registered {
+                state.register();
+            }
+        }
+    }
+
+    fn unmount(state: &mut Self::State) {
+        state.unregister();
+    }
+
+    fn mount(state: &mut Self::State) {
+        state.register();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{delegate_event, on_delegated, OnDelegated};
+    use crate::{
+        html::attribute::Attribute,
+        renderer::{
+            mock_dom::{Document, Event, MockDom},
+            Renderer,
+        },
+    };
+    use std::sync::Mutex;
+
+    static LOG: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    type Handler = OnDelegated<Event, fn(Event)>;
+
+    #[test]
+    fn delegated_events_bubble_to_handlers() {
+        let root = Document::create_element("ul");
+        let li = Document::create_element("li");
+        let text = MockDom::create_text_node("item");
+        MockDom::insert_node(&root, li.as_ref(), None);
+        MockDom::insert_node(&li, text.as_ref(), None);
+        delegate_event::<MockDom>(&root, "click");
+        assert_eq!(Document::listener_count(&root), 1);
+
+        let on_li: Handler =
+            on_delegated("click", |_| LOG.lock().unwrap().push("li"));
+        let on_root: Handler =
+            on_delegated("click", |_| LOG.lock().unwrap().push("ul"));
+        let mut li_state = Attribute::<MockDom>::build(on_li, &li);
+        let _root_state = Attribute::<MockDom>::build(on_root, &root);
+        assert_eq!(Document::listener_count(&li), 0);
+
+        Document::dispatch(Event::new("click", &text));
+        assert_eq!(*LOG.lock().unwrap(), ["li", "ul"]);
+
+        <Handler as Attribute<MockDom>>::unmount(&mut li_state);
+        Document::dispatch(Event::new("click", &text));
+        assert_eq!(*LOG.lock().unwrap(), ["li", "ul", "ul"]);
+    }
+}
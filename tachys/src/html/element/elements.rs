@@ -0,0 +1,68 @@
+use super::{ElementType, HtmlElement};
+
+macro_rules! html_elements {
+    ($($tag:ident $ty:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("The `<", stringify!($tag), ">` element.")]
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+            pub struct $ty;
+
+            impl ElementType for $ty {
+                const TAG: &'static str = stringify!($tag);
+                const SELF_CLOSING: bool = false;
+
+                fn tag(&self) -> &str {
+                    Self::TAG
+                }
+            }
+
+            #[doc = concat!("Creates a `<", stringify!($tag), ">` element.")]
+            pub fn $tag<At, Ch>(
+                attributes: At,
+                children: Ch,
+            ) -> HtmlElement<$ty, At, Ch> {
+                HtmlElement::new($ty, attributes, children)
+            }
+        )*
+    };
+}
+
+macro_rules! html_void_elements {
+    ($($tag:ident $ty:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("The `<", stringify!($tag), ">` element.")]
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+            pub struct $ty;
+
+            impl ElementType for $ty {
+                const TAG: &'static str = stringify!($tag);
+                const SELF_CLOSING: bool = true;
+
+                fn tag(&self) -> &str {
+                    Self::TAG
+                }
+            }
+
+            #[doc = concat!("Creates a `<", stringify!($tag), ">` element.")]
+            pub fn $tag<At>(attributes: At) -> HtmlElement<$ty, At, ()> {
+                HtmlElement::new($ty, attributes, ())
+            }
+        )*
+    };
+}
+
+html_elements![
+    a A, article Article, aside Aside, b B, body Body, button Button,
+    code Code, details Details, div Div, em Em, footer Footer, form Form,
+    h1 H1, h2 H2, h3 H3, h4 H4, h5 H5, h6 H6, head Head, header Header,
+    html Html, i I, label Label, li Li, main Main, nav Nav, ol Ol,
+    option Option_, p P, pre Pre, section Section, select Select,
+    span Span, strong Strong, summary Summary, table Table, tbody Tbody,
+    td Td, textarea Textarea, th Th, thead Thead, title Title, tr Tr,
+    ul Ul,
+];
+
+html_void_elements![
+    area Area, base Base, br Br, col Col, embed Embed, hr Hr, img Img,
+    input Input, link Link, meta Meta, source Source, track Track, wbr Wbr,
+];
@@ -0,0 +1,547 @@
+use crate::{
+    html::{
+        attribute::Attribute,
+        event::{on, On},
+    },
+    hydration::{failed_to_cast_element, Cursor, Position, PositionState},
+    renderer::{CastFrom, ElementRenderer, Renderer},
+    ssr::StreamBuilder,
+    view::{Mountable, Render, RenderHtml},
+};
+use std::fmt::Debug;
+
+mod elements;
+
+pub use elements::*;
+
+/// A type of element, which an [`ElementRenderer`] knows how to create.
+///
+/// The HTML elements in this module implement this trait, but other renderers can define their
+/// own sets of typed tags (for example, the widgets in a terminal UI) by implementing it for
+/// their own types.
+pub trait ElementType: Send + 'static {
+    /// The tag name of the element.
+    ///
+    /// This is empty for elements whose tag is only known at runtime, like [`Custom`].
+    const TAG: &'static str;
+
+    /// Whether this element has no children, and is rendered without a closing tag.
+    const SELF_CLOSING: bool;
+
+    /// The tag name of this element.
+    fn tag(&self) -> &str;
+}
+
+/// An element whose tag name is set at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Custom(pub &'static str);
+
+impl ElementType for Custom {
+    const TAG: &'static str = "";
+    const SELF_CLOSING: bool = false;
+
+    fn tag(&self) -> &str {
+        self.0
+    }
+}
+
+/// Creates an element with a tag name that is set at runtime.
+pub fn custom<At, Ch>(
+    tag: &'static str,
+    attributes: At,
+    children: Ch,
+) -> HtmlElement<Custom, At, Ch> {
+    HtmlElement::new(Custom(tag), attributes, children)
+}
+
+/// An element view, with a typed tag, attributes, and children.
+pub struct HtmlElement<E, At, Ch> {
+    tag: E,
+    attributes: At,
+    children: Ch,
+}
+
+impl<E, At, Ch> Debug for HtmlElement<E, At, Ch>
+where
+    E: ElementType,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HtmlElement")
+            .field("tag", &self.tag.tag())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E, At, Ch> Clone for HtmlElement<E, At, Ch>
+where
+    E: Clone,
+    At: Clone,
+    Ch: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            tag: self.tag.clone(),
+            attributes: self.attributes.clone(),
+            children: self.children.clone(),
+        }
+    }
+}
+
+impl<E, At, Ch> HtmlElement<E, At, Ch> {
+    /// Creates a new element view.
+    pub fn new(tag: E, attributes: At, children: Ch) -> Self {
+        Self {
+            tag,
+            attributes,
+            children,
+        }
+    }
+
+    /// The tag of this element.
+    pub fn tag(&self) -> &E {
+        &self.tag
+    }
+
+    /// Adds an attribute (or tuple of attributes) to the element.
+    pub fn attr<NewAt>(
+        self,
+        attribute: NewAt,
+    ) -> HtmlElement<E, (At, NewAt), Ch> {
+        HtmlElement {
+            tag: self.tag,
+            attributes: (self.attributes, attribute),
+            children: self.children,
+        }
+    }
+
+    /// Adds an event listener to the element.
+    pub fn on<Ev, F>(
+        self,
+        name: &'static str,
+        handler: F,
+    ) -> HtmlElement<E, (At, On<Ev, F>), Ch>
+    where
+        F: FnMut(Ev) + Send + 'static,
+    {
+        self.attr(on(name, handler))
+    }
+
+    /// Adds a child view after the element's existing children.
+    pub fn child<NewCh>(self, child: NewCh) -> HtmlElement<E, At, (Ch, NewCh)> {
+        HtmlElement {
+            tag: self.tag,
+            attributes: self.attributes,
+            children: (self.children, child),
+        }
+    }
+}
+
+/// Retained view state for an [`HtmlElement`].
+pub struct ElementState<E, At, Ch, R>
+where
+    At: Attribute<R>,
+    Ch: Render<R>,
+    R: Renderer,
+{
+    tag: E,
+    pub(crate) el: R::Element,
+    attributes: At::State,
+    children: Ch::State,
+    // whether the children were unmounted along with the element
+    children_unmounted: bool,
+}
+
+impl<E, At, Ch, R> ElementState<E, At, Ch, R>
+where
+    At: Attribute<R>,
+    Ch: Render<R>,
+    R: Renderer,
+{
+    /// The element that was created for this view.
+    pub fn element(&self) -> &R::Element {
+        &self.el
+    }
+}
+
+impl<E, At, Ch, R> Render<R> for HtmlElement<E, At, Ch>
+where
+    E: ElementType,
+    At: Attribute<R> + 'static,
+    Ch: Render<R>,
+    R: ElementRenderer,
+{
+    type State = ElementState<E, At, Ch, R>;
+
+    fn build(self) -> Self::State {
+        let el = R::create_element(&self.tag);
+        let attributes = self.attributes.build(&el);
+        let mut children = self.children.build();
+        // void elements can't have children, so their (empty) children are never mounted
+        if !E::SELF_CLOSING {
+            children.mount(&el, None);
+        }
+        ElementState {
+            tag: self.tag,
+            el,
+            attributes,
+            children,
+            children_unmounted: false,
+        }
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        // only elements whose tag is set at runtime can change their tag
+        if E::TAG.is_empty() && self.tag.tag() != state.tag.tag() {
+            let mut new = self.build();
+            state.insert_before_this(&mut new);
+            state.unmount();
+            *state = new;
+        } else {
+            self.attributes.rebuild(&mut state.attributes);
+            self.children.rebuild(&mut state.children);
+        }
+    }
+}
+
+impl<E, At, Ch, R> RenderHtml<R> for HtmlElement<E, At, Ch>
+where
+    E: ElementType,
+    At: Attribute<R> + 'static,
+    Ch: RenderHtml<R>,
+    R: ElementRenderer,
+{
+    type AsyncOutput = HtmlElement<E, At, Ch::AsyncOutput>;
+
+    const MIN_LENGTH: usize = if E::SELF_CLOSING {
+        E::TAG.len() + 2
+    } else {
+        E::TAG.len() * 2 + 5 + Ch::MIN_LENGTH
+    };
+
+    fn dry_resolve(&mut self) {
+        self.children.dry_resolve();
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        HtmlElement {
+            tag: self.tag,
+            attributes: self.attributes,
+            children: self.children.resolve().await,
+        }
+    }
+
+    fn html_len(&self) -> usize {
+        self.tag.tag().len() * 2
+            + 5
+            + self.attributes.html_len()
+            + self.children.html_len()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        // elements are markup, so plain text only includes their children
+        if !escape {
+            self.children.to_html_with_buf(buf, escape);
+            return;
+        }
+        open_tag::<R>(self.tag.tag(), self.attributes, buf);
+        if !E::SELF_CLOSING {
+            self.children.to_html_with_buf(buf, escape);
+            close_tag(self.tag.tag(), buf);
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        if !escape {
+            self.children.to_html_async_with_buf(buf, escape);
+            return;
+        }
+        buf.with_buf(|buf| open_tag::<R>(self.tag.tag(), self.attributes, buf));
+        if !E::SELF_CLOSING {
+            self.children.to_html_async_with_buf(buf, escape);
+            buf.with_buf(|buf| close_tag(self.tag.tag(), buf));
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let node = cursor.next_node(position);
+        let el = R::Element::cast_from(node.clone())
+            .unwrap_or_else(|| failed_to_cast_element::<R>(node));
+        let attributes = self.attributes.hydrate::<FROM_SERVER>(&el);
+
+        // hydrate the children, then move the cursor back to this element
+        let children = if E::SELF_CLOSING {
+            self.children.build()
+        } else {
+            position.set(Position::FirstChild);
+            let children =
+                self.children.hydrate::<FROM_SERVER>(cursor, position);
+            cursor.set(el.as_ref().clone());
+            children
+        };
+        position.set(Position::NextChild);
+
+        ElementState {
+            tag: self.tag,
+            el,
+            attributes,
+            children,
+            children_unmounted: false,
+        }
+    }
+}
+
+fn open_tag<R: Renderer>(
+    tag: &str,
+    attributes: impl Attribute<R>,
+    buf: &mut String,
+) {
+    let (mut class, mut style) = (String::new(), String::new());
+    buf.push('<');
+    buf.push_str(tag);
+    attributes.to_html(buf, &mut class, &mut style);
+    if !class.is_empty() {
+        buf.push_str(" class=\"");
+        buf.push_str(&class);
+        buf.push('"');
+    }
+    if !style.is_empty() {
+        buf.push_str(" style=\"");
+        buf.push_str(&style);
+        buf.push('"');
+    }
+    buf.push('>');
+}
+
+fn close_tag(tag: &str, buf: &mut String) {
+    buf.push_str("</");
+    buf.push_str(tag);
+    buf.push('>');
+}
+
+impl<E, At, Ch, R> Mountable<R> for ElementState<E, At, Ch, R>
+where
+    E: ElementType,
+    At: Attribute<R>,
+    Ch: Render<R>,
+    R: Renderer,
+{
+    fn unmount(&mut self) {
+        R::remove(self.el.as_ref());
+        At::unmount(&mut self.attributes);
+        // the children may have listeners of their own
+        if !E::SELF_CLOSING && !self.children_unmounted {
+            self.children.unmount();
+            self.children_unmounted = true;
+        }
+    }
+
+    fn mount(&mut self, parent: &R::Element, marker: Option<&R::Node>) {
+        if self.children_unmounted {
+            self.children.mount(&self.el, None);
+            self.children_unmounted = false;
+        }
+        R::insert_node(parent, self.el.as_ref(), marker);
+        At::mount(&mut self.attributes);
+    }
+
+    fn insert_before_this(&self, child: &mut dyn Mountable<R>) -> bool {
+        self.el.insert_before_this(child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{custom, div, input, li, ul, HtmlElement};
+    use crate::{
+        html::attribute::{attr, class, style},
+        renderer::mock_dom::{Document, Event, MockDom},
+        view::{Mountable, Render, RenderHtml},
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn builds_elements_with_attributes_and_children() {
+        let root = Document::create_element("body");
+        let view = div(
+            (attr("id", "app"), class("dark", true)),
+            (
+                ul((), (li((), "a"), li((), 1))),
+                input(attr("disabled", true)),
+            ),
+        );
+        Render::<MockDom>::build(view).mount(&root, None);
+        assert_eq!(
+            root.to_debug_html(),
+            "<body><div id=\"app\" class=\"dark\"><ul><li>a</li><li>1</li></ul>\
+             <input disabled=\"\"></input></div></body>"
+        );
+    }
+
+    #[test]
+    fn rebuilds_attributes_and_children_in_place() {
+        let root = Document::create_element("body");
+        let view = |n: i32| div(attr("data-n", n), (custom("x-count", (), n),));
+        let mut state = Render::<MockDom>::build(view(1));
+        state.mount(&root, None);
+        let el = state.element().clone();
+        Render::<MockDom>::rebuild(view(2), &mut state);
+        assert_eq!(
+            root.to_debug_html(),
+            "<body><div data-n=\"2\"><x-count>2</x-count></div></body>"
+        );
+        assert_eq!(state.element(), &el);
+    }
+
+    #[test]
+    fn rebuilding_with_another_tag_replaces_the_element() {
+        let root = Document::create_element("body");
+        let view = |tag| (custom(tag, attr("id", tag), "text"), "end");
+        let mut state = Render::<MockDom>::build(view("a"));
+        state.mount(&root, None);
+        let el = state.0.element().clone();
+        Render::<MockDom>::rebuild(view("b"), &mut state);
+        assert_eq!(
+            root.to_debug_html(),
+            "<body><b id=\"b\">text</b>end</body>"
+        );
+        assert_ne!(state.0.element(), &el);
+
+        // the new element is updated in place from then on
+        let el = state.0.element().clone();
+        Render::<MockDom>::rebuild(view("b"), &mut state);
+        assert_eq!(state.0.element(), &el);
+    }
+
+    #[test]
+    fn renders_elements_as_html() {
+        let view: HtmlElement<_, _, _> = div(
+            (
+                attr("title", "<hi>"),
+                class("a", true),
+                style("color", "red"),
+            ),
+            (input(()), "a & b"),
+        )
+        .child(li((), ()));
+        assert_eq!(
+            RenderHtml::<MockDom>::to_html(view),
+            "<div title=\"&lt;hi&gt;\" class=\"a\" style=\"color: red;\">\
+             <input>a &amp; b<li><!></li></div>"
+        );
+        let view = div((), ("a", ul((), "b")));
+        assert_eq!(RenderHtml::<MockDom>::to_text(view), "ab");
+    }
+
+    #[test]
+    fn hydrates_existing_elements() {
+        let root = Document::create_element("body");
+        Render::<MockDom>::build(div(
+            (),
+            (ul((), li((), "a")), input(()), "b"),
+        ))
+        .mount(&root, None);
+        let nodes = root.children();
+
+        let mut state = RenderHtml::<MockDom>::hydrate_from::<true>(
+            div(attr("id", "x"), (ul((), li((), "a")), input(()), "b")),
+            &root,
+        );
+        assert_eq!(root.children(), nodes);
+        Render::<MockDom>::rebuild(
+            div(attr("id", "y"), (ul((), li((), "c")), input(()), "d")),
+            &mut state,
+        );
+        assert_eq!(
+            root.to_debug_html(),
+            "<body><div id=\"y\"><ul><li>c</li></ul><input></input>d</div>\
+             </body>"
+        );
+    }
+
+    #[test]
+    fn listeners_are_removed_when_element_is_unmounted() {
+        static CLICKS: AtomicUsize = AtomicUsize::new(0);
+
+        let root = Document::create_element("body");
+        let view = div((), "click me").on("click", |_: Event| {
+            CLICKS.fetch_add(1, Ordering::Relaxed);
+        });
+        let mut state = Render::<MockDom>::build(view);
+        state.mount(&root, None);
+        let el = state.element().clone();
+        Document::dispatch(Event::new("click", &el));
+        assert_eq!(CLICKS.load(Ordering::Relaxed), 1);
+
+        state.unmount();
+        assert_eq!(Document::listener_count(&el), 0);
+        state.mount(&root, None);
+        assert_eq!(Document::listener_count(&el), 1);
+    }
+
+    #[test]
+    fn listeners_on_children_are_removed_when_element_is_unmounted() {
+        static CLICKS: AtomicUsize = AtomicUsize::new(0);
+
+        let root = Document::create_element("body");
+        let view = div(
+            (),
+            (
+                "a",
+                li((), "b").on("click", |_: Event| {
+                    CLICKS.fetch_add(1, Ordering::Relaxed);
+                }),
+            ),
+        );
+        let mut state = Render::<MockDom>::build(view);
+        state.mount(&root, None);
+        let li = state.children.1.element().clone();
+        Document::dispatch(Event::new("click", &li));
+        assert_eq!(CLICKS.load(Ordering::Relaxed), 1);
+
+        state.unmount();
+        assert_eq!(Document::listener_count(&li), 0);
+
+        // mounting again restores the children and their listeners
+        state.mount(&root, None);
+        assert_eq!(root.to_debug_html(), "<body><div>a<li>b</li></div></body>");
+        Document::dispatch(Event::new("click", &li));
+        assert_eq!(CLICKS.load(Ordering::Relaxed), 2);
+    }
+
+    #[cfg(feature = "delegation")]
+    #[test]
+    fn delegated_handlers_on_children_are_removed_when_element_is_unmounted() {
+        use crate::html::delegation::{delegate_event, on_delegated};
+
+        static CLICKS: AtomicUsize = AtomicUsize::new(0);
+
+        let root = Document::create_element("body");
+        delegate_event::<MockDom>(&root, "click");
+        let view = div(
+            (),
+            li(
+                on_delegated("click", |_: Event| {
+                    CLICKS.fetch_add(1, Ordering::Relaxed);
+                }),
+                "b",
+            ),
+        );
+        let mut state = Render::<MockDom>::build(view);
+        state.mount(&root, None);
+        let li = state.children.element().clone();
+        Document::dispatch(Event::new("click", &li));
+        assert_eq!(CLICKS.load(Ordering::Relaxed), 1);
+
+        state.unmount();
+        Document::dispatch(Event::new("click", &li));
+        assert_eq!(CLICKS.load(Ordering::Relaxed), 1);
+
+        state.mount(&root, None);
+        Document::dispatch(Event::new("click", &li));
+        assert_eq!(CLICKS.load(Ordering::Relaxed), 2);
+    }
+}
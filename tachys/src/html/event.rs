@@ -0,0 +1,231 @@
+use crate::{html::attribute::Attribute, renderer::EventRenderer};
+use std::{cell::RefCell, fmt::Debug, marker::PhantomData, rc::Rc};
+
+pub(crate) type SharedHandler<E> = Rc<RefCell<Box<dyn FnMut(E)>>>;
+
+/// An event listener, which can be applied to an element like any other [`Attribute`].
+///
+/// The listener is added when the element is built, and is owned by the element's view state:
+/// it is removed when the element is unmounted (and added again if it is mounted again), and
+/// when the state is dropped.
+pub struct On<E, F> {
+    name: &'static str,
+    handler: F,
+    event: PhantomData<fn(E)>,
+}
+
+impl<E, F> Debug for On<E, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("On")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E, F> Clone for On<E, F>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            handler: self.handler.clone(),
+            event: PhantomData,
+        }
+    }
+}
+
+/// Adds a listener for the event with the given name.
+pub fn on<E, F>(name: &'static str, handler: F) -> On<E, F>
+where
+    F: FnMut(E) + Send + 'static,
+{
+    On {
+        name,
+        handler,
+        event: PhantomData,
+    }
+}
+
+/// Retained state for an [`On`] event listener.
+pub struct OnState<R>
+where
+    R: EventRenderer,
+{
+    el: R::Element,
+    name: &'static str,
+    handler: SharedHandler<R::Event>,
+    handle: Option<R::ListenerHandle>,
+}
+
+impl<R> Debug for OnState<R>
+where
+    R: EventRenderer,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnState")
+            .field("name", &self.name)
+            .field("listening", &self.handle.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R> OnState<R>
+where
+    R: EventRenderer,
+{
+    fn new(
+        el: &R::Element,
+        name: &'static str,
+        handler: SharedHandler<R::Event>,
+    ) -> Self {
+        let mut state = Self {
+            el: el.clone(),
+            name,
+            handler,
+            handle: None,
+        };
+        state.listen();
+        state
+    }
+
+    /// Whether the listener is currently attached to the element.
+    pub fn is_listening(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    fn listen(&mut self) {
+        if self.handle.is_none() {
+            let handler = Rc::clone(&self.handler);
+            self.handle = Some(R::add_event_listener(
+                &self.el,
+                self.name,
+                Box::new(move |ev| (handler.borrow_mut())(ev)),
+            ));
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            R::remove_event_listener(&self.el, self.name, handle);
+        }
+    }
+}
+
+impl<R> Drop for OnState<R>
+where
+    R: EventRenderer,
+{
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl<F, R> Attribute<R> for On<R::Event, F>
+where
+    F: FnMut(R::Event) + Send + 'static,
+    R: EventRenderer,
+{
+    type State = OnState<R>;
+
+    fn html_len(&self) -> usize {
+        0
+    }
+
+    fn to_html(
+        self,
+        _buf: &mut String,
+        _class: &mut String,
+        _style: &mut String,
+    ) {
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(self, el: &R::Element) -> Self::State {
+        self.build(el)
+    }
+
+    fn build(self, el: &R::Element) -> Self::State {
+        OnState::new(
+            el,
+            self.name,
+            Rc::new(RefCell::new(Box::new(self.handler))),
+        )
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        if state.name == self.name {
+            // the listener calls through to the shared handler, so it can be swapped in place
+            *state.handler.borrow_mut() = Box::new(self.handler);
+        } else {
+            let listening = state.is_listening();
+            state.stop();
+            state.name = self.name;
+            *state.handler.borrow_mut() = Box::new(self.handler);
+            if listening {
+                state.listen();
+            }
+        }
+    }
+
+    fn unmount(state: &mut Self::State) {
+        state.stop();
+    }
+
+    fn mount(state: &mut Self::State) {
+        state.listen();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{on, On};
+    use crate::{
+        html::attribute::Attribute,
+        renderer::mock_dom::{Document, Event, MockDom},
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn listeners_are_removed_on_unmount_and_drop() {
+        static CLICKS: AtomicUsize = AtomicUsize::new(0);
+        type Listener = On<Event, fn(Event)>;
+
+        let el = Document::create_element("button");
+        let listener: Listener = on("click", |_| {
+            CLICKS.fetch_add(1, Ordering::Relaxed);
+        });
+        let mut state = Attribute::<MockDom>::build(listener, &el);
+        Document::dispatch(Event::new("click", &el));
+        Document::dispatch(Event::new("input", &el));
+        assert_eq!(CLICKS.load(Ordering::Relaxed), 1);
+
+        <Listener as Attribute<MockDom>>::unmount(&mut state);
+        Document::dispatch(Event::new("click", &el));
+        assert_eq!(CLICKS.load(Ordering::Relaxed), 1);
+        assert_eq!(Document::listener_count(&el), 0);
+
+        <Listener as Attribute<MockDom>>::mount(&mut state);
+        assert_eq!(Document::listener_count(&el), 1);
+        drop(state);
+        assert_eq!(Document::listener_count(&el), 0);
+    }
+
+    #[test]
+    fn rebuilding_replaces_the_handler() {
+        let el = Document::create_element("button");
+        let seen = Arc::new(AtomicUsize::new(0));
+        let handler = |n: usize| {
+            let seen = Arc::clone(&seen);
+            move |_: Event| seen.store(n, Ordering::Relaxed)
+        };
+        let mut state =
+            Attribute::<MockDom>::build(on("click", handler(1)), &el);
+        Attribute::<MockDom>::rebuild(on("click", handler(2)), &mut state);
+        Document::dispatch(Event::new("click", &el));
+        assert_eq!(seen.load(Ordering::Relaxed), 2);
+        assert_eq!(Document::listener_count(&el), 1);
+    }
+}
@@ -0,0 +1,9 @@
+/// Types for setting attributes, classes, styles, and properties on elements.
+pub mod attribute;
+/// Delegated event listeners, which share a single listener on a root element.
+#[cfg(feature = "delegation")]
+pub mod delegation;
+/// Element views with typed tags.
+pub mod element;
+/// Event listeners that are owned by the view state.
+pub mod event;
@@ -0,0 +1,235 @@
+use crate::renderer::{CastFrom, Renderer};
+use std::{cell::Cell, cell::RefCell, fmt::Debug, rc::Rc};
+
+/// Hydration works by walking over an existing tree of nodes, adding interactivity as needed.
+///
+/// This cursor tracks the location in the tree that is currently being hydrated. Each type that
+/// implements [`RenderHtml`](crate::view::RenderHtml) knows how to advance the cursor to claim
+/// the nodes it needs, using [`Renderer::first_child`] and [`Renderer::next_sibling`].
+pub struct Cursor<R>(Rc<RefCell<R::Node>>)
+where
+    R: Renderer;
+
+impl<R> Debug for Cursor<R>
+where
+    R: Renderer,
+    R::Node: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Cursor").field(&self.0).finish()
+    }
+}
+
+impl<R> Clone for Cursor<R>
+where
+    R: Renderer,
+{
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<R> Cursor<R>
+where
+    R: Renderer,
+{
+    /// Creates a new cursor starting at the root element.
+    pub fn new(root: R::Element) -> Self {
+        Self(Rc::new(RefCell::new(root.as_ref().clone())))
+    }
+
+    /// Returns the node at which the cursor is currently located.
+    pub fn current(&self) -> R::Node {
+        self.0.borrow().clone()
+    }
+
+    /// Advances to the next child of the node at which the cursor is located.
+    ///
+    /// Does nothing if there is no child.
+    pub fn child(&self) {
+        let mut inner = self.0.borrow_mut();
+        if let Some(node) = R::first_child(&inner) {
+            *inner = node;
+        }
+    }
+
+    /// Advances to the next sibling of the node at which the cursor is located.
+    ///
+    /// Does nothing if there is no sibling.
+    pub fn sibling(&self) {
+        let mut inner = self.0.borrow_mut();
+        if let Some(node) = R::next_sibling(&inner) {
+            *inner = node;
+        }
+    }
+
+    /// Moves to the parent of the node at which the cursor is located.
+    ///
+    /// Does nothing if there is no parent.
+    pub fn parent(&self) {
+        let mut inner = self.0.borrow_mut();
+        if let Some(node) = R::get_parent(&inner) {
+            *inner = node;
+        }
+    }
+
+    /// Sets the cursor to some node.
+    pub fn set(&self, node: R::Node) {
+        *self.0.borrow_mut() = node;
+    }
+
+    /// Advances to the next node, based on the current position, and returns it.
+    pub fn next_node(&self, position: &PositionState) -> R::Node {
+        if position.get() == Position::FirstChild {
+            self.child();
+        } else {
+            self.sibling();
+        }
+        position.set(Position::NextChild);
+        self.current()
+    }
+
+    /// Advances to the next node, which should be a text node, and returns it.
+    pub fn next_text(&self, position: &PositionState) -> R::Text {
+        let node = self.next_node(position);
+        R::Text::cast_from(node.clone())
+            .unwrap_or_else(|| failed_to_cast_text_node::<R>(node))
+    }
+
+    /// Advances to the next node, which should be a placeholder, and returns it.
+    pub fn next_placeholder(&self, position: &PositionState) -> R::Placeholder {
+        let node = self.next_node(position);
+        R::Placeholder::cast_from(node.clone())
+            .unwrap_or_else(|| failed_to_cast_marker_node::<R>(node))
+    }
+}
+
+/// Represents the cursor's position relative to the node it is located at.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// The next node to be hydrated is the first child of the current node.
+    #[default]
+    FirstChild,
+    /// The next node to be hydrated is the next sibling of the current node.
+    NextChild,
+}
+
+/// Tracks the cursor's [`Position`] as hydration walks the tree.
+#[derive(Debug, Clone, Default)]
+pub struct PositionState(Rc<Cell<Position>>);
+
+impl PositionState {
+    /// Creates a new position tracker.
+    pub fn new(position: Position) -> Self {
+        Self(Rc::new(Cell::new(position)))
+    }
+
+    /// Sets the current position.
+    pub fn set(&self, position: Position) {
+        self.0.set(position);
+    }
+
+    /// Gets the current position.
+    pub fn get(&self) -> Position {
+        self.0.get()
+    }
+
+    /// Creates a new [`PositionState`] that starts with the same position, but is not shared with
+    /// this one.
+    pub fn deep_clone(&self) -> Self {
+        Self::new(self.get())
+    }
+}
+
+/// Logs the node that failed to hydrate as an element, and panics.
+#[track_caller]
+pub fn failed_to_cast_element<R: Renderer>(node: R::Node) -> R::Element {
+    R::log_node(&node);
+    panic!(
+        "Unrecoverable hydration error: expected an element, but found the \
+         node logged directly above this message."
+    );
+}
+
+/// Logs the node that failed to hydrate as a text node, and panics.
+#[track_caller]
+pub fn failed_to_cast_text_node<R: Renderer>(node: R::Node) -> R::Text {
+    R::log_node(&node);
+    panic!(
+        "Unrecoverable hydration error: expected a text node, but found the \
+         node logged directly above this message."
+    );
+}
+
+/// Logs the node that failed to hydrate as a placeholder, and panics.
+#[track_caller]
+pub fn failed_to_cast_marker_node<R: Renderer>(
+    node: R::Node,
+) -> R::Placeholder {
+    R::log_node(&node);
+    panic!(
+        "Unrecoverable hydration error: expected a placeholder, but found the \
+         node logged directly above this message."
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        renderer::mock_dom::{Document, MockDom},
+        view::{keyed::keyed, Mountable, Render, RenderHtml},
+    };
+
+    #[test]
+    fn hydration_claims_existing_nodes() {
+        let root = Document::create_element("div");
+        let mut state = Render::<MockDom>::build(("a", 1, ()));
+        state.mount(&root, None);
+        let nodes = root.children();
+
+        let mut state =
+            RenderHtml::<MockDom>::hydrate_from::<true>(("a", 1, ()), &root);
+        assert_eq!(root.children(), nodes);
+
+        Render::<MockDom>::rebuild(("b", 2, ()), &mut state);
+        assert_eq!(root.to_debug_html(), "<div>b2<!></div>");
+        assert_eq!(root.children(), nodes);
+    }
+
+    #[test]
+    fn hydrating_locally_updates_text() {
+        let root = Document::create_element("div");
+        Render::<MockDom>::build(("a", String::from("b"))).mount(&root, None);
+
+        RenderHtml::<MockDom>::hydrate_from::<false>(
+            ("c", String::from("d")),
+            &root,
+        );
+        assert_eq!(root.to_debug_html(), "<div>cd</div>");
+    }
+
+    #[test]
+    fn hydrated_keyed_list_can_add_items() {
+        fn item(_: usize, key: usize) -> (fn(usize), String) {
+            (|_| {}, key.to_string())
+        }
+
+        let root = Document::create_element("ul");
+        Render::<MockDom>::build(keyed::<_, _, _, _, _, _, _, MockDom>(
+            [1, 2],
+            |k| *k,
+            item,
+        ))
+        .mount(&root, None);
+
+        let mut state = RenderHtml::<MockDom>::hydrate_from::<true>(
+            keyed::<_, _, _, _, _, _, _, MockDom>([1, 2], |k| *k, item),
+            &root,
+        );
+        Render::<MockDom>::rebuild(
+            keyed::<_, _, _, _, _, _, _, MockDom>([1, 2, 3], |k| *k, item),
+            &mut state,
+        );
+        assert_eq!(root.to_debug_html(), "<ul>123<!></ul>");
+    }
+}
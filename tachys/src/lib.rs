@@ -20,14 +20,20 @@ pub mod prelude {
     pub use crate::{
         renderer::Renderer,
         view::{
-            any_view::{AnyView, IntoAny, IntoMaybeErased},
-            IntoRender, Mountable, Render,
+            any_view::{AnyView, IntoAny, IntoAnyHtml, IntoMaybeErased},
+            IntoRender, Mountable, Render, RenderHtml,
         },
     };
 }
 
+/// Types for building HTML-like element views for any renderer.
+pub mod html;
+/// Helpers for hydrating views from an existing tree of nodes.
+pub mod hydration;
 /// Defines various backends that can render views.
 pub mod renderer;
+/// Helpers for rendering views as text.
+pub mod ssr;
 /// Core logic for manipulating views.
 pub mod view;
 
@@ -1,4 +1,9 @@
-use crate::prelude::{Mountable, Render, Renderer};
+use crate::{
+    html::attribute::static_value,
+    hydration::{Cursor, PositionState},
+    prelude::{Mountable, Render, RenderHtml, Renderer},
+    view::strings::push_text,
+};
 use oco_ref::Oco;
 
 /// Retained view state for [`Oco`].
@@ -30,6 +35,41 @@ where
     }
 }
 
+impl<R> RenderHtml<R> for Oco<'static, str>
+where
+    R: Renderer,
+{
+    type AsyncOutput = Self;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {}
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        self
+    }
+
+    fn html_len(&self) -> usize {
+        self.len()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        push_text(buf, &self, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let node = cursor.next_text(position);
+        if !FROM_SERVER {
+            R::set_text(&node, &self);
+        }
+        OcoStrState { node, str: self }
+    }
+}
+
 impl<R> Mountable<R> for OcoStrState<R>
 where
     R: Renderer,
@@ -46,3 +86,5 @@ where
         self.node.insert_before_this(child)
     }
 }
+
+static_value!(Oco<'static, str>, |this| Some(this.as_str()), this.len());
@@ -0,0 +1,165 @@
+use super::ReactiveFunction;
+use crate::{
+    html::attribute::{AttributeKind, AttributeValue},
+    renderer::Renderer,
+};
+use reactive_graph::effect::RenderEffect;
+
+/// Retained state for a reactive [`AttributeValue`].
+pub struct RenderEffectAttrState<T, R>
+where
+    T: 'static,
+    R: Renderer,
+{
+    el: R::Element,
+    effect: RenderEffect<T>,
+}
+
+impl<F, V, R> AttributeValue<R> for F
+where
+    F: ReactiveFunction<Output = V>,
+    V: AttributeValue<R> + 'static,
+    V::State: 'static,
+    R: Renderer,
+{
+    type State = RenderEffectAttrState<V::State, R>;
+
+    fn html_len(&self) -> usize {
+        0
+    }
+
+    fn to_html<K: AttributeKind>(
+        mut self,
+        key: &str,
+        buf: &mut String,
+        class: &mut String,
+        style: &mut String,
+    ) {
+        self.invoke().to_html::<K>(key, buf, class, style);
+    }
+
+    #[track_caller]
+    fn hydrate<K: AttributeKind, const FROM_SERVER: bool>(
+        mut self,
+        key: &'static str,
+        el: &R::Element,
+    ) -> Self::State {
+        let effect = RenderEffect::new({
+            let el = el.clone();
+            move |prev| {
+                let value = self.invoke();
+                if let Some(mut state) = prev {
+                    value.rebuild::<K>(key, &mut state);
+                    state
+                } else {
+                    value.hydrate::<K, FROM_SERVER>(key, &el)
+                }
+            }
+        });
+        RenderEffectAttrState {
+            el: el.clone(),
+            effect,
+        }
+    }
+
+    #[track_caller]
+    fn build<K: AttributeKind>(
+        self,
+        key: &'static str,
+        el: &R::Element,
+    ) -> Self::State {
+        build_effect::<K, _, _, R>(self, key, el, None)
+    }
+
+    #[track_caller]
+    fn rebuild<K: AttributeKind>(
+        self,
+        key: &'static str,
+        state: &mut Self::State,
+    ) {
+        // carry the previous state over, so that the new value is diffed against the old one
+        let prev = state.effect.take_value();
+        *state = build_effect::<K, _, _, R>(self, key, &state.el, prev);
+    }
+}
+
+#[track_caller]
+fn build_effect<K, F, V, R>(
+    mut fun: F,
+    key: &'static str,
+    el: &R::Element,
+    prev: Option<V::State>,
+) -> RenderEffectAttrState<V::State, R>
+where
+    K: AttributeKind,
+    F: ReactiveFunction<Output = V>,
+    V: AttributeValue<R> + 'static,
+    V::State: 'static,
+    R: Renderer,
+{
+    let run = {
+        let el = el.clone();
+        move |prev: Option<V::State>| {
+            let value = fun.invoke();
+            if let Some(mut state) = prev {
+                value.rebuild::<K>(key, &mut state);
+                state
+            } else {
+                value.build::<K>(key, &el)
+            }
+        }
+    };
+    let effect = match prev {
+        Some(prev) => RenderEffect::new_with_value(run, Some(prev)),
+        None => RenderEffect::new(run),
+    };
+    RenderEffectAttrState {
+        el: el.clone(),
+        effect,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        html::attribute::{attr, class, Attribute},
+        renderer::mock_dom::{Document, MockDom},
+    };
+    use any_spawner::Executor;
+    use reactive_graph::{
+        owner::Owner,
+        signal::RwSignal,
+        traits::{Get, Set},
+    };
+    use tokio::task;
+
+    #[tokio::test]
+    async fn reactive_attributes_update() {
+        _ = Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+        task::LocalSet::new()
+            .run_until(async {
+                let id = RwSignal::new(String::from("a"));
+                let active = RwSignal::new(false);
+                let el = Document::create_element("div");
+                let _state = Attribute::<MockDom>::build(
+                    (
+                        attr("id", move || id.get()),
+                        class("on", move || active.get()),
+                    ),
+                    &el,
+                );
+                assert_eq!(el.to_debug_html(), r#"<div id="a"></div>"#);
+
+                id.set(String::from("b"));
+                active.set(true);
+                Executor::tick().await;
+                assert_eq!(
+                    el.to_debug_html(),
+                    r#"<div id="b" class="on"></div>"#
+                );
+            })
+            .await;
+    }
+}
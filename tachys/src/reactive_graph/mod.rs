@@ -1,15 +1,21 @@
 use crate::{
+    hydration::{Cursor, PositionState},
     renderer::Renderer,
-    view::{Mountable, Render},
+    ssr::StreamBuilder,
+    view::{Mountable, Render, RenderHtml},
 };
 use reactive_graph::effect::RenderEffect;
 use std::sync::{Arc, Mutex};
 
+mod attribute;
 mod owned;
 mod suspense;
+mod virtualized;
 
+pub use attribute::*;
 pub use owned::*;
 pub use suspense::*;
+pub use virtualized::*;
 
 impl<F, V, R> Render<R> for F
 where
@@ -47,6 +53,63 @@ where
     }
 }
 
+impl<F, V, R> RenderHtml<R> for F
+where
+    F: ReactiveFunction<Output = V>,
+    V: RenderHtml<R> + 'static,
+    V::State: 'static,
+    R: Renderer,
+{
+    type AsyncOutput = V::AsyncOutput;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {
+        self.invoke().dry_resolve();
+    }
+
+    async fn resolve(mut self) -> Self::AsyncOutput {
+        self.invoke().resolve().await
+    }
+
+    fn html_len(&self) -> usize {
+        V::MIN_LENGTH
+    }
+
+    fn to_html_with_buf(mut self, buf: &mut String, escape: bool) {
+        let value = self.invoke();
+        value.to_html_with_buf(buf, escape)
+    }
+
+    fn to_html_async_with_buf(mut self, buf: &mut StreamBuilder, escape: bool) {
+        let value = self.invoke();
+        value.to_html_async_with_buf(buf, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        mut self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let cursor = cursor.clone();
+        let position = position.clone();
+        let hook = throw_error::get_error_hook();
+        RenderEffect::new(move |prev| {
+            let _guard = hook
+                .as_ref()
+                .map(|h| throw_error::set_error_hook(Arc::clone(h)));
+            let value = self.invoke();
+            if let Some(mut state) = prev {
+                value.rebuild(&mut state);
+                state
+            } else {
+                value.hydrate::<FROM_SERVER>(&cursor, &position)
+            }
+        })
+        .into()
+    }
+}
+
 /// Retained view state for a [`RenderEffect`].
 pub struct RenderEffectState<T: 'static>(Option<RenderEffect<T>>);
 
@@ -221,15 +284,119 @@ macro_rules! reactive_impl {
                 old.unmount();
             }
         }
+
+        #[allow(deprecated)]
+        impl<R,$($gen),*> RenderHtml<R> for $name<$($gen),*>
+        where
+            $v: RenderHtml<R> + Clone + Send + Sync,
+            <$v as Render<R>>::State: 'static,
+            R: Renderer,
+            $($where_clause)*
+
+        {
+            type AsyncOutput = Self;
+
+            const MIN_LENGTH: usize = 0;
+
+            fn dry_resolve(&mut self) {
+                self.get().dry_resolve();
+            }
+
+            async fn resolve(self) -> Self::AsyncOutput {
+                self
+            }
+
+            fn html_len(&self) -> usize {
+                <$v as RenderHtml<R>>::MIN_LENGTH
+            }
+
+            fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+                let value = self.get();
+                value.to_html_with_buf(buf, escape)
+            }
+
+            fn to_html_async_with_buf(
+                self,
+                buf: &mut StreamBuilder,
+                escape: bool,
+            ) {
+                let value = self.get();
+                value.to_html_async_with_buf(buf, escape);
+            }
+
+            #[track_caller]
+            fn hydrate<const FROM_SERVER: bool>(
+                self,
+                cursor: &Cursor<R>,
+                position: &PositionState,
+            ) -> Self::State {
+                (move || self.get()).hydrate::<FROM_SERVER>(cursor, position)
+            }
+        }
+
+        #[allow(deprecated)]
+        impl<R,$($gen),*> AttributeValue<R> for $name<$($gen),*>
+        where
+            $v: AttributeValue<R> + Clone + Send + Sync + 'static,
+            <$v as AttributeValue<R>>::State: 'static,
+            R: Renderer,
+            $($where_clause)*
+        {
+            type State = RenderEffectAttrState<<$v as AttributeValue<R>>::State, R>;
+
+            fn html_len(&self) -> usize {
+                0
+            }
+
+            fn to_html<Kind: AttributeKind>(
+                self,
+                key: &str,
+                buf: &mut String,
+                class: &mut String,
+                style: &mut String,
+            ) {
+                self.get().to_html::<Kind>(key, buf, class, style);
+            }
+
+            #[track_caller]
+            fn hydrate<Kind: AttributeKind, const FROM_SERVER: bool>(
+                self,
+                key: &'static str,
+                el: &R::Element,
+            ) -> Self::State {
+                (move || self.get()).hydrate::<Kind, FROM_SERVER>(key, el)
+            }
+
+            #[track_caller]
+            fn build<Kind: AttributeKind>(
+                self,
+                key: &'static str,
+                el: &R::Element,
+            ) -> Self::State {
+                (move || self.get()).build::<Kind>(key, el)
+            }
+
+            #[track_caller]
+            fn rebuild<Kind: AttributeKind>(
+                self,
+                key: &'static str,
+                state: &mut Self::State,
+            ) {
+                (move || self.get()).rebuild::<Kind>(key, state)
+            }
+        }
     };
 }
 
 #[cfg(not(feature = "nightly"))]
 mod stable {
-    use super::RenderEffectState;
+    use super::{RenderEffectAttrState, RenderEffectState};
     use crate::{
+        html::attribute::{AttributeKind, AttributeValue},
+        hydration::{Cursor, PositionState},
         renderer::Renderer,
-        view::{Mountable, Render},
+        ssr::StreamBuilder,
+        view::{Mountable, Render, RenderHtml},
     };
     #[allow(deprecated)]
     use reactive_graph::wrappers::read::MaybeSignal;
@@ -297,8 +464,13 @@ mod stable {
 
 #[cfg(feature = "reactive_stores")]
 mod reactive_stores {
-    use super::{RenderEffectState, Renderer};
-    use crate::view::{Mountable, Render};
+    use super::{RenderEffectAttrState, RenderEffectState, Renderer};
+    use crate::{
+        html::attribute::{AttributeKind, AttributeValue},
+        hydration::{Cursor, PositionState},
+        ssr::StreamBuilder,
+        view::{Mountable, Render, RenderHtml},
+    };
     #[allow(deprecated)]
     use reactive_graph::{owner::Storage, traits::Get};
     use reactive_stores::{
@@ -374,7 +546,6 @@ mod reactive_stores {
     reactive_impl!(ArcField, <V>, V, ArcField<V>: Get<Value = V>);
 }
 
-/*
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -382,54 +553,80 @@ mod tests {
         renderer::mock_dom::MockDom,
         view::Render,
     };
-    use leptos_reactive::{create_runtime, RwSignal, SignalGet, SignalSet};
-
-    #[test]
-    fn create_dynamic_element() {
-        let rt = create_runtime();
-        let count = RwSignal::new(0);
-        let app: HtmlElement<_, _, _, MockDom> =
-            button((), move || count.get().to_string());
-        let el = app.build();
-        assert_eq!(el.el.to_debug_html(), "<button>0</button>");
-        rt.dispose();
+    use any_spawner::Executor;
+    use reactive_graph::{
+        owner::Owner,
+        signal::RwSignal,
+        traits::{Get, Set},
+    };
+    use tokio::task;
+
+    #[tokio::test]
+    async fn create_dynamic_element() {
+        _ = Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+        task::LocalSet::new()
+            .run_until(async {
+                let count = RwSignal::new(0);
+                let app: HtmlElement<_, _, _> =
+                    button((), move || count.get().to_string());
+                let el = Render::<MockDom>::build(app);
+                assert_eq!(el.el.to_debug_html(), "<button>0</button>");
+            })
+            .await;
     }
 
-    #[test]
-    fn update_dynamic_element() {
-        let rt = create_runtime();
-        let count = RwSignal::new(0);
-        let app: HtmlElement<_, _, _, MockDom> =
-            button((), move || count.get().to_string());
-        let el = app.build();
-        assert_eq!(el.el.to_debug_html(), "<button>0</button>");
-        count.set(1);
-        assert_eq!(el.el.to_debug_html(), "<button>1</button>");
-        rt.dispose();
+    #[tokio::test]
+    async fn update_dynamic_element() {
+        _ = Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+        task::LocalSet::new()
+            .run_until(async {
+                let count = RwSignal::new(0);
+                let app: HtmlElement<_, _, _> =
+                    button((), move || count.get().to_string());
+                let el = Render::<MockDom>::build(app);
+                assert_eq!(el.el.to_debug_html(), "<button>0</button>");
+                count.set(1);
+                Executor::tick().await;
+                assert_eq!(el.el.to_debug_html(), "<button>1</button>");
+            })
+            .await;
     }
 
-    #[test]
-    fn update_dynamic_element_among_siblings() {
-        let rt = create_runtime();
-        let count = RwSignal::new(0);
-        let app: HtmlElement<_, _, _, MockDom> = main(
-            (),
-            button(
-                (),
-                ("Hello, my ", move || count.get().to_string(), " friends."),
-            ),
-        );
-        let el = app.build();
-        assert_eq!(
-            el.el.to_debug_html(),
-            "<main><button>Hello, my 0 friends.</button></main>"
-        );
-        count.set(42);
-        assert_eq!(
-            el.el.to_debug_html(),
-            "<main><button>Hello, my 42 friends.</button></main>"
-        );
-        rt.dispose();
+    #[tokio::test]
+    async fn update_dynamic_element_among_siblings() {
+        _ = Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+        task::LocalSet::new()
+            .run_until(async {
+                let count = RwSignal::new(0);
+                let app: HtmlElement<_, _, _> = main(
+                    (),
+                    button(
+                        (),
+                        (
+                            "Hello, my ",
+                            move || count.get().to_string(),
+                            " friends.",
+                        ),
+                    ),
+                );
+                let el = Render::<MockDom>::build(app);
+                assert_eq!(
+                    el.el.to_debug_html(),
+                    "<main><button>Hello, my 0 friends.</button></main>"
+                );
+                count.set(42);
+                Executor::tick().await;
+                assert_eq!(
+                    el.el.to_debug_html(),
+                    "<main><button>Hello, my 42 friends.</button></main>"
+                );
+            })
+            .await;
     }
 }
- */
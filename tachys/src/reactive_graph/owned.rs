@@ -1,8 +1,10 @@
 use crate::{
+    hydration::{Cursor, PositionState},
     prelude::{Mountable, Renderer},
-    view::Render,
+    ssr::StreamBuilder,
+    view::{Render, RenderHtml},
 };
-use reactive_graph::owner::Owner;
+use reactive_graph::{computed::ScopedFuture, owner::Owner};
 use std::marker::PhantomData;
 
 /// A view wrapper that sets the reactive [`Owner`] to a particular owner whenever it is rendered.
@@ -71,6 +73,52 @@ where
     }
 }
 
+impl<T, R> RenderHtml<R> for OwnedView<T>
+where
+    T: RenderHtml<R>,
+    R: Renderer,
+{
+    type AsyncOutput = OwnedView<T::AsyncOutput>;
+
+    const MIN_LENGTH: usize = T::MIN_LENGTH;
+
+    fn dry_resolve(&mut self) {
+        self.owner.with(|| self.view.dry_resolve());
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        let OwnedView { owner, view } = self;
+        let view = owner
+            .with(|| ScopedFuture::new(async move { view.resolve().await }))
+            .await;
+        OwnedView { owner, view }
+    }
+
+    fn html_len(&self) -> usize {
+        self.view.html_len()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        self.owner.with(|| self.view.to_html_with_buf(buf, escape));
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        self.owner
+            .with(|| self.view.to_html_async_with_buf(buf, escape));
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let state = self
+            .owner
+            .with(|| self.view.hydrate::<FROM_SERVER>(cursor, position));
+        OwnedViewState::new(state, self.owner)
+    }
+}
+
 impl<T, R> Mountable<R> for OwnedViewState<T, R>
 where
     T: Mountable<R>,
@@ -1,6 +1,8 @@
 use crate::{
+    hydration::{Cursor, PositionState},
     prelude::Renderer,
-    view::{iterators::OptionState, Mountable, Render},
+    ssr::StreamBuilder,
+    view::{iterators::OptionState, Mountable, Render, RenderHtml},
 };
 use any_spawner::Executor;
 use futures::{
@@ -120,47 +122,17 @@ impl<T> Suspend<T> {
     }
 }
 
-impl<T> Debug for Suspend<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Suspend").finish()
-    }
-}
-
-/// Retained view state for [`Suspend`].
-pub struct SuspendState<T, R>
-where
-    T: Render<R>,
-    R: Renderer,
-{
-    inner: Rc<RefCell<OptionState<T, R>>>,
-}
-
-impl<T, R> Mountable<R> for SuspendState<T, R>
-where
-    T: Render<R>,
-    R: Renderer,
-{
-    fn unmount(&mut self) {
-        self.inner.borrow_mut().unmount();
-    }
-
-    fn mount(&mut self, parent: &R::Element, marker: Option<&R::Node>) {
-        self.inner.borrow_mut().mount(parent, marker);
-    }
-
-    fn insert_before_this(&self, child: &mut dyn Mountable<R>) -> bool {
-        self.inner.borrow_mut().insert_before_this(child)
-    }
-}
-
-impl<T, R> Render<R> for Suspend<T>
-where
-    T: Render<R>,
-    R: Renderer,
-{
-    type State = SuspendState<T, R>;
-
-    fn build(self) -> Self::State {
+impl<T> Suspend<T> {
+    /// Creates the view state, using `initial_state` to create the state for the value (if it is
+    /// ready immediately) or for the fallback placeholder (if it is not).
+    fn build_with<R>(
+        self,
+        initial_state: impl FnOnce(Option<T>) -> OptionState<T, R>,
+    ) -> SuspendState<T, R>
+    where
+        T: Render<R>,
+        R: Renderer,
+    {
         let Self { subscriber, inner } = self;
 
         // create a Future that will be aborted on on_cleanup
@@ -175,7 +147,7 @@ where
         // otherwise, start with the fallback
         let initial = fut.as_mut().now_or_never().and_then(Result::ok);
         let initially_pending = initial.is_none();
-        let inner = Rc::new(RefCell::new(initial.build()));
+        let inner = Rc::new(RefCell::new(initial_state(initial)));
 
         // get a unique ID if there's a SuspenseContext
         let id = use_context::<SuspenseContext>().map(|sc| sc.task_id());
@@ -208,6 +180,51 @@ where
 
         SuspendState { inner }
     }
+}
+
+impl<T> Debug for Suspend<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Suspend").finish()
+    }
+}
+
+/// Retained view state for [`Suspend`].
+pub struct SuspendState<T, R>
+where
+    T: Render<R>,
+    R: Renderer,
+{
+    inner: Rc<RefCell<OptionState<T, R>>>,
+}
+
+impl<T, R> Mountable<R> for SuspendState<T, R>
+where
+    T: Render<R>,
+    R: Renderer,
+{
+    fn unmount(&mut self) {
+        self.inner.borrow_mut().unmount();
+    }
+
+    fn mount(&mut self, parent: &R::Element, marker: Option<&R::Node>) {
+        self.inner.borrow_mut().mount(parent, marker);
+    }
+
+    fn insert_before_this(&self, child: &mut dyn Mountable<R>) -> bool {
+        self.inner.borrow_mut().insert_before_this(child)
+    }
+}
+
+impl<T, R> Render<R> for Suspend<T>
+where
+    T: Render<R>,
+    R: Renderer,
+{
+    type State = SuspendState<T, R>;
+
+    fn build(self) -> Self::State {
+        self.build_with(Render::build)
+    }
 
     fn rebuild(self, state: &mut Self::State) {
         let Self { subscriber, inner } = self;
@@ -247,3 +264,83 @@ where
         });
     }
 }
+
+impl<T, R> RenderHtml<R> for Suspend<T>
+where
+    T: RenderHtml<R> + 'static,
+    R: Renderer,
+{
+    type AsyncOutput = Option<T>;
+
+    const MIN_LENGTH: usize = T::MIN_LENGTH;
+
+    fn dry_resolve(&mut self) {
+        // polling a future again after it has completed will panic, so if it is already ready,
+        // we store the value in a new future that `resolve` or `to_html` can poll instead
+        if let Some(mut inner) = (&mut self.inner).now_or_never() {
+            inner.dry_resolve();
+            self.inner = Box::pin(async move { inner });
+        }
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        Some(self.inner.await)
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        // if the value is not ready yet, this renders the same placeholder as `build`
+        let value = self.inner.now_or_never();
+        RenderHtml::<R>::to_html_with_buf(value, buf, escape);
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        let mut inner = self.inner;
+        match inner.as_mut().now_or_never() {
+            Some(value) => value.to_html_async_with_buf(buf, escape),
+            None => buf.push_async(async move {
+                let value = inner.await;
+                let mut builder = StreamBuilder::new();
+                value.to_html_async_with_buf(&mut builder, escape);
+                builder.take_chunks()
+            }),
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        self.build_with(|initial| {
+            initial.hydrate::<FROM_SERVER>(cursor, position)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Suspend;
+    use crate::{renderer::mock_dom::MockDom, view::RenderHtml};
+    use futures::{channel::oneshot, StreamExt};
+
+    #[tokio::test]
+    async fn pending_suspend_renders_placeholder() {
+        let (_tx, rx) = oneshot::channel::<&'static str>();
+        let view = ("a", Suspend::new(async move { rx.await.unwrap() }));
+        assert_eq!(RenderHtml::<MockDom>::to_html(view), "a<!>");
+    }
+
+    #[tokio::test]
+    async fn stream_waits_for_suspend() {
+        let (tx, rx) = oneshot::channel();
+        let view = (
+            "a",
+            Suspend::new(async move { ("b", rx.await.unwrap()) }),
+            "d",
+        );
+        let stream = RenderHtml::<MockDom>::to_html_stream_in_order(view);
+        tx.send("c").unwrap();
+        let html = stream.collect::<String>().await;
+        assert_eq!(html, "abcd");
+    }
+}
@@ -0,0 +1,493 @@
+use super::RenderEffectState;
+use crate::{
+    hydration::{Cursor, PositionState},
+    renderer::{CastFrom, Renderer},
+    ssr::StreamBuilder,
+    view::{Mountable, Render, RenderHtml},
+};
+use reactive_graph::{
+    effect::RenderEffect,
+    graph::untrack,
+    owner::Owner,
+    traits::{Get, GetUntracked},
+    wrappers::read::Signal,
+};
+use std::{collections::VecDeque, ops::Range};
+
+/// The visible part of a [`Virtualized`] list.
+///
+/// Both values use the same units as the list's row height: pixels for a browser, for example,
+/// or lines for a terminal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Viewport {
+    /// The distance from the top of the list to the top of the visible area.
+    pub offset: usize,
+    /// The height of the visible area.
+    pub height: usize,
+}
+
+impl Viewport {
+    /// Creates a new viewport.
+    pub fn new(offset: usize, height: usize) -> Self {
+        Self { offset, height }
+    }
+}
+
+/// Creates a list that only renders the rows of `count` that are inside the `viewport`.
+pub fn virtualized<VF, V>(
+    count: impl Into<Signal<usize>>,
+    viewport: impl Into<Signal<Viewport>>,
+    row_fn: VF,
+) -> Virtualized<VF>
+where
+    VF: Fn(usize) -> V + Send + 'static,
+{
+    Virtualized {
+        count: count.into(),
+        viewport: viewport.into(),
+        row_height: 1,
+        overscan: 0,
+        row_fn,
+    }
+}
+
+/// A list of rows, of which only those inside a reactive [`Viewport`] are built and mounted.
+///
+/// As the viewport moves, the rows that leave it are rebuilt with the rows that enter it and
+/// moved into place, rather than being dropped and created again.
+///
+/// Each row is built with its own [`Owner`], which is only cleaned up when the row leaves the
+/// viewport, so rows that stay in view keep their reactive state as the list scrolls.
+pub struct Virtualized<VF> {
+    count: Signal<usize>,
+    viewport: Signal<Viewport>,
+    row_height: usize,
+    overscan: usize,
+    row_fn: VF,
+}
+
+impl<VF> Virtualized<VF> {
+    /// Sets the height of each row, in the same units as the [`Viewport`]. Defaults to `1`.
+    pub fn row_height(mut self, row_height: usize) -> Self {
+        self.row_height = row_height.max(1);
+        self
+    }
+
+    /// Sets the number of extra rows to render on each side of the viewport. Defaults to `0`.
+    pub fn overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    fn range(&self, count: usize, viewport: Viewport) -> Range<usize> {
+        let first = viewport.offset / self.row_height;
+        let last =
+            (viewport.offset + viewport.height).div_ceil(self.row_height);
+        let start = first.saturating_sub(self.overscan).min(count);
+        let end = last.saturating_add(self.overscan).min(count).max(start);
+        start..end
+    }
+
+    fn range_untracked(&self) -> Range<usize> {
+        self.range(self.count.get_untracked(), self.viewport.get_untracked())
+    }
+}
+
+/// The rows of a [`Virtualized`] list that are currently rendered.
+pub struct VirtualRows<S, R>
+where
+    R: Renderer,
+{
+    parent: Option<R::Element>,
+    marker: R::Placeholder,
+    // the parent of each row's owner, which is not cleaned up when the viewport changes
+    owner: Owner,
+    start: usize,
+    rows: VecDeque<(Owner, S)>,
+}
+
+impl<S, R> VirtualRows<S, R>
+where
+    S: Mountable<R>,
+    R: Renderer,
+{
+    fn build<V>(
+        owner: Owner,
+        range: Range<usize>,
+        row_fn: &dyn Fn(usize) -> V,
+    ) -> Self
+    where
+        V: Render<R, State = S>,
+    {
+        let start = range.start;
+        let rows = range
+            .map(|index| build_row(&owner, || row_fn(index).build()))
+            .collect();
+        Self {
+            parent: None,
+            marker: R::create_placeholder(),
+            owner,
+            start,
+            rows,
+        }
+    }
+
+    fn update<V>(&mut self, range: Range<usize>, row_fn: &dyn Fn(usize) -> V)
+    where
+        V: Render<R, State = S>,
+    {
+        // the rendered rows and the new range are both contiguous, so the rows to keep are the
+        // ones left after trimming either end
+        let mut spare = Vec::new();
+        while !self.rows.is_empty() && !range.contains(&self.start) {
+            spare.extend(self.rows.pop_front());
+            self.start += 1;
+        }
+        while !self.rows.is_empty()
+            && !range.contains(&(self.start + self.rows.len() - 1))
+        {
+            spare.extend(self.rows.pop_back());
+        }
+        if self.rows.is_empty() {
+            self.start = range.start;
+        }
+
+        {
+            let owner = &self.owner;
+            // a recycled row is given a new owner, so that whatever the row it replaces created
+            // is cleaned up
+            let mut next_row = |index: usize| match spare.pop() {
+                Some((prev_owner, mut row)) => {
+                    let row = build_row(owner, || {
+                        row_fn(index).rebuild(&mut row);
+                        row
+                    });
+                    drop(prev_owner);
+                    row
+                }
+                None => build_row(owner, || row_fn(index).build()),
+            };
+
+            for index in (range.start..self.start).rev() {
+                let (row_owner, mut row) = next_row(index);
+                if let Some(parent) = &self.parent {
+                    match self.rows.front() {
+                        Some((_, first)) => first.insert_before_this_or_marker(
+                            parent,
+                            &mut row,
+                            Some(self.marker.as_ref()),
+                        ),
+                        None => row.mount(parent, Some(self.marker.as_ref())),
+                    }
+                }
+                self.rows.push_front((row_owner, row));
+                self.start = index;
+            }
+
+            for index in self.start + self.rows.len()..range.end {
+                let (row_owner, mut row) = next_row(index);
+                if let Some(parent) = &self.parent {
+                    row.mount(parent, Some(self.marker.as_ref()));
+                }
+                self.rows.push_back((row_owner, row));
+            }
+        }
+
+        // dropping the owners of the rows that left the viewport cleans them up
+        for (_, mut row) in spare {
+            row.unmount();
+        }
+    }
+}
+
+/// Builds a row with a new child of `owner`, and without tracking the row's reactive reads in
+/// the list's effect.
+fn build_row<S>(owner: &Owner, build: impl FnOnce() -> S) -> (Owner, S) {
+    let row_owner = owner.child();
+    let row = untrack(|| row_owner.with(build));
+    (row_owner, row)
+}
+
+impl<VF, V, R> Render<R> for Virtualized<VF>
+where
+    VF: Fn(usize) -> V + Send + 'static,
+    V: Render<R>,
+    V::State: 'static,
+    R: Renderer,
+{
+    type State = RenderEffectState<VirtualRows<V::State, R>>;
+
+    #[track_caller]
+    fn build(self) -> Self::State {
+        build_effect(self, None)
+    }
+
+    #[track_caller]
+    fn rebuild(self, state: &mut Self::State) {
+        // carry the rendered rows over, so they are recycled rather than built again
+        let prev = state.0.as_ref().and_then(|effect| effect.take_value());
+        *state = build_effect(self, prev);
+    }
+}
+
+#[track_caller]
+fn build_effect<VF, V, R>(
+    list: Virtualized<VF>,
+    prev: Option<VirtualRows<V::State, R>>,
+) -> RenderEffectState<VirtualRows<V::State, R>>
+where
+    VF: Fn(usize) -> V + Send + 'static,
+    V: Render<R>,
+    V::State: 'static,
+    R: Renderer,
+{
+    // created outside the effect, so that the rows are not cleaned up each time it runs
+    let owner = Owner::new();
+    let run = move |prev: Option<VirtualRows<V::State, R>>| {
+        let range = list.range(list.count.get(), list.viewport.get());
+        match prev {
+            Some(mut rows) => {
+                rows.update(range, &list.row_fn);
+                rows
+            }
+            None => VirtualRows::build(owner.clone(), range, &list.row_fn),
+        }
+    };
+    match prev {
+        Some(prev) => RenderEffect::new_with_value(run, Some(prev)),
+        None => RenderEffect::new(run),
+    }
+    .into()
+}
+
+impl<VF, V, R> RenderHtml<R> for Virtualized<VF>
+where
+    VF: Fn(usize) -> V + Send + 'static,
+    V: RenderHtml<R>,
+    V::State: 'static,
+    R: Renderer,
+{
+    type AsyncOutput = Self;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {
+        // rows are only created when the list is rendered
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        self
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        for index in self.range_untracked() {
+            (self.row_fn)(index).to_html_with_buf(buf, escape);
+        }
+        if escape {
+            buf.push_str("<!>");
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        for index in self.range_untracked() {
+            (self.row_fn)(index).to_html_async_with_buf(buf, escape);
+        }
+        if escape {
+            buf.push_sync("<!>");
+        }
+    }
+
+    #[track_caller]
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let cursor = cursor.clone();
+        let position = position.clone();
+        let owner = Owner::new();
+        RenderEffect::new(move |prev: Option<VirtualRows<V::State, R>>| {
+            let range = self.range(self.count.get(), self.viewport.get());
+            if let Some(mut rows) = prev {
+                rows.update(range, &self.row_fn);
+                return rows;
+            }
+            let start = range.start;
+            let rows = range
+                .map(|index| {
+                    build_row(&owner, || {
+                        (self.row_fn)(index)
+                            .hydrate::<FROM_SERVER>(&cursor, &position)
+                    })
+                })
+                .collect();
+            let marker = cursor.next_placeholder(&position);
+            // hydrated views are never mounted, so the parent is taken from the tree instead
+            let parent =
+                R::get_parent(marker.as_ref()).and_then(R::Element::cast_from);
+            VirtualRows {
+                parent,
+                marker,
+                owner: owner.clone(),
+                start,
+                rows,
+            }
+        })
+        .into()
+    }
+}
+
+impl<S, R> Mountable<R> for VirtualRows<S, R>
+where
+    S: Mountable<R>,
+    R: Renderer,
+{
+    fn unmount(&mut self) {
+        for (_, row) in &mut self.rows {
+            row.unmount();
+        }
+        self.marker.unmount();
+    }
+
+    fn mount(&mut self, parent: &R::Element, marker: Option<&R::Node>) {
+        self.parent = Some(parent.clone());
+        for (_, row) in &mut self.rows {
+            row.mount(parent, marker);
+        }
+        self.marker.mount(parent, marker);
+    }
+
+    fn insert_before_this(&self, child: &mut dyn Mountable<R>) -> bool {
+        self.rows
+            .front()
+            .map(|(_, row)| row.insert_before_this(child))
+            .unwrap_or_else(|| self.marker.insert_before_this(child))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{virtualized, Viewport};
+    use crate::{
+        renderer::mock_dom::{Document, MockDom},
+        view::{Mountable, Render, RenderHtml},
+    };
+    use any_spawner::Executor;
+    use reactive_graph::{
+        owner::Owner,
+        signal::RwSignal,
+        traits::{Get, IsDisposed, Set},
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+    use tokio::task;
+
+    #[tokio::test]
+    async fn only_rows_in_the_viewport_are_built() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        _ = Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+        task::LocalSet::new()
+            .run_until(async {
+                let viewport = RwSignal::new(Viewport::new(0, 3));
+                let root = Document::create_element("ul");
+                let list = virtualized(100_000, viewport, |index| {
+                    CALLS.fetch_add(1, Ordering::Relaxed);
+                    format!("[{index}]")
+                });
+                let mut state = Render::<MockDom>::build(list);
+                state.mount(&root, None);
+                assert_eq!(root.to_debug_html(), "<ul>[0][1][2]<!></ul>");
+                let nodes = root.children();
+
+                // scrolling by one row recycles the row that left the window
+                viewport.set(Viewport::new(1, 3));
+                Executor::tick().await;
+                assert_eq!(root.to_debug_html(), "<ul>[1][2][3]<!></ul>");
+                assert_eq!(root.children()[..2], nodes[1..3]);
+                assert_eq!(root.children()[2], nodes[0]);
+
+                viewport.set(Viewport::new(0, 2));
+                Executor::tick().await;
+                assert_eq!(root.to_debug_html(), "<ul>[0][1]<!></ul>");
+                assert_eq!(CALLS.load(Ordering::Relaxed), 5);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn rows_that_stay_in_the_viewport_keep_their_state() {
+        _ = Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+        task::LocalSet::new()
+            .run_until(async {
+                let viewport = RwSignal::new(Viewport::new(0, 3));
+                let labels = Arc::new(Mutex::new(Vec::new()));
+                let root = Document::create_element("ul");
+                let list = virtualized(100, viewport, {
+                    let labels = Arc::clone(&labels);
+                    move |index| {
+                        let label = RwSignal::new(format!("[{index}]"));
+                        labels.lock().unwrap().push(label);
+                        move || label.get()
+                    }
+                });
+                let mut state = Render::<MockDom>::build(list);
+                state.mount(&root, None);
+                assert_eq!(root.to_debug_html(), "<ul>[0][1][2]<!></ul>");
+
+                viewport.set(Viewport::new(1, 3));
+                Executor::tick().await;
+                assert_eq!(root.to_debug_html(), "<ul>[1][2][3]<!></ul>");
+
+                let labels = labels.lock().unwrap().clone();
+                // the row that left the viewport was cleaned up, and the others were kept
+                assert!(labels[0].is_disposed());
+                assert!(!labels[2].is_disposed());
+                labels[2].set("two".to_string());
+                Executor::tick().await;
+                assert_eq!(root.to_debug_html(), "<ul>[1]two[3]<!></ul>");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn rows_follow_the_count_and_row_height() {
+        _ = Executor::init_tokio();
+        let owner = Owner::new();
+        owner.set();
+        task::LocalSet::new()
+            .run_until(async {
+                let count = RwSignal::new(10);
+                let viewport = RwSignal::new(Viewport::new(40, 25));
+                let root = Document::create_element("ul");
+                let list = virtualized(count, viewport, |index| index)
+                    .row_height(10)
+                    .overscan(1);
+                let mut state = Render::<MockDom>::build(list);
+                state.mount(&root, None);
+                assert_eq!(root.to_debug_html(), "<ul>34567<!></ul>");
+
+                count.set(6);
+                Executor::tick().await;
+                assert_eq!(root.to_debug_html(), "<ul>345<!></ul>");
+
+                viewport.set(Viewport::new(0, 10));
+                Executor::tick().await;
+                assert_eq!(root.to_debug_html(), "<ul>01<!></ul>");
+            })
+            .await;
+    }
+
+    #[test]
+    fn renders_only_the_viewport_as_html() {
+        let _owner = Owner::new();
+        let list = virtualized(1_000, Viewport::new(5, 2), |index| index);
+        assert_eq!(RenderHtml::<MockDom>::to_html(list), "56<!>");
+    }
+}
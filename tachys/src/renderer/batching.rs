@@ -0,0 +1,755 @@
+//! A renderer adapter that records mutations and applies them to another renderer in one pass.
+
+use super::{CastFrom, ElementRenderer, EventRenderer, Renderer};
+use crate::{html::element::ElementType, view::Mountable};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    mem,
+};
+
+/// A [`Renderer`] that wraps some other renderer, batching its mutations.
+///
+/// Inside [`BatchingRenderer::batch`], calls that change the structure of the tree (inserting,
+/// moving, and removing nodes), set text, or update an element's attributes are recorded as a
+/// list of operations rather than being applied. Redundant operations are coalesced as they are
+/// recorded: only the last text set on a node is kept, and a node that is inserted and then
+/// moved or removed again only keeps its final operation. When the outermost batch ends, the
+/// remaining operations are applied to the inner renderer in a single pass.
+///
+/// Outside of a batch, every call is forwarded to the inner renderer immediately.
+///
+/// [`Renderer::get_parent`] takes pending operations into account. Reading other parts of the
+/// tree structure ([`Renderer::first_child`] and [`Renderer::next_sibling`]) first applies any
+/// pending operations, so that the result is accurate.
+///
+/// Operations are queued per thread and per inner renderer type. Node equality (used to
+/// coalesce operations) is provided by the inner renderer's `PartialEq` implementation.
+#[derive(Debug)]
+pub struct BatchingRenderer<R>(PhantomData<R>);
+
+enum Op<R>
+where
+    R: Renderer,
+{
+    Insert {
+        parent: R::Element,
+        child: R::Node,
+        marker: Option<R::Node>,
+    },
+    RemoveChild {
+        parent: R::Element,
+        child: R::Node,
+    },
+    Remove {
+        node: R::Node,
+    },
+    ClearChildren {
+        parent: R::Element,
+    },
+    SetText {
+        node: R::Text,
+        text: String,
+    },
+    /// Some other change to an element (like setting an attribute), applied in order.
+    Update(Box<dyn FnOnce()>),
+}
+
+impl<R> Op<R>
+where
+    R: Renderer,
+    R::Node: PartialEq,
+{
+    /// The node that is moved or removed by this operation, if any.
+    fn moved(&self) -> Option<&R::Node> {
+        match self {
+            Op::Insert { child, .. } | Op::RemoveChild { child, .. } => {
+                Some(child)
+            }
+            Op::Remove { node } => Some(node),
+            Op::ClearChildren { .. } | Op::SetText { .. } | Op::Update(_) => {
+                None
+            }
+        }
+    }
+
+    fn uses_as_marker(&self, node: &R::Node) -> bool {
+        matches!(self, Op::Insert { marker: Some(marker), .. } if marker == node)
+    }
+
+    fn apply(self) {
+        match self {
+            Op::Insert {
+                parent,
+                child,
+                marker,
+            } => R::insert_node(&parent, &child, marker.as_ref()),
+            Op::RemoveChild { parent, child } => {
+                R::remove_node(&parent, &child);
+            }
+            Op::Remove { node } => R::remove(&node),
+            Op::ClearChildren { parent } => R::clear_children(&parent),
+            Op::SetText { node, text } => R::set_text(&node, &text),
+            Op::Update(update) => update(),
+        }
+    }
+}
+
+/// What the queue knows about the parent of a node.
+enum PendingParent<N> {
+    /// A pending operation determines the parent.
+    Known(Option<N>),
+    /// No pending operation affects the parent.
+    Unchanged,
+    /// A pending operation may affect the parent, but it cannot be known without applying it.
+    Unknown,
+}
+
+struct Queue<R>
+where
+    R: Renderer,
+{
+    depth: usize,
+    ops: Vec<Op<R>>,
+}
+
+impl<R> Default for Queue<R>
+where
+    R: Renderer,
+{
+    fn default() -> Self {
+        Self {
+            depth: 0,
+            ops: Vec::new(),
+        }
+    }
+}
+
+impl<R> Queue<R>
+where
+    R: Renderer,
+    R::Node: PartialEq,
+{
+    fn push(&mut self, op: Op<R>) {
+        let op = match op {
+            Op::SetText { node, text } => {
+                // only the last text set on a node is ever visible
+                self.ops.retain(|prev| match prev {
+                    Op::SetText { node: prev, .. } => {
+                        prev.as_ref() != node.as_ref()
+                    }
+                    _ => true,
+                });
+                Op::SetText { node, text }
+            }
+            Op::ClearChildren { parent } => Op::ClearChildren { parent },
+            Op::Update(update) => Op::Update(update),
+            op => {
+                let child = op.moved().expect("op should move a node");
+                match self
+                    .ops
+                    .iter()
+                    .rposition(|prev| prev.moved() == Some(child))
+                {
+                    // an insertion that is followed by another move or removal can be skipped,
+                    // as long as nothing in between used the node as its insertion point
+                    Some(index)
+                        if matches!(self.ops[index], Op::Insert { .. })
+                            && !self.ops[index + 1..]
+                                .iter()
+                                .any(|prev| prev.uses_as_marker(child)) =>
+                    {
+                        self.ops.remove(index);
+                        match op {
+                            // the node is no longer necessarily a child of this parent, so
+                            // remove it from wherever it is
+                            Op::RemoveChild { child, .. } => {
+                                Op::Remove { node: child }
+                            }
+                            op => op,
+                        }
+                    }
+                    // removing a node twice is the same as removing it once
+                    Some(index)
+                        if matches!(self.ops[index], Op::Remove { .. })
+                            && matches!(op, Op::Remove { .. }) =>
+                    {
+                        return;
+                    }
+                    _ => op,
+                }
+            }
+        };
+        self.ops.push(op);
+    }
+
+    fn pending_parent(&self, node: &R::Node) -> PendingParent<R::Node> {
+        for op in self.ops.iter().rev() {
+            match op {
+                Op::Insert { parent, child, .. } if child == node => {
+                    return PendingParent::Known(Some(parent.as_ref().clone()));
+                }
+                Op::Remove { node: removed } if removed == node => {
+                    return PendingParent::Known(None);
+                }
+                Op::RemoveChild { child, .. } if child == node => {
+                    return PendingParent::Unknown;
+                }
+                Op::ClearChildren { .. } => return PendingParent::Unknown,
+                _ => {}
+            }
+        }
+        PendingParent::Unchanged
+    }
+}
+
+thread_local! {
+    static QUEUES: RefCell<HashMap<TypeId, Box<dyn Any>>> = Default::default();
+}
+
+fn with_queue<R, T>(fun: impl FnOnce(&mut Queue<R>) -> T) -> T
+where
+    R: Renderer,
+{
+    QUEUES.with_borrow_mut(|queues| {
+        let queue = queues
+            .entry(TypeId::of::<R>())
+            .or_insert_with(|| Box::new(Queue::<R>::default()));
+        fun(queue
+            .downcast_mut()
+            .expect("batch queue should match its renderer"))
+    })
+}
+
+/// Ends a batch when dropped, even if the batched function panics.
+struct BatchGuard<R>(PhantomData<R>)
+where
+    R: Renderer,
+    R::Node: PartialEq;
+
+impl<R> Drop for BatchGuard<R>
+where
+    R: Renderer,
+    R::Node: PartialEq,
+{
+    fn drop(&mut self) {
+        let depth = with_queue::<R, _>(|queue| {
+            queue.depth -= 1;
+            queue.depth
+        });
+        if depth == 0 {
+            BatchingRenderer::<R>::flush();
+        }
+    }
+}
+
+impl<R> BatchingRenderer<R>
+where
+    R: Renderer,
+    R::Node: PartialEq,
+{
+    /// Runs the function, recording any mutations it makes and applying them when it returns.
+    ///
+    /// Batches can be nested; mutations are only applied when the outermost batch ends.
+    pub fn batch<T>(fun: impl FnOnce() -> T) -> T {
+        with_queue::<R, _>(|queue| queue.depth += 1);
+        let _guard = BatchGuard::<R>(PhantomData);
+        fun()
+    }
+
+    /// Whether mutations are currently being recorded.
+    pub fn is_batching() -> bool {
+        with_queue::<R, _>(|queue| queue.depth > 0)
+    }
+
+    /// The number of mutations that have been recorded but not yet applied.
+    pub fn pending() -> usize {
+        with_queue::<R, _>(|queue| queue.ops.len())
+    }
+
+    /// Applies all pending mutations to the inner renderer, returning how many were applied.
+    ///
+    /// This does not end the current batch.
+    pub fn flush() -> usize {
+        let ops = with_queue::<R, _>(|queue| mem::take(&mut queue.ops));
+        let count = ops.len();
+        for op in ops {
+            op.apply();
+        }
+        count
+    }
+
+    fn update(
+        node: &BatchElement<R>,
+        update: impl FnOnce(&R::Element) + 'static,
+    ) {
+        let node = node.inner.clone();
+        Self::record(Op::Update(Box::new(move || update(&node))));
+    }
+
+    fn record(op: Op<R>) {
+        let op = with_queue::<R, _>(|queue| {
+            if queue.depth > 0 {
+                queue.push(op);
+                None
+            } else {
+                Some(op)
+            }
+        });
+        if let Some(op) = op {
+            op.apply();
+        }
+    }
+}
+
+/// A node in a [`BatchingRenderer`].
+pub struct BatchNode<R>(R::Node)
+where
+    R: Renderer;
+
+impl<R> BatchNode<R>
+where
+    R: Renderer,
+{
+    /// Wraps a node from the inner renderer.
+    pub fn new(node: R::Node) -> Self {
+        Self(node)
+    }
+
+    /// The node in the inner renderer.
+    pub fn inner(&self) -> &R::Node {
+        &self.0
+    }
+}
+
+impl<R> Clone for BatchNode<R>
+where
+    R: Renderer,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<R> Debug for BatchNode<R>
+where
+    R: Renderer,
+    R::Node: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BatchNode").field(&self.0).finish()
+    }
+}
+
+impl<R> PartialEq for BatchNode<R>
+where
+    R: Renderer,
+    R::Node: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<R> Mountable<BatchingRenderer<R>> for BatchNode<R>
+where
+    R: Renderer,
+    R::Node: PartialEq,
+{
+    fn unmount(&mut self) {
+        BatchingRenderer::<R>::remove(self);
+    }
+
+    fn mount(
+        &mut self,
+        parent: &BatchElement<R>,
+        marker: Option<&BatchNode<R>>,
+    ) {
+        BatchingRenderer::<R>::insert_node(parent, self, marker);
+    }
+
+    fn insert_before_this(
+        &self,
+        child: &mut dyn Mountable<BatchingRenderer<R>>,
+    ) -> bool {
+        let parent = BatchingRenderer::<R>::get_parent(self)
+            .and_then(BatchElement::cast_from);
+        if let Some(parent) = parent {
+            child.mount(&parent, Some(self));
+            return true;
+        }
+        false
+    }
+}
+
+macro_rules! batch_wrapper {
+    ($name:ident, $ty:ident, $doc:literal) => {
+        #[doc = $doc]
+        pub struct $name<R>
+        where
+            R: Renderer,
+        {
+            inner: R::$ty,
+            node: BatchNode<R>,
+        }
+
+        impl<R> $name<R>
+        where
+            R: Renderer,
+        {
+            /// Wraps a node from the inner renderer.
+            pub fn new(inner: R::$ty) -> Self {
+                let node = BatchNode(inner.as_ref().clone());
+                Self { inner, node }
+            }
+
+            /// The node in the inner renderer.
+            pub fn inner(&self) -> &R::$ty {
+                &self.inner
+            }
+        }
+
+        impl<R> Clone for $name<R>
+        where
+            R: Renderer,
+        {
+            fn clone(&self) -> Self {
+                Self {
+                    inner: self.inner.clone(),
+                    node: self.node.clone(),
+                }
+            }
+        }
+
+        impl<R> Debug for $name<R>
+        where
+            R: Renderer,
+            R::$ty: Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.inner).finish()
+            }
+        }
+
+        impl<R> AsRef<BatchNode<R>> for $name<R>
+        where
+            R: Renderer,
+        {
+            fn as_ref(&self) -> &BatchNode<R> {
+                &self.node
+            }
+        }
+
+        impl<R> CastFrom<BatchNode<R>> for $name<R>
+        where
+            R: Renderer,
+        {
+            fn cast_from(source: BatchNode<R>) -> Option<Self> {
+                R::$ty::cast_from(source.0).map(Self::new)
+            }
+        }
+
+        impl<R> Mountable<BatchingRenderer<R>> for $name<R>
+        where
+            R: Renderer,
+            R::Node: PartialEq,
+        {
+            fn unmount(&mut self) {
+                self.node.unmount()
+            }
+
+            fn mount(
+                &mut self,
+                parent: &BatchElement<R>,
+                marker: Option<&BatchNode<R>>,
+            ) {
+                self.node.mount(parent, marker)
+            }
+
+            fn insert_before_this(
+                &self,
+                child: &mut dyn Mountable<BatchingRenderer<R>>,
+            ) -> bool {
+                self.node.insert_before_this(child)
+            }
+        }
+    };
+}
+
+batch_wrapper!(
+    BatchElement,
+    Element,
+    "An element in a [`BatchingRenderer`]."
+);
+batch_wrapper!(BatchText, Text, "A text node in a [`BatchingRenderer`].");
+batch_wrapper!(
+    BatchPlaceholder,
+    Placeholder,
+    "A placeholder node in a [`BatchingRenderer`]."
+);
+
+impl<R> Renderer for BatchingRenderer<R>
+where
+    R: Renderer,
+    R::Node: PartialEq,
+{
+    type Node = BatchNode<R>;
+    type Element = BatchElement<R>;
+    type Text = BatchText<R>;
+    type Placeholder = BatchPlaceholder<R>;
+
+    fn intern(text: &str) -> &str {
+        R::intern(text)
+    }
+
+    fn create_text_node(text: &str) -> Self::Text {
+        BatchText::new(R::create_text_node(text))
+    }
+
+    fn create_placeholder() -> Self::Placeholder {
+        BatchPlaceholder::new(R::create_placeholder())
+    }
+
+    fn set_text(node: &Self::Text, text: &str) {
+        Self::record(Op::SetText {
+            node: node.inner.clone(),
+            text: text.to_string(),
+        });
+    }
+
+    fn set_attribute(node: &Self::Element, name: &str, value: &str) {
+        let (name, value) = (name.to_string(), value.to_string());
+        Self::update(node, move |node| R::set_attribute(node, &name, &value));
+    }
+
+    fn remove_attribute(node: &Self::Element, name: &str) {
+        let name = name.to_string();
+        Self::update(node, move |node| R::remove_attribute(node, &name));
+    }
+
+    fn add_class(node: &Self::Element, name: &str) {
+        let name = name.to_string();
+        Self::update(node, move |node| R::add_class(node, &name));
+    }
+
+    fn remove_class(node: &Self::Element, name: &str) {
+        let name = name.to_string();
+        Self::update(node, move |node| R::remove_class(node, &name));
+    }
+
+    fn set_style_property(node: &Self::Element, name: &str, value: &str) {
+        let (name, value) = (name.to_string(), value.to_string());
+        Self::update(node, move |node| {
+            R::set_style_property(node, &name, &value)
+        });
+    }
+
+    fn remove_style_property(node: &Self::Element, name: &str) {
+        let name = name.to_string();
+        Self::update(node, move |node| R::remove_style_property(node, &name));
+    }
+
+    fn set_property(node: &Self::Element, name: &str, value: &str) {
+        let (name, value) = (name.to_string(), value.to_string());
+        Self::update(node, move |node| R::set_property(node, &name, &value));
+    }
+
+    fn remove_property(node: &Self::Element, name: &str) {
+        let name = name.to_string();
+        Self::update(node, move |node| R::remove_property(node, &name));
+    }
+
+    fn insert_node(
+        parent: &Self::Element,
+        new_child: &Self::Node,
+        marker: Option<&Self::Node>,
+    ) {
+        Self::record(Op::Insert {
+            parent: parent.inner.clone(),
+            child: new_child.0.clone(),
+            marker: marker.map(|marker| marker.0.clone()),
+        });
+    }
+
+    fn remove_node(
+        parent: &Self::Element,
+        child: &Self::Node,
+    ) -> Option<Self::Node> {
+        if Self::is_batching() {
+            Self::record(Op::RemoveChild {
+                parent: parent.inner.clone(),
+                child: child.0.clone(),
+            });
+            Some(child.clone())
+        } else {
+            R::remove_node(&parent.inner, &child.0).map(BatchNode)
+        }
+    }
+
+    fn clear_children(parent: &Self::Element) {
+        Self::record(Op::ClearChildren {
+            parent: parent.inner.clone(),
+        });
+    }
+
+    fn remove(node: &Self::Node) {
+        Self::record(Op::Remove {
+            node: node.0.clone(),
+        });
+    }
+
+    fn get_parent(node: &Self::Node) -> Option<Self::Node> {
+        match with_queue::<R, _>(|queue| queue.pending_parent(&node.0)) {
+            PendingParent::Known(parent) => parent.map(BatchNode),
+            PendingParent::Unchanged => R::get_parent(&node.0).map(BatchNode),
+            PendingParent::Unknown => {
+                Self::flush();
+                R::get_parent(&node.0).map(BatchNode)
+            }
+        }
+    }
+
+    fn first_child(node: &Self::Node) -> Option<Self::Node> {
+        Self::flush();
+        R::first_child(&node.0).map(BatchNode)
+    }
+
+    fn next_sibling(node: &Self::Node) -> Option<Self::Node> {
+        Self::flush();
+        R::next_sibling(&node.0).map(BatchNode)
+    }
+
+    fn log_node(node: &Self::Node) {
+        R::log_node(&node.0)
+    }
+}
+
+impl<R> ElementRenderer for BatchingRenderer<R>
+where
+    R: ElementRenderer,
+    R::Node: PartialEq,
+{
+    fn create_element<E: ElementType>(tag: &E) -> Self::Element {
+        BatchElement::new(R::create_element(tag))
+    }
+}
+
+impl<R> EventRenderer for BatchingRenderer<R>
+where
+    R: EventRenderer,
+    R::Node: PartialEq,
+{
+    type Event = R::Event;
+    type ListenerHandle = R::ListenerHandle;
+
+    fn add_event_listener(
+        node: &Self::Element,
+        name: &str,
+        cb: Box<dyn FnMut(Self::Event)>,
+    ) -> Self::ListenerHandle {
+        // listeners do not change what is rendered, so they are not batched
+        R::add_event_listener(&node.inner, name, cb)
+    }
+
+    fn remove_event_listener(
+        node: &Self::Element,
+        name: &str,
+        handle: Self::ListenerHandle,
+    ) {
+        R::remove_event_listener(&node.inner, name, handle)
+    }
+
+    fn event_target(event: &Self::Event) -> Option<Self::Node> {
+        R::event_target(event).map(BatchNode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchElement, BatchingRenderer};
+    use crate::{
+        either::Either,
+        renderer::mock_dom::{Document, MockDom},
+        view::{Mountable, Render},
+    };
+
+    type Batched = BatchingRenderer<MockDom>;
+
+    fn root() -> BatchElement<MockDom> {
+        BatchElement::new(Document::create_element("div"))
+    }
+
+    #[test]
+    fn applies_mutations_when_batch_ends() {
+        let root = root();
+        Batched::batch(|| {
+            Render::<Batched>::build(("a", 1)).mount(&root, None);
+            assert_eq!(root.inner().to_debug_html(), "<div></div>");
+            assert_eq!(Batched::pending(), 2);
+        });
+        assert_eq!(root.inner().to_debug_html(), "<div>a1</div>");
+        assert_eq!(Batched::pending(), 0);
+    }
+
+    #[test]
+    fn applies_mutations_immediately_outside_batch() {
+        let root = root();
+        Render::<Batched>::build("a").mount(&root, None);
+        assert_eq!(root.inner().to_debug_html(), "<div>a</div>");
+    }
+
+    #[test]
+    fn coalesces_repeated_set_text() {
+        let root = root();
+        let mut state = Render::<Batched>::build(String::from("a"));
+        state.mount(&root, None);
+        Batched::batch(|| {
+            for text in ["b", "c", "d"] {
+                Render::<Batched>::rebuild(String::from(text), &mut state);
+            }
+            assert_eq!(Batched::pending(), 1);
+        });
+        assert_eq!(root.inner().to_debug_html(), "<div>d</div>");
+    }
+
+    #[test]
+    fn coalesces_insert_then_remove() {
+        let root = root();
+        Batched::batch(|| {
+            let mut state = Render::<Batched>::build("a");
+            state.mount(&root, None);
+            state.unmount();
+            assert_eq!(Batched::pending(), 1);
+        });
+        assert_eq!(root.inner().to_debug_html(), "<div></div>");
+    }
+
+    #[test]
+    fn nested_batches_apply_once() {
+        let root = root();
+        Batched::batch(|| {
+            Batched::batch(|| {
+                Render::<Batched>::build("a").mount(&root, None);
+            });
+            assert_eq!(Batched::pending(), 1);
+        });
+        assert_eq!(root.inner().to_debug_html(), "<div>a</div>");
+    }
+
+    #[test]
+    fn can_replace_nodes_inserted_in_same_batch() {
+        type View = Either<(&'static str, ()), &'static str>;
+
+        let root = root();
+        Batched::batch(|| {
+            let mut state = Render::<Batched>::build(View::Left(("a", ())));
+            state.mount(&root, None);
+            Render::<Batched>::rebuild(View::Right("b"), &mut state);
+            assert_eq!(root.inner().to_debug_html(), "<div></div>");
+        });
+        assert_eq!(root.inner().to_debug_html(), "<div>b</div>");
+    }
+}
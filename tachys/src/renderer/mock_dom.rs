@@ -0,0 +1,742 @@
+//! A stupidly-simple mock DOM implementation that can be used for testing.
+//!
+//! Do not use this for anything real.
+
+use super::{CastFrom, ElementRenderer, EventRenderer, Renderer};
+use crate::{html::element::ElementType, view::Mountable};
+use std::{cell::RefCell, rc::Rc};
+
+/// A [`Renderer`] that uses a mock DOM structure running in Rust code.
+///
+/// This is intended as a rendering backend that can be used to test component logic, without
+/// running a browser or any other platform.
+///
+/// Every thread has its own [`Document`]. Nodes are never freed until [`Document::reset`] is
+/// called, so node IDs remain valid for the lifetime of a test.
+#[derive(Debug)]
+pub struct MockDom;
+
+/// A unique identifier for a mock DOM node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// A mock DOM node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node(NodeId);
+
+/// A mock element.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Element(Node);
+
+/// A mock text node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Text(Node);
+
+/// A mock comment node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placeholder(Node);
+
+#[derive(Debug)]
+struct NodeData {
+    parent: Option<NodeId>,
+    ty: NodeType,
+}
+
+#[derive(Debug)]
+enum NodeType {
+    Text(String),
+    Element {
+        tag: String,
+        attrs: Vec<(String, String)>,
+        props: Vec<(String, String)>,
+        children: Vec<NodeId>,
+    },
+    Placeholder,
+}
+
+/// A mock event, which bubbles from its target up through its ancestors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    name: String,
+    target: Node,
+}
+
+impl Event {
+    /// Creates a new event with the given name, targeting the given node.
+    pub fn new(name: impl Into<String>, target: impl AsRef<Node>) -> Self {
+        Self {
+            name: name.into(),
+            target: target.as_ref().clone(),
+        }
+    }
+
+    /// The name of the event.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The node at which the event was dispatched.
+    pub fn target(&self) -> &Node {
+        &self.target
+    }
+}
+
+/// Identifies an event listener in the mock DOM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ListenerId(usize);
+
+type Callback = Rc<RefCell<Box<dyn FnMut(Event)>>>;
+
+struct Listener {
+    id: ListenerId,
+    node: NodeId,
+    name: String,
+    cb: Callback,
+}
+
+thread_local! {
+    static DOCUMENT: RefCell<Vec<NodeData>> = const { RefCell::new(Vec::new()) };
+    static LISTENERS: RefCell<Vec<Listener>> = const { RefCell::new(Vec::new()) };
+    static NEXT_LISTENER: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// The mock document that owns every node created by [`MockDom`] on this thread.
+#[derive(Debug)]
+pub struct Document;
+
+impl Document {
+    /// Removes every node from the document.
+    ///
+    /// Any node handles created before this call are invalidated.
+    pub fn reset() {
+        DOCUMENT.with(|d| d.borrow_mut().clear());
+        LISTENERS.with(|l| l.borrow_mut().clear());
+    }
+
+    /// Dispatches an event to the listeners on its target, then to those on each ancestor.
+    pub fn dispatch(event: Event) {
+        let mut node = Some(event.target.clone());
+        while let Some(current) = node {
+            let callbacks = LISTENERS.with(|l| {
+                l.borrow()
+                    .iter()
+                    .filter(|l| l.node == current.0 && l.name == event.name)
+                    .map(|l| Rc::clone(&l.cb))
+                    .collect::<Vec<_>>()
+            });
+            for cb in callbacks {
+                (cb.borrow_mut())(event.clone());
+            }
+            node = current.parent();
+        }
+    }
+
+    /// The number of event listeners currently attached to the node.
+    pub fn listener_count(node: impl AsRef<Node>) -> usize {
+        let id = node.as_ref().0;
+        LISTENERS.with(|l| l.borrow().iter().filter(|l| l.node == id).count())
+    }
+
+    /// Creates a new element, which can be used as the root that a view is mounted into.
+    pub fn create_element(tag: &str) -> Element {
+        Element(Self::push(NodeType::Element {
+            tag: tag.to_string(),
+            attrs: Vec::new(),
+            props: Vec::new(),
+            children: Vec::new(),
+        }))
+    }
+
+    fn push(ty: NodeType) -> Node {
+        DOCUMENT.with(|d| {
+            let mut d = d.borrow_mut();
+            d.push(NodeData { parent: None, ty });
+            Node(NodeId(d.len() - 1))
+        })
+    }
+
+    fn with_node<U>(id: NodeId, f: impl FnOnce(&NodeData) -> U) -> U {
+        DOCUMENT.with(|d| {
+            f(d.borrow()
+                .get(id.0)
+                .expect("mock DOM node used after Document::reset()"))
+        })
+    }
+
+    fn with_node_mut<U>(id: NodeId, f: impl FnOnce(&mut NodeData) -> U) -> U {
+        DOCUMENT.with(|d| {
+            f(d.borrow_mut()
+                .get_mut(id.0)
+                .expect("mock DOM node used after Document::reset()"))
+        })
+    }
+
+    fn children(id: NodeId) -> Vec<NodeId> {
+        Self::with_node(id, |node| match &node.ty {
+            NodeType::Element { children, .. } => children.clone(),
+            _ => Vec::new(),
+        })
+    }
+
+    fn with_attrs_mut(id: NodeId, f: impl FnOnce(&mut Vec<(String, String)>)) {
+        Self::with_node_mut(id, |node| {
+            if let NodeType::Element { attrs, .. } = &mut node.ty {
+                f(attrs);
+            }
+        })
+    }
+
+    fn with_props_mut(id: NodeId, f: impl FnOnce(&mut Vec<(String, String)>)) {
+        Self::with_node_mut(id, |node| {
+            if let NodeType::Element { props, .. } = &mut node.ty {
+                f(props);
+            }
+        })
+    }
+
+    fn detach(id: NodeId) {
+        if let Some(parent) = Self::with_node_mut(id, |node| node.parent.take())
+        {
+            Self::with_node_mut(parent, |node| {
+                if let NodeType::Element { children, .. } = &mut node.ty {
+                    children.retain(|child| *child != id);
+                }
+            });
+        }
+    }
+}
+
+impl Node {
+    /// The unique identifier for this node.
+    pub fn id(&self) -> NodeId {
+        self.0
+    }
+
+    /// Returns this node's parent, if it has been mounted.
+    pub fn parent(&self) -> Option<Node> {
+        Document::with_node(self.0, |node| node.parent).map(Node)
+    }
+
+    /// Returns this node's children. Only elements have children.
+    pub fn children(&self) -> Vec<Node> {
+        Document::children(self.0).into_iter().map(Node).collect()
+    }
+
+    /// Returns the concatenated contents of every text node within this node.
+    pub fn text_content(&self) -> String {
+        let mut buf = String::new();
+        self.push_text_content(&mut buf);
+        buf
+    }
+
+    fn push_text_content(&self, buf: &mut String) {
+        let text = Document::with_node(self.0, |node| match &node.ty {
+            NodeType::Text(text) => Some(text.clone()),
+            _ => None,
+        });
+        match text {
+            Some(text) => buf.push_str(&text),
+            None => {
+                for child in self.children() {
+                    child.push_text_content(buf);
+                }
+            }
+        }
+    }
+
+    /// Serializes this node and its children as an HTML-like string.
+    ///
+    /// Placeholders are rendered as `<!>`.
+    pub fn to_debug_html(&self) -> String {
+        let mut buf = String::new();
+        self.push_debug_html(&mut buf);
+        buf
+    }
+
+    fn push_debug_html(&self, buf: &mut String) {
+        let tag = Document::with_node(self.0, |node| match &node.ty {
+            NodeType::Text(text) => {
+                buf.push_str(text);
+                None
+            }
+            NodeType::Element { tag, attrs, .. } => {
+                Some((tag.clone(), attrs.clone()))
+            }
+            NodeType::Placeholder => {
+                buf.push_str("<!>");
+                None
+            }
+        });
+        if let Some((tag, attrs)) = tag {
+            buf.push('<');
+            buf.push_str(&tag);
+            for (name, value) in attrs {
+                buf.push(' ');
+                buf.push_str(&name);
+                buf.push_str("=\"");
+                buf.push_str(&value);
+                buf.push('"');
+            }
+            buf.push('>');
+            for child in self.children() {
+                child.push_debug_html(buf);
+            }
+            buf.push_str("</");
+            buf.push_str(&tag);
+            buf.push('>');
+        }
+    }
+}
+
+impl Element {
+    /// The tag name of this element.
+    pub fn tag(&self) -> String {
+        Document::with_node(self.0 .0, |node| match &node.ty {
+            NodeType::Element { tag, .. } => tag.clone(),
+            _ => unreachable!("mock Element did not point to an element node"),
+        })
+    }
+
+    /// Returns this element's children.
+    pub fn children(&self) -> Vec<Node> {
+        self.0.children()
+    }
+
+    /// The current value of an attribute, if it is set.
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        Document::with_node(self.0 .0, |node| match &node.ty {
+            NodeType::Element { attrs, .. } => get_entry(attrs, name),
+            _ => None,
+        })
+    }
+
+    /// The current value of a property, if it is set.
+    pub fn property(&self, name: &str) -> Option<String> {
+        Document::with_node(self.0 .0, |node| match &node.ty {
+            NodeType::Element { props, .. } => get_entry(props, name),
+            _ => None,
+        })
+    }
+
+    /// Returns the concatenated contents of every text node within this element.
+    pub fn text_content(&self) -> String {
+        self.0.text_content()
+    }
+
+    /// Serializes this element and its children as an HTML-like string.
+    pub fn to_debug_html(&self) -> String {
+        self.0.to_debug_html()
+    }
+
+    /// Returns every descendant element with the given tag, in document order.
+    pub fn query_all(&self, tag: &str) -> Vec<Element> {
+        let mut found = Vec::new();
+        self.push_matching(tag, &mut found);
+        found
+    }
+
+    /// Returns the first descendant element with the given tag, in document order.
+    pub fn query(&self, tag: &str) -> Option<Element> {
+        self.query_all(tag).into_iter().next()
+    }
+
+    fn push_matching(&self, tag: &str, found: &mut Vec<Element>) {
+        for child in self.children() {
+            if let Some(child) = Element::cast_from(child) {
+                if child.tag() == tag {
+                    found.push(child.clone());
+                }
+                child.push_matching(tag, found);
+            }
+        }
+    }
+}
+
+impl Text {
+    /// The current contents of this text node.
+    pub fn text(&self) -> String {
+        Document::with_node(self.0 .0, |node| match &node.ty {
+            NodeType::Text(text) => text.clone(),
+            _ => unreachable!("mock Text did not point to a text node"),
+        })
+    }
+}
+
+fn get_entry(entries: &[(String, String)], name: &str) -> Option<String> {
+    entries
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.clone())
+}
+
+fn set_entry(entries: &mut Vec<(String, String)>, name: &str, value: String) {
+    match entries.iter_mut().find(|(key, _)| key == name) {
+        Some((_, old)) => *old = value,
+        None => entries.push((name.to_string(), value)),
+    }
+}
+
+fn remove_entry(entries: &mut Vec<(String, String)>, name: &str) {
+    entries.retain(|(key, _)| key != name);
+}
+
+/// Updates the list of classes in a `class` attribute.
+fn update_classes(
+    attrs: &mut Vec<(String, String)>,
+    f: impl FnOnce(&mut Vec<String>),
+) {
+    let mut classes = get_entry(attrs, "class")
+        .map(|class| class.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+    f(&mut classes);
+    if classes.is_empty() {
+        remove_entry(attrs, "class");
+    } else {
+        set_entry(attrs, "class", classes.join(" "));
+    }
+}
+
+/// Updates the list of properties in a `style` attribute.
+fn update_styles(
+    attrs: &mut Vec<(String, String)>,
+    f: impl FnOnce(&mut Vec<(String, String)>),
+) {
+    let mut styles = get_entry(attrs, "style")
+        .map(|style| {
+            style
+                .split(';')
+                .filter_map(|decl| decl.split_once(':'))
+                .map(|(name, value)| {
+                    (name.trim().to_string(), value.trim().to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    f(&mut styles);
+    if styles.is_empty() {
+        remove_entry(attrs, "style");
+    } else {
+        let style = styles
+            .iter()
+            .map(|(name, value)| format!("{name}: {value};"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        set_entry(attrs, "style", style);
+    }
+}
+
+impl AsRef<Node> for Node {
+    fn as_ref(&self) -> &Node {
+        self
+    }
+}
+
+macro_rules! node_wrapper {
+    ($name:ident, $pat:pat) => {
+        impl AsRef<Node> for $name {
+            fn as_ref(&self) -> &Node {
+                &self.0
+            }
+        }
+
+        impl CastFrom<Node> for $name {
+            fn cast_from(source: Node) -> Option<Self> {
+                Document::with_node(source.0, |node| matches!(node.ty, $pat))
+                    .then_some($name(source))
+            }
+        }
+
+        impl Mountable<MockDom> for $name {
+            fn unmount(&mut self) {
+                self.0.unmount()
+            }
+
+            fn mount(&mut self, parent: &Element, marker: Option<&Node>) {
+                self.0.mount(parent, marker)
+            }
+
+            fn insert_before_this(
+                &self,
+                child: &mut dyn Mountable<MockDom>,
+            ) -> bool {
+                self.0.insert_before_this(child)
+            }
+        }
+    };
+}
+
+node_wrapper!(Element, NodeType::Element { .. });
+node_wrapper!(Text, NodeType::Text(_));
+node_wrapper!(Placeholder, NodeType::Placeholder);
+
+impl Mountable<MockDom> for Node {
+    fn unmount(&mut self) {
+        MockDom::remove(self);
+    }
+
+    fn mount(&mut self, parent: &Element, marker: Option<&Node>) {
+        MockDom::insert_node(parent, self, marker);
+    }
+
+    fn insert_before_this(&self, child: &mut dyn Mountable<MockDom>) -> bool {
+        let parent = MockDom::get_parent(self).and_then(Element::cast_from);
+        if let Some(parent) = parent {
+            child.mount(&parent, Some(self));
+            return true;
+        }
+        false
+    }
+}
+
+impl Renderer for MockDom {
+    type Node = Node;
+    type Element = Element;
+    type Text = Text;
+    type Placeholder = Placeholder;
+
+    fn intern(text: &str) -> &str {
+        text
+    }
+
+    fn create_text_node(text: &str) -> Self::Text {
+        Text(Document::push(NodeType::Text(text.to_string())))
+    }
+
+    fn create_placeholder() -> Self::Placeholder {
+        Placeholder(Document::push(NodeType::Placeholder))
+    }
+
+    fn set_text(node: &Self::Text, text: &str) {
+        Document::with_node_mut(node.0 .0, |node| {
+            if let NodeType::Text(ref mut old) = node.ty {
+                *old = text.to_string();
+            }
+        });
+    }
+
+    fn set_attribute(node: &Self::Element, name: &str, value: &str) {
+        Document::with_attrs_mut(node.0 .0, |attrs| {
+            set_entry(attrs, name, value.to_string())
+        });
+    }
+
+    fn remove_attribute(node: &Self::Element, name: &str) {
+        Document::with_attrs_mut(node.0 .0, |attrs| remove_entry(attrs, name));
+    }
+
+    fn add_class(node: &Self::Element, name: &str) {
+        Document::with_attrs_mut(node.0 .0, |attrs| {
+            update_classes(attrs, |classes| {
+                if !classes.iter().any(|class| class == name) {
+                    classes.push(name.to_string());
+                }
+            })
+        });
+    }
+
+    fn remove_class(node: &Self::Element, name: &str) {
+        Document::with_attrs_mut(node.0 .0, |attrs| {
+            update_classes(attrs, |classes| {
+                classes.retain(|class| class != name)
+            })
+        });
+    }
+
+    fn set_style_property(node: &Self::Element, name: &str, value: &str) {
+        Document::with_attrs_mut(node.0 .0, |attrs| {
+            update_styles(attrs, |styles| {
+                set_entry(styles, name, value.to_string())
+            })
+        });
+    }
+
+    fn remove_style_property(node: &Self::Element, name: &str) {
+        Document::with_attrs_mut(node.0 .0, |attrs| {
+            update_styles(attrs, |styles| remove_entry(styles, name))
+        });
+    }
+
+    fn set_property(node: &Self::Element, name: &str, value: &str) {
+        Document::with_props_mut(node.0 .0, |props| {
+            set_entry(props, name, value.to_string())
+        });
+    }
+
+    fn remove_property(node: &Self::Element, name: &str) {
+        Document::with_props_mut(node.0 .0, |props| remove_entry(props, name));
+    }
+
+    fn insert_node(
+        parent: &Self::Element,
+        new_child: &Self::Node,
+        marker: Option<&Self::Node>,
+    ) {
+        let parent_id = parent.0 .0;
+        let child_id = new_child.0;
+        Document::detach(child_id);
+        Document::with_node_mut(parent_id, |node| {
+            if let NodeType::Element { children, .. } = &mut node.ty {
+                let index = marker
+                    .and_then(|marker| {
+                        children.iter().position(|id| *id == marker.0)
+                    })
+                    .unwrap_or(children.len());
+                children.insert(index, child_id);
+            }
+        });
+        Document::with_node_mut(child_id, |node| node.parent = Some(parent_id));
+    }
+
+    fn remove_node(
+        parent: &Self::Element,
+        child: &Self::Node,
+    ) -> Option<Self::Node> {
+        let is_child = Document::with_node(child.0, |node| node.parent)
+            == Some(parent.0 .0);
+        if is_child {
+            Document::detach(child.0);
+            Some(child.clone())
+        } else {
+            None
+        }
+    }
+
+    fn clear_children(parent: &Self::Element) {
+        for child in Document::children(parent.0 .0) {
+            Document::detach(child);
+        }
+    }
+
+    fn remove(node: &Self::Node) {
+        Document::detach(node.0);
+    }
+
+    fn get_parent(node: &Self::Node) -> Option<Self::Node> {
+        node.parent()
+    }
+
+    fn first_child(node: &Self::Node) -> Option<Self::Node> {
+        Document::children(node.0).first().copied().map(Node)
+    }
+
+    fn next_sibling(node: &Self::Node) -> Option<Self::Node> {
+        let parent = node.parent()?;
+        let siblings = Document::children(parent.0);
+        let index = siblings.iter().position(|id| *id == node.0)?;
+        siblings.get(index + 1).copied().map(Node)
+    }
+
+    fn log_node(node: &Self::Node) {
+        eprintln!("{}", node.to_debug_html());
+    }
+}
+
+impl ElementRenderer for MockDom {
+    fn create_element<E: ElementType>(tag: &E) -> Self::Element {
+        Document::create_element(tag.tag())
+    }
+}
+
+impl EventRenderer for MockDom {
+    type Event = Event;
+    type ListenerHandle = ListenerId;
+
+    fn add_event_listener(
+        node: &Self::Element,
+        name: &str,
+        cb: Box<dyn FnMut(Self::Event)>,
+    ) -> Self::ListenerHandle {
+        let id = ListenerId(NEXT_LISTENER.with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            id
+        }));
+        LISTENERS.with(|l| {
+            l.borrow_mut().push(Listener {
+                id,
+                node: node.0 .0,
+                name: name.to_string(),
+                cb: Rc::new(RefCell::new(cb)),
+            })
+        });
+        id
+    }
+
+    fn remove_event_listener(
+        _node: &Self::Element,
+        _name: &str,
+        handle: Self::ListenerHandle,
+    ) {
+        LISTENERS.with(|l| l.borrow_mut().retain(|l| l.id != handle));
+    }
+
+    fn event_target(event: &Self::Event) -> Option<Self::Node> {
+        Some(event.target.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Document, MockDom};
+    use crate::{
+        either::Either,
+        renderer::Renderer,
+        view::{Mountable, Render},
+    };
+
+    #[test]
+    fn mounts_text_and_placeholders() {
+        let root = Document::create_element("div");
+        let mut state = Render::<MockDom>::build(("Hello, ", 42, ()));
+        state.mount(&root, None);
+        assert_eq!(root.to_debug_html(), "<div>Hello, 42<!></div>");
+        assert_eq!(root.text_content(), "Hello, 42");
+    }
+
+    #[test]
+    fn rebuilds_in_place() {
+        let root = Document::create_element("div");
+        let mut state = Render::<MockDom>::build(("a", String::from("b")));
+        state.mount(&root, None);
+        let first = root.children()[1].clone();
+        Render::<MockDom>::rebuild(("a", String::from("c")), &mut state);
+        assert_eq!(root.to_debug_html(), "<div>ac</div>");
+        assert_eq!(root.children()[1], first);
+    }
+
+    #[test]
+    fn either_swaps_branches() {
+        let root = Document::create_element("div");
+        let view: Either<&str, i32> = Either::Left("left");
+        let mut state = Render::<MockDom>::build(view);
+        state.mount(&root, None);
+        assert_eq!(root.to_debug_html(), "<div>left</div>");
+        Render::<MockDom>::rebuild(Either::<&str, i32>::Right(1), &mut state);
+        assert_eq!(root.to_debug_html(), "<div>1</div>");
+    }
+
+    #[test]
+    fn unmounting_removes_nodes() {
+        let root = Document::create_element("div");
+        let mut state = Render::<MockDom>::build(("a", "b"));
+        state.mount(&root, None);
+        state.unmount();
+        assert_eq!(root.to_debug_html(), "<div></div>");
+        assert!(MockDom::first_child(root.as_ref()).is_none());
+    }
+
+    #[test]
+    fn queries_descendants() {
+        let root = Document::create_element("ul");
+        let item = Document::create_element("li");
+        MockDom::insert_node(&root, item.as_ref(), None);
+        let mut text = Render::<MockDom>::build("one");
+        text.mount(&item, None);
+        assert_eq!(root.query_all("li"), vec![item.clone()]);
+        assert_eq!(
+            root.query("li").map(|li| li.text_content()),
+            Some("one".into())
+        );
+        assert!(root.query("p").is_none());
+    }
+}
@@ -1,18 +1,20 @@
-use crate::view::Mountable;
+use crate::{html::element::ElementType, view::Mountable};
 use std::fmt::Debug;
 
-/* #[cfg(feature = "testing")]
+/// A renderer adapter that batches and coalesces mutations.
+pub mod batching;
 /// A renderer based on a mock DOM.
+#[cfg(any(test, feature = "testing"))]
 pub mod mock_dom;
-/// A DOM renderer optimized for element creation.
-#[cfg(feature = "sledgehammer")]
-pub mod sledgehammer; */
 
 /// Implements the instructions necessary to render an interface on some platform.
 ///
 /// By default, this is implemented for the Document Object Model (DOM) in a Web
 /// browser, but implementing this trait for some other platform allows you to use
 /// the library to render any tree-based UI.
+///
+/// The operations on attributes, classes, styles and properties do nothing by default, so that
+/// platforms without those concepts only need to implement the ones they support.
 pub trait Renderer: Send + Sized + Debug + 'static {
     /// The basic type of node in the view tree.
     type Node: Mountable<Self> + Clone + 'static;
@@ -48,6 +50,49 @@ pub trait Renderer: Send + Sized + Debug + 'static {
     /// Sets the text content of the node. If it's not a text node, this does nothing.
     fn set_text(node: &Self::Text, text: &str);
 
+    /// Sets an attribute on an element.
+    fn set_attribute(node: &Self::Element, name: &str, value: &str) {
+        _ = (node, name, value);
+    }
+
+    /// Removes an attribute from an element.
+    fn remove_attribute(node: &Self::Element, name: &str) {
+        _ = (node, name);
+    }
+
+    /// Adds a class to an element's list of classes.
+    fn add_class(node: &Self::Element, name: &str) {
+        _ = (node, name);
+    }
+
+    /// Removes a class from an element's list of classes.
+    fn remove_class(node: &Self::Element, name: &str) {
+        _ = (node, name);
+    }
+
+    /// Sets a property in an element's inline style.
+    fn set_style_property(node: &Self::Element, name: &str, value: &str) {
+        _ = (node, name, value);
+    }
+
+    /// Removes a property from an element's inline style.
+    fn remove_style_property(node: &Self::Element, name: &str) {
+        _ = (node, name);
+    }
+
+    /// Sets a property on an element.
+    ///
+    /// Property values are given as strings; the renderer is responsible for converting them
+    /// into whatever type the platform expects.
+    fn set_property(node: &Self::Element, name: &str, value: &str) {
+        _ = (node, name, value);
+    }
+
+    /// Removes (or unsets) a property on an element.
+    fn remove_property(node: &Self::Element, name: &str) {
+        _ = (node, name);
+    }
+
     /// Appends the new child to the parent, before the anchor node. If `anchor` is `None`,
     /// append to the end of the parent's children.
     fn insert_node(
@@ -114,6 +159,43 @@ pub trait Renderer: Send + Sized + Debug + 'static {
     fn log_node(node: &Self::Node);
 }
 
+/// A [`Renderer`] that can create elements from typed tags.
+///
+/// Element views, such as [`HtmlElement`](crate::html::element::HtmlElement), can only be used
+/// with renderers that implement this.
+pub trait ElementRenderer: Renderer {
+    /// Creates a new element of the given type.
+    fn create_element<E: ElementType>(tag: &E) -> Self::Element;
+}
+
+/// A [`Renderer`] for a platform that dispatches events to its elements.
+///
+/// Event listeners, such as [`on`](crate::html::event::on), can only be used with renderers
+/// that implement this.
+pub trait EventRenderer: Renderer {
+    /// An event that is dispatched to the listeners on an element.
+    type Event: 'static;
+    /// Identifies an event listener that has been added, so that it can be removed.
+    type ListenerHandle: 'static;
+
+    /// Adds an event listener to an element, returning a handle that can be used to remove it.
+    fn add_event_listener(
+        node: &Self::Element,
+        name: &str,
+        cb: Box<dyn FnMut(Self::Event)>,
+    ) -> Self::ListenerHandle;
+
+    /// Removes an event listener that was added with [`EventRenderer::add_event_listener`].
+    fn remove_event_listener(
+        node: &Self::Element,
+        name: &str,
+        handle: Self::ListenerHandle,
+    );
+
+    /// Returns the node at which an event was originally dispatched, if any.
+    fn event_target(event: &Self::Event) -> Option<Self::Node>;
+}
+
 /// Attempts to cast from one type to another.
 ///
 /// This works in a similar way to `TryFrom`. We implement it as a separate trait
@@ -0,0 +1,170 @@
+use futures::Stream;
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+type PinnedFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Manages streaming text rendering for a view tree.
+///
+/// Synchronous sections of the view are collected into a buffer; asynchronous sections are
+/// pushed as futures, which are awaited in order when the builder is polled as a [`Stream`].
+#[derive(Default)]
+pub struct StreamBuilder {
+    sync_buf: String,
+    chunks: VecDeque<StreamChunk>,
+}
+
+/// A chunk of the text stream.
+pub enum StreamChunk {
+    /// Some synchronously-available text.
+    Sync(String),
+    /// The chunks produced by some asynchronous section of the view.
+    Async {
+        /// The future that resolves to the inner chunks.
+        chunks: PinnedFuture<VecDeque<StreamChunk>>,
+    },
+}
+
+impl Debug for StreamChunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sync(arg0) => f.debug_tuple("Sync").field(arg0).finish(),
+            Self::Async { .. } => {
+                f.debug_struct("Async").finish_non_exhaustive()
+            }
+        }
+    }
+}
+
+impl Debug for StreamBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamBuilder")
+            .field("sync_buf", &self.sync_buf)
+            .field("chunks", &self.chunks)
+            .finish()
+    }
+}
+
+impl StreamBuilder {
+    /// Creates a new, empty stream.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new stream with the given capacity in its synchronous buffer.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            sync_buf: String::with_capacity(capacity),
+            chunks: VecDeque::new(),
+        }
+    }
+
+    /// Adds some synchronous text to the stream.
+    pub fn push_sync(&mut self, string: &str) {
+        self.sync_buf.push_str(string);
+    }
+
+    /// Runs a function with access to the synchronous buffer.
+    pub fn with_buf(&mut self, fun: impl FnOnce(&mut String)) {
+        fun(&mut self.sync_buf)
+    }
+
+    /// Adds an asynchronous section to the stream, after any text that has already been pushed.
+    pub fn push_async(
+        &mut self,
+        fut: impl Future<Output = VecDeque<StreamChunk>> + Send + 'static,
+    ) {
+        self.flush();
+        self.chunks.push_back(StreamChunk::Async {
+            chunks: Box::pin(fut),
+        });
+    }
+
+    /// Appends another stream to this one.
+    pub fn append(&mut self, mut other: StreamBuilder) {
+        self.flush();
+        self.chunks.append(&mut other.take_chunks());
+    }
+
+    /// Moves any remaining synchronous text into the chunk list.
+    pub fn finish(mut self) -> Self {
+        self.flush();
+        self
+    }
+
+    /// Takes the chunks from this stream, including any unflushed synchronous text.
+    pub fn take_chunks(&mut self) -> VecDeque<StreamChunk> {
+        self.flush();
+        mem::take(&mut self.chunks)
+    }
+
+    fn flush(&mut self) {
+        if !self.sync_buf.is_empty() {
+            let sync = mem::take(&mut self.sync_buf);
+            self.chunks.push_back(StreamChunk::Sync(sync));
+        }
+    }
+}
+
+impl Stream for StreamBuilder {
+    type Item = String;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        this.flush();
+        match this.chunks.pop_front() {
+            None => Poll::Ready(None),
+            Some(StreamChunk::Sync(mut value)) => {
+                // merge adjacent synchronous chunks into a single item
+                while let Some(StreamChunk::Sync(next)) = this.chunks.front() {
+                    value.push_str(next);
+                    this.chunks.pop_front();
+                }
+                Poll::Ready(Some(value))
+            }
+            Some(StreamChunk::Async { mut chunks }) => {
+                match chunks.as_mut().poll(cx) {
+                    Poll::Ready(chunks) => {
+                        for chunk in chunks.into_iter().rev() {
+                            this.chunks.push_front(chunk);
+                        }
+                        Pin::new(this).poll_next(cx)
+                    }
+                    Poll::Pending => {
+                        this.chunks.push_front(StreamChunk::Async { chunks });
+                        Poll::Pending
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamBuilder;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn streams_chunks_in_order() {
+        let mut builder = StreamBuilder::new();
+        builder.push_sync("a");
+        builder.push_async(async {
+            let mut inner = StreamBuilder::new();
+            inner.push_sync("b");
+            inner.take_chunks()
+        });
+        builder.push_sync("c");
+        let chunks = builder.finish().collect::<Vec<_>>().await;
+        assert_eq!(chunks, vec!["a", "bc"]);
+    }
+}
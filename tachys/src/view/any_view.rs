@@ -1,9 +1,11 @@
-use super::{Mountable, Render};
+use super::{Mountable, Render, RenderHtml};
 use crate::{
     erased::{Erased, ErasedLocal},
+    hydration::{Cursor, PositionState},
     prelude::Renderer,
+    ssr::StreamBuilder,
 };
-use std::{any::TypeId, fmt::Debug};
+use std::{any::TypeId, fmt::Debug, future::Future, pin::Pin};
 
 /// A type-erased view. This can be used if control flow requires that multiple different types of
 /// view must be received, and it is either impossible or too cumbersome to use the `EitherOf___`
@@ -15,6 +17,10 @@ use std::{any::TypeId, fmt::Debug};
 /// Generally speaking, using `AnyView` restricts the amount of information available to the
 /// compiler and should be limited to situations in which it is necessary to preserve the maximum
 /// amount of type information possible.
+///
+/// An `AnyView` can only be rendered as text or hydrated if it was created with
+/// [`IntoAnyHtml::into_any_html`], which requires the view to implement [`RenderHtml`]. Rendering
+/// a view created with [`IntoAny::into_any`] as text panics.
 pub struct AnyView<R>
 where
     R: Renderer,
@@ -23,6 +29,24 @@ where
     value: Erased,
     build: fn(Erased) -> AnyViewState<R>,
     rebuild: fn(Erased, &mut AnyViewState<R>),
+    html: Option<AnyViewHtml<R>>,
+}
+
+/// The functions used to render an [`AnyView`] as text, or to hydrate it.
+struct AnyViewHtml<R>
+where
+    R: Renderer,
+{
+    len: usize,
+    dry_resolve: fn(&mut Erased),
+    #[allow(clippy::type_complexity)]
+    resolve: fn(Erased) -> Pin<Box<dyn Future<Output = AnyView<R>> + Send>>,
+    to_html: fn(Erased, &mut String, bool),
+    to_html_async: fn(Erased, &mut StreamBuilder, bool),
+    hydrate_from_server:
+        fn(Erased, &Cursor<R>, &PositionState) -> AnyViewState<R>,
+    hydrate_from_local:
+        fn(Erased, &Cursor<R>, &PositionState) -> AnyViewState<R>,
 }
 
 impl<R> Debug for AnyView<R>
@@ -71,6 +95,15 @@ where
     fn into_any(self) -> AnyView<R>;
 }
 
+/// Allows converting some view into an [`AnyView`] that can also be rendered as text or hydrated.
+pub trait IntoAnyHtml<R>
+where
+    R: Renderer,
+{
+    /// Converts the view into a type-erased [`AnyView`] that implements [`RenderHtml`].
+    fn into_any_html(self) -> AnyView<R>;
+}
+
 /// A more general version of [`IntoAny`] that allows into [`AnyView`],
 /// but also erasing other types that don't implement [`RenderHtml`] like routing.
 pub trait IntoMaybeErased<R> {
@@ -138,6 +171,73 @@ where
     state.get_ref::<T::State>().insert_before_this(child)
 }
 
+fn dry_resolve<T, R>(value: &mut Erased)
+where
+    T: RenderHtml<R> + 'static,
+    R: Renderer,
+{
+    value.get_mut::<T>().dry_resolve();
+}
+
+fn resolve<T, R>(
+    value: Erased,
+) -> Pin<Box<dyn Future<Output = AnyView<R>> + Send>>
+where
+    T: RenderHtml<R> + 'static,
+    R: Renderer,
+{
+    Box::pin(
+        async move { value.into_inner::<T>().resolve().await.into_any_html() },
+    )
+}
+
+fn to_html<T, R>(value: Erased, buf: &mut String, escape: bool)
+where
+    T: RenderHtml<R> + 'static,
+    R: Renderer,
+{
+    value.into_inner::<T>().to_html_with_buf(buf, escape);
+}
+
+fn to_html_async<T, R>(value: Erased, buf: &mut StreamBuilder, escape: bool)
+where
+    T: RenderHtml<R> + 'static,
+    R: Renderer,
+{
+    value.into_inner::<T>().to_html_async_with_buf(buf, escape);
+}
+
+fn erase_state<T, R>(state: T::State) -> AnyViewState<R>
+where
+    T: Render<R>,
+    T::State: 'static,
+    R: Renderer,
+{
+    AnyViewState {
+        type_id: TypeId::of::<T>(),
+        state: ErasedLocal::new(state),
+        mount: mount_any::<T, R>,
+        unmount: unmount_any::<T, R>,
+        insert_before_this: insert_before_this::<T, R>,
+    }
+}
+
+fn hydrate<T, R, const FROM_SERVER: bool>(
+    value: Erased,
+    cursor: &Cursor<R>,
+    position: &PositionState,
+) -> AnyViewState<R>
+where
+    T: RenderHtml<R> + 'static,
+    T::State: 'static,
+    R: Renderer,
+{
+    let state = value
+        .into_inner::<T>()
+        .hydrate::<FROM_SERVER>(cursor, position);
+    erase_state::<T, R>(state)
+}
+
 impl<T, R> IntoAny<R> for T
 where
     T: Send,
@@ -146,15 +246,11 @@ where
     R: Renderer,
 {
     fn into_any(self) -> AnyView<R> {
-        fn build<T: Render<R>, R: Renderer>(value: Erased) -> AnyViewState<R> {
-            let state = ErasedLocal::new(value.into_inner::<T>().build());
-            AnyViewState {
-                type_id: TypeId::of::<T>(),
-                state,
-                mount: mount_any::<T, R>,
-                unmount: unmount_any::<T, R>,
-                insert_before_this: insert_before_this::<T, R>,
-            }
+        fn build<T: Render<R>, R: Renderer>(value: Erased) -> AnyViewState<R>
+        where
+            T::State: 'static,
+        {
+            erase_state::<T, R>(value.into_inner::<T>().build())
         }
 
         fn rebuild<T: Render<R>, R: Renderer>(
@@ -170,10 +266,33 @@ where
             value: Erased::new(self),
             build: build::<T, R>,
             rebuild: rebuild::<T, R>,
+            html: None,
         }
     }
 }
 
+impl<T, R> IntoAnyHtml<R> for T
+where
+    T: RenderHtml<R>,
+    T::State: 'static,
+    R: Renderer,
+{
+    fn into_any_html(self) -> AnyView<R> {
+        let len = self.html_len();
+        let mut view = self.into_any();
+        view.html = Some(AnyViewHtml {
+            len,
+            dry_resolve: dry_resolve::<T, R>,
+            resolve: resolve::<T, R>,
+            to_html: to_html::<T, R>,
+            to_html_async: to_html_async::<T, R>,
+            hydrate_from_server: hydrate::<T, R, true>,
+            hydrate_from_local: hydrate::<T, R, false>,
+        });
+        view
+    }
+}
+
 impl<R> Render<R> for AnyView<R>
 where
     R: Renderer,
@@ -196,6 +315,65 @@ where
     }
 }
 
+impl<R> AnyView<R>
+where
+    R: Renderer,
+{
+    fn expect_html(&self) -> &AnyViewHtml<R> {
+        self.html.as_ref().expect(
+            "an `AnyView` can only be rendered as text or hydrated if it was created with \
+             `IntoAnyHtml::into_any_html`",
+        )
+    }
+}
+
+impl<R> RenderHtml<R> for AnyView<R>
+where
+    R: Renderer,
+{
+    type AsyncOutput = Self;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {
+        if let Some(html) = &self.html {
+            (html.dry_resolve)(&mut self.value)
+        }
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        match &self.html {
+            Some(html) => (html.resolve)(self.value).await,
+            None => self,
+        }
+    }
+
+    fn html_len(&self) -> usize {
+        self.html.as_ref().map_or(0, |html| html.len)
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        (self.expect_html().to_html)(self.value, buf, escape);
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        (self.expect_html().to_html_async)(self.value, buf, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let html = self.expect_html();
+        if FROM_SERVER {
+            (html.hydrate_from_server)(self.value, cursor, position)
+        } else {
+            (html.hydrate_from_local)(self.value, cursor, position)
+        }
+    }
+}
+
 impl<R> Mountable<R> for AnyViewState<R>
 where
     R: Renderer,
@@ -213,12 +391,10 @@ where
     }
 }
 
-/*
 #[cfg(test)]
 mod tests {
-    use super::IntoAny;
+    use super::{IntoAny, IntoAnyHtml};
     use crate::{
-        html::element::{p, span},
         renderer::mock_dom::MockDom,
         view::{any_view::AnyView, RenderHtml},
     };
@@ -226,14 +402,26 @@ mod tests {
     #[test]
     fn should_handle_html_creation() {
         let x = 1;
-        let mut buf = String::new();
         let view: AnyView<MockDom> = if x == 0 {
-            p((), "foo").into_any()
+            "foo".into_any_html()
         } else {
-            span((), "bar").into_any()
+            ("bar", ()).into_any_html()
         };
-        view.to_html(&mut buf, &Default::default());
-        assert_eq!(buf, "<span>bar</span><!>");
+        assert_eq!(view.to_html(), "bar<!>");
+    }
+
+    #[test]
+    fn should_escape_text() {
+        let view: AnyView<MockDom> = "<b>&</b>".into_any_html();
+        assert_eq!(view.to_html(), "&lt;b&gt;&amp;&lt;/b&gt;");
+        let view: AnyView<MockDom> = ("<b>", ()).into_any_html();
+        assert_eq!(view.to_text(), "<b>");
+    }
+
+    #[test]
+    #[should_panic(expected = "IntoAnyHtml::into_any_html")]
+    fn views_erased_without_html_cannot_be_rendered_as_text() {
+        let view: AnyView<MockDom> = "foo".into_any();
+        view.to_html();
     }
 }
- */
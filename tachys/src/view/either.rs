@@ -1,7 +1,37 @@
-use super::{Mountable, Render};
-use crate::prelude::Renderer;
+use super::{min_usize, Mountable, Render, RenderHtml};
+use crate::{
+    hydration::{Cursor, PositionState},
+    prelude::Renderer,
+    ssr::StreamBuilder,
+};
 use either_of::*;
 
+/// Writes a comment marking the start of a branch, if the `mark_branches` feature is enabled.
+///
+/// Markers are only written when escaping, i.e., when the output is HTML rather than text.
+#[inline(always)]
+#[allow(unused_variables, clippy::ptr_arg)]
+fn open_branch(buf: &mut String, branch: &str, escape: bool) {
+    #[cfg(feature = "mark_branches")]
+    if escape {
+        buf.push_str("<!--bo-");
+        buf.push_str(branch);
+        buf.push_str("-->");
+    }
+}
+
+/// Writes a comment marking the end of a branch, if the `mark_branches` feature is enabled.
+#[inline(always)]
+#[allow(unused_variables, clippy::ptr_arg)]
+fn close_branch(buf: &mut String, branch: &str, escape: bool) {
+    #[cfg(feature = "mark_branches")]
+    if escape {
+        buf.push_str("<!--bc-");
+        buf.push_str(branch);
+        buf.push_str("-->");
+    }
+}
+
 impl<A, B, R> Render<R> for Either<A, B>
 where
     A: Render<R>,
@@ -41,6 +71,83 @@ where
     }
 }
 
+impl<A, B, R> RenderHtml<R> for Either<A, B>
+where
+    A: RenderHtml<R>,
+    B: RenderHtml<R>,
+    R: Renderer,
+{
+    type AsyncOutput = Either<A::AsyncOutput, B::AsyncOutput>;
+
+    const MIN_LENGTH: usize = min_usize(&[A::MIN_LENGTH, B::MIN_LENGTH]);
+
+    fn dry_resolve(&mut self) {
+        match self {
+            Either::Left(left) => left.dry_resolve(),
+            Either::Right(right) => right.dry_resolve(),
+        }
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        match self {
+            Either::Left(left) => Either::Left(left.resolve().await),
+            Either::Right(right) => Either::Right(right.resolve().await),
+        }
+    }
+
+    fn html_len(&self) -> usize {
+        match self {
+            Either::Left(left) => left.html_len(),
+            Either::Right(right) => right.html_len(),
+        }
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        match self {
+            Either::Left(left) => {
+                open_branch(buf, "left", escape);
+                left.to_html_with_buf(buf, escape);
+                close_branch(buf, "left", escape);
+            }
+            Either::Right(right) => {
+                open_branch(buf, "right", escape);
+                right.to_html_with_buf(buf, escape);
+                close_branch(buf, "right", escape);
+            }
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        match self {
+            Either::Left(left) => {
+                buf.with_buf(|buf| open_branch(buf, "left", escape));
+                left.to_html_async_with_buf(buf, escape);
+                buf.with_buf(|buf| close_branch(buf, "left", escape));
+            }
+            Either::Right(right) => {
+                buf.with_buf(|buf| open_branch(buf, "right", escape));
+                right.to_html_async_with_buf(buf, escape);
+                buf.with_buf(|buf| close_branch(buf, "right", escape));
+            }
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        match self {
+            Either::Left(left) => {
+                Either::Left(left.hydrate::<FROM_SERVER>(cursor, position))
+            }
+            Either::Right(right) => {
+                Either::Right(right.hydrate::<FROM_SERVER>(cursor, position))
+            }
+        }
+    }
+}
+
 impl<A, B, R> Mountable<R> for Either<A, B>
 where
     A: Mountable<R>,
@@ -139,6 +246,101 @@ where
     }
 }
 
+impl<A, B, R> RenderHtml<R> for EitherKeepAlive<A, B>
+where
+    A: RenderHtml<R>,
+    B: RenderHtml<R>,
+    R: Renderer,
+{
+    type AsyncOutput = EitherKeepAlive<A::AsyncOutput, B::AsyncOutput>;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {
+        if let Some(inner) = &mut self.a {
+            inner.dry_resolve();
+        }
+        if let Some(inner) = &mut self.b {
+            inner.dry_resolve();
+        }
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        let EitherKeepAlive { a, b, show_b } = self;
+        let (a, b) = futures::join!(
+            async move {
+                match a {
+                    Some(a) => Some(a.resolve().await),
+                    None => None,
+                }
+            },
+            async move {
+                match b {
+                    Some(b) => Some(b.resolve().await),
+                    None => None,
+                }
+            }
+        );
+        EitherKeepAlive { a, b, show_b }
+    }
+
+    fn html_len(&self) -> usize {
+        if self.show_b {
+            self.b.as_ref().map(|b| b.html_len()).unwrap_or(0)
+        } else {
+            self.a.as_ref().map(|a| a.html_len()).unwrap_or(0)
+        }
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        if self.show_b {
+            self.b
+                .expect("showing B, but B was not present")
+                .to_html_with_buf(buf, escape);
+        } else {
+            self.a
+                .expect("showing A, but A was not present")
+                .to_html_with_buf(buf, escape);
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        if self.show_b {
+            self.b
+                .expect("showing B, but B was not present")
+                .to_html_async_with_buf(buf, escape);
+        } else {
+            self.a
+                .expect("showing A, but A was not present")
+                .to_html_async_with_buf(buf, escape);
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let showing_b = self.show_b;
+        // only the branch that is shown exists in the tree, so the other is built
+        let a = self.a.map(|a| {
+            if showing_b {
+                a.build()
+            } else {
+                a.hydrate::<FROM_SERVER>(cursor, position)
+            }
+        });
+        let b = self.b.map(|b| {
+            if showing_b {
+                b.hydrate::<FROM_SERVER>(cursor, position)
+            } else {
+                b.build()
+            }
+        });
+        EitherKeepAliveState { a, b, showing_b }
+    }
+}
+
 impl<A, B, R> Mountable<R> for EitherKeepAliveState<A, B>
 where
     A: Mountable<R>,
@@ -227,6 +429,72 @@ macro_rules! tuples {
                 }
             }
 
+            impl<$($ty,)* Rndr> RenderHtml<Rndr> for [<EitherOf $num>]<$($ty,)*>
+            where
+                $($ty: RenderHtml<Rndr>,)*
+                Rndr: Renderer
+
+            {
+                type AsyncOutput = [<EitherOf $num>]<$($ty::AsyncOutput,)*>;
+
+                const MIN_LENGTH: usize = min_usize(&[$($ty::MIN_LENGTH,)*]);
+
+                fn dry_resolve(&mut self) {
+                    match self {
+                        $([<EitherOf $num>]::$ty(this) => this.dry_resolve(),)*
+                    }
+                }
+
+                async fn resolve(self) -> Self::AsyncOutput {
+                    match self {
+                        $([<EitherOf $num>]::$ty(this) => [<EitherOf $num>]::$ty(this.resolve().await),)*
+                    }
+                }
+
+                fn html_len(&self) -> usize {
+                    match self {
+                        $([<EitherOf $num>]::$ty(this) => this.html_len(),)*
+                    }
+                }
+
+                fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+                    match self {
+                        $([<EitherOf $num>]::$ty(this) => {
+                            open_branch(buf, stringify!($ty), escape);
+                            this.to_html_with_buf(buf, escape);
+                            close_branch(buf, stringify!($ty), escape);
+                        })*
+                    }
+                }
+
+                fn to_html_async_with_buf(
+                    self,
+                    buf: &mut StreamBuilder,
+                    escape: bool,
+                ) {
+                    match self {
+                        $([<EitherOf $num>]::$ty(this) => {
+                            buf.with_buf(|buf| open_branch(buf, stringify!($ty), escape));
+                            this.to_html_async_with_buf(buf, escape);
+                            buf.with_buf(|buf| close_branch(buf, stringify!($ty), escape));
+                        })*
+                    }
+                }
+
+                fn hydrate<const FROM_SERVER: bool>(
+                    self,
+                    cursor: &Cursor<Rndr>,
+                    position: &PositionState,
+                ) -> Self::State {
+                    let state = match self {
+                        $([<EitherOf $num>]::$ty(this) => {
+                            [<EitherOf $num>]::$ty(this.hydrate::<FROM_SERVER>(cursor, position))
+                        })*
+                    };
+                    Self::State { state }
+                }
+            }
+
             impl<$($ty,)* Rndr> Render<Rndr> for [<EitherOf $num>]<$($ty,)*>
             where
                 $($ty: Render<Rndr>,)*
@@ -1,6 +1,8 @@
 use crate::{
+    hydration::{Cursor, PositionState},
     prelude::Renderer,
-    view::{iterators::OptionState, Mountable, Render},
+    ssr::StreamBuilder,
+    view::{iterators::OptionState, Mountable, Render, RenderHtml},
 };
 use either_of::Either;
 use std::sync::Arc;
@@ -62,6 +64,80 @@ where
     }
 }
 
+impl<T, E, R> RenderHtml<R> for Result<T, E>
+where
+    T: RenderHtml<R>,
+    E: Into<AnyError> + Send + 'static,
+    R: Renderer,
+{
+    type AsyncOutput = Result<T::AsyncOutput, E>;
+
+    const MIN_LENGTH: usize = T::MIN_LENGTH;
+
+    fn dry_resolve(&mut self) {
+        if let Ok(inner) = self.as_mut() {
+            inner.dry_resolve()
+        }
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        match self {
+            Ok(view) => Ok(view.resolve().await),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn html_len(&self) -> usize {
+        match self {
+            Ok(inner) => inner.html_len(),
+            Err(_) => <() as RenderHtml<R>>::MIN_LENGTH,
+        }
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        match self {
+            Ok(inner) => inner.to_html_with_buf(buf, escape),
+            Err(e) => {
+                RenderHtml::<R>::to_html_with_buf((), buf, escape);
+                throw_error::throw(e.into());
+            }
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        match self {
+            Ok(inner) => inner.to_html_async_with_buf(buf, escape),
+            Err(e) => {
+                RenderHtml::<R>::to_html_async_with_buf((), buf, escape);
+                throw_error::throw(e.into());
+            }
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let hook = throw_error::get_error_hook();
+        let (state, error) = match self {
+            Ok(view) => (
+                Either::Left(view.hydrate::<FROM_SERVER>(cursor, position)),
+                None,
+            ),
+            Err(e) => {
+                let state = RenderHtml::<R>::hydrate::<FROM_SERVER>(
+                    (),
+                    cursor,
+                    position,
+                );
+                (Either::Right(state), Some(throw_error::throw(e.into())))
+            }
+        };
+        ResultState { state, error, hook }
+    }
+}
+
 /// View state for a `Result<_, _>` view.
 pub struct ResultState<T, R>
 where
@@ -1,5 +1,5 @@
 use super::{
-    any_view::{AnyView, IntoAny},
+    any_view::{AnyView, IntoAny, IntoAnyHtml},
     iterators::StaticVec,
 };
 use crate::prelude::Renderer;
@@ -45,7 +45,7 @@ where
     R: Renderer,
 {
     fn from(value: Fragment<R>) -> Self {
-        value.nodes.into_any()
+        value.nodes.into_any_html()
     }
 }
 
@@ -1,5 +1,9 @@
-use super::{Mountable, Render};
-use crate::prelude::Renderer;
+use super::{Mountable, Render, RenderHtml};
+use crate::{
+    hydration::{Cursor, PositionState},
+    prelude::Renderer,
+    ssr::StreamBuilder,
+};
 use either_of::Either;
 use itertools::Itertools;
 use std::marker::PhantomData;
@@ -32,6 +36,63 @@ where
     }
 }
 
+impl<T, R> RenderHtml<R> for Option<T>
+where
+    T: RenderHtml<R>,
+    R: Renderer,
+{
+    type AsyncOutput = Option<T::AsyncOutput>;
+
+    const MIN_LENGTH: usize =
+        super::min_usize(&[T::MIN_LENGTH, <() as RenderHtml<R>>::MIN_LENGTH]);
+
+    fn dry_resolve(&mut self) {
+        if let Some(inner) = self.as_mut() {
+            inner.dry_resolve();
+        }
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        match self {
+            None => None,
+            Some(value) => Some(value.resolve().await),
+        }
+    }
+
+    fn html_len(&self) -> usize {
+        match self {
+            Some(i) => i.html_len(),
+            None => <() as RenderHtml<R>>::MIN_LENGTH,
+        }
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        match self {
+            Some(value) => value.to_html_with_buf(buf, escape),
+            None => RenderHtml::<R>::to_html_with_buf((), buf, escape),
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        match self {
+            Some(value) => value.to_html_async_with_buf(buf, escape),
+            None => RenderHtml::<R>::to_html_async_with_buf((), buf, escape),
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        match self {
+            Some(value) => Either::Left(value),
+            None => Either::Right(()),
+        }
+        .hydrate::<FROM_SERVER>(cursor, position)
+    }
+}
+
 impl<T, R> Render<R> for Vec<T>
 where
     T: Render<R>,
@@ -88,6 +149,61 @@ where
     }
 }
 
+impl<T, R> RenderHtml<R> for Vec<T>
+where
+    T: RenderHtml<R>,
+    R: Renderer,
+{
+    type AsyncOutput = Vec<T::AsyncOutput>;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {
+        for inner in self.iter_mut() {
+            inner.dry_resolve();
+        }
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        futures::future::join_all(self.into_iter().map(T::resolve)).await
+    }
+
+    fn html_len(&self) -> usize {
+        self.iter().map(|n| n.html_len()).sum::<usize>() + 3
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        for child in self.into_iter() {
+            child.to_html_with_buf(buf, escape);
+        }
+        if escape {
+            buf.push_str("<!>");
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        for child in self.into_iter() {
+            child.to_html_async_with_buf(buf, escape);
+        }
+        if escape {
+            buf.push_sync("<!>");
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let states = self
+            .into_iter()
+            .map(|child| child.hydrate::<FROM_SERVER>(cursor, position))
+            .collect();
+        let marker = cursor.next_placeholder(position);
+        VecState { states, marker }
+    }
+}
+
 /// Retained view state for a `Vec<_>`.
 pub struct VecState<T, R>
 where
@@ -299,6 +415,64 @@ where
     }
 }
 
+impl<T, R> RenderHtml<R> for StaticVec<T>
+where
+    T: RenderHtml<R>,
+    R: Renderer,
+{
+    type AsyncOutput = StaticVec<T::AsyncOutput>;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {
+        for inner in self.0.iter_mut() {
+            inner.dry_resolve();
+        }
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        futures::future::join_all(self.0.into_iter().map(T::resolve))
+            .await
+            .into()
+    }
+
+    fn html_len(&self) -> usize {
+        self.0.iter().map(|n| n.html_len()).sum::<usize>() + 3
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        for child in self.0.into_iter() {
+            child.to_html_with_buf(buf, escape);
+        }
+        if escape {
+            buf.push_str("<!>");
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        for child in self.0.into_iter() {
+            child.to_html_async_with_buf(buf, escape);
+        }
+        if escape {
+            buf.push_sync("<!>");
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let states = self
+            .0
+            .into_iter()
+            .map(|child| child.hydrate::<FROM_SERVER>(cursor, position))
+            .collect();
+        let marker = cursor.next_placeholder(position);
+        StaticVecState { states, marker }
+    }
+}
+
 impl<T, R, const N: usize> Render<R> for [T; N]
 where
     T: Render<R>,
@@ -322,3 +496,54 @@ where
             .for_each(|(new, old)| T::rebuild(new, old));
     }
 }
+
+impl<T, R, const N: usize> RenderHtml<R> for [T; N]
+where
+    T: RenderHtml<R>,
+    R: Renderer,
+{
+    type AsyncOutput = [T::AsyncOutput; N];
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {
+        for inner in self.iter_mut() {
+            inner.dry_resolve();
+        }
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        futures::future::join_all(self.map(T::resolve))
+            .await
+            .try_into()
+            .unwrap_or_else(|_| unreachable!())
+    }
+
+    fn html_len(&self) -> usize {
+        self.iter().map(RenderHtml::html_len).sum::<usize>()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        for child in self.into_iter() {
+            child.to_html_with_buf(buf, escape);
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        for child in self.into_iter() {
+            child.to_html_async_with_buf(buf, escape);
+        }
+    }
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let states =
+            self.map(|child| child.hydrate::<FROM_SERVER>(cursor, position));
+        ArrayState {
+            states,
+            _phantom: PhantomData,
+        }
+    }
+}
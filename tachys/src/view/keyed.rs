@@ -1,6 +1,9 @@
-use super::{Mountable, Render};
-use crate::renderer::Renderer;
-use drain_filter_polyfill::VecExt as VecDrainFilterExt;
+use super::{Mountable, Render, RenderHtml};
+use crate::{
+    hydration::{Cursor, PositionState},
+    renderer::{CastFrom, Renderer},
+    ssr::StreamBuilder,
+};
 use indexmap::IndexSet;
 use rustc_hash::FxHasher;
 use std::hash::{BuildHasherDefault, Hash};
@@ -8,6 +11,8 @@ use std::hash::{BuildHasherDefault, Hash};
 type FxIndexSet<T> = IndexSet<T, BuildHasherDefault<FxHasher>>;
 
 /// Creates a keyed list of views.
+///
+/// Keys should be unique. If several items have the same key, only the first of them is rendered.
 pub fn keyed<T, I, K, KF, VF, VFS, V, R>(
     items: I,
     key_fn: KF,
@@ -26,6 +31,7 @@ where
         items: Some(items),
         key_fn,
         view_fn,
+        on_diff: None,
     }
 }
 
@@ -41,6 +47,43 @@ where
     items: Option<I>,
     key_fn: KF,
     view_fn: VF,
+    on_diff: Option<DiffHook>,
+}
+
+type DiffHook = Box<dyn Fn(KeyedDiffStats) + Send>;
+
+impl<T, I, K, KF, VF, VFS, V> Keyed<T, I, K, KF, VF, VFS, V>
+where
+    I: IntoIterator<Item = T>,
+    K: Eq + Hash + 'static,
+    KF: Fn(&T) -> K,
+    VF: Fn(usize, T) -> (VFS, V),
+    VFS: Fn(usize),
+{
+    /// Calls `hook` with the [`KeyedDiffStats`] for the rebuild that uses this list.
+    ///
+    /// This is not called when the list is first built or hydrated.
+    pub fn on_diff(
+        mut self,
+        hook: impl Fn(KeyedDiffStats) + Send + 'static,
+    ) -> Self {
+        self.on_diff = Some(Box::new(hook));
+        self
+    }
+}
+
+/// The changes that were made to a keyed list when it was rebuilt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyedDiffStats {
+    /// The number of items that were created and inserted.
+    pub inserted: usize,
+    /// The number of existing items that were moved to a new position.
+    ///
+    /// Items that keep their order relative to each other are never moved, so this is the
+    /// smallest number of moves that can produce the new order.
+    pub moved: usize,
+    /// The number of items that were removed.
+    pub removed: usize,
 }
 
 /// Retained view state for a keyed list.
@@ -54,7 +97,7 @@ where
     parent: Option<R::Element>,
     marker: R::Placeholder,
     hashed_items: IndexSet<K, BuildHasherDefault<FxHasher>>,
-    rendered_items: Vec<Option<(VFS, V::State)>>,
+    rendered_items: Vec<(VFS, V::State)>,
 }
 
 impl<T, I, K, KF, VF, VFS, V, R> Render<R> for Keyed<T, I, K, KF, VF, VFS, V>
@@ -71,16 +114,15 @@ where
     type State = KeyedState<K, VFS, V, R>;
 
     fn build(self) -> Self::State {
-        let items = self.items.into_iter().flatten();
-        let (capacity, _) = items.size_hint();
-        let mut hashed_items =
-            FxIndexSet::with_capacity_and_hasher(capacity, Default::default());
-        let mut rendered_items = Vec::with_capacity(capacity);
-        for (index, item) in items.enumerate() {
-            hashed_items.insert((self.key_fn)(&item));
-            let (set_index, view) = (self.view_fn)(index, item);
-            rendered_items.push(Some((set_index, view.build())));
-        }
+        let (hashed_items, items) = dedupe(self.items, &self.key_fn);
+        let rendered_items = items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let (set_index, view) = (self.view_fn)(index, item);
+                (set_index, view.build())
+            })
+            .collect();
         KeyedState {
             parent: None,
             marker: R::create_placeholder(),
@@ -94,34 +136,106 @@ where
             parent,
             marker,
             hashed_items,
-            ref mut rendered_items,
+            rendered_items,
         } = state;
-        let new_items = self.items.into_iter().flatten();
-        let (capacity, _) = new_items.size_hint();
-        let mut new_hashed_items =
-            FxIndexSet::with_capacity_and_hasher(capacity, Default::default());
-
-        let mut items = Vec::new();
-        for item in new_items {
-            new_hashed_items.insert((self.key_fn)(&item));
-            items.push(Some(item));
-        }
-
-        let cmds = diff(hashed_items, &new_hashed_items);
+        let (new_hashed_items, items) = dedupe(self.items, &self.key_fn);
 
-        apply_diff(
+        let stats = apply_diff(
             parent.as_ref(),
             marker,
-            cmds,
+            hashed_items,
+            &new_hashed_items,
             rendered_items,
             &self.view_fn,
             items,
         );
+        if let Some(on_diff) = &self.on_diff {
+            on_diff(stats);
+        }
 
         *hashed_items = new_hashed_items;
     }
 }
 
+impl<T, I, K, KF, VF, VFS, V, R> RenderHtml<R>
+    for Keyed<T, I, K, KF, VF, VFS, V>
+where
+    I: IntoIterator<Item = T> + Send + 'static,
+    K: Eq + Hash + 'static,
+    KF: Fn(&T) -> K + Send + 'static,
+    V: RenderHtml<R> + 'static,
+    VF: Fn(usize, T) -> (VFS, V) + Send + 'static,
+    VFS: Fn(usize) + 'static,
+    R: Renderer,
+    T: 'static,
+{
+    type AsyncOutput = Vec<V::AsyncOutput>;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {
+        // the item views are only created when the list is rendered, so there is nothing to
+        // resolve ahead of time
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        let (_, items) = dedupe(self.items, &self.key_fn);
+        let views = items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| (self.view_fn)(index, item).1.resolve());
+        futures::future::join_all(views).await
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        let (_, items) = dedupe(self.items, &self.key_fn);
+        for (index, item) in items.into_iter().enumerate() {
+            let (_, view) = (self.view_fn)(index, item);
+            view.to_html_with_buf(buf, escape);
+        }
+        if escape {
+            buf.push_str("<!>");
+        }
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        let (_, items) = dedupe(self.items, &self.key_fn);
+        for (index, item) in items.into_iter().enumerate() {
+            let (_, view) = (self.view_fn)(index, item);
+            view.to_html_async_with_buf(buf, escape);
+        }
+        if escape {
+            buf.push_sync("<!>");
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let (hashed_items, items) = dedupe(self.items, &self.key_fn);
+        let rendered_items = items
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let (set_index, view) = (self.view_fn)(index, item);
+                (set_index, view.hydrate::<FROM_SERVER>(cursor, position))
+            })
+            .collect();
+        let marker = cursor.next_placeholder(position);
+        // hydrated views are never mounted, so the parent is taken from the tree instead
+        let parent =
+            R::get_parent(marker.as_ref()).and_then(R::Element::cast_from);
+        KeyedState {
+            parent,
+            marker,
+            hashed_items,
+            rendered_items,
+        }
+    }
+}
+
 impl<K, VFS, V, R> Mountable<R> for KeyedState<K, VFS, V, R>
 where
     K: Eq + Hash + 'static,
@@ -131,14 +245,14 @@ where
 {
     fn mount(&mut self, parent: &R::Element, marker: Option<&R::Node>) {
         self.parent = Some(parent.clone());
-        for (_, item) in self.rendered_items.iter_mut().flatten() {
+        for (_, item) in &mut self.rendered_items {
             item.mount(parent, marker);
         }
         self.marker.mount(parent, marker);
     }
 
     fn unmount(&mut self) {
-        for (_, item) in self.rendered_items.iter_mut().flatten() {
+        for (_, item) in &mut self.rendered_items {
             item.unmount();
         }
         self.marker.unmount();
@@ -147,481 +261,357 @@ where
     fn insert_before_this(&self, child: &mut dyn Mountable<R>) -> bool {
         self.rendered_items
             .first()
-            .map(|item| {
-                if let Some((_, item)) = item {
-                    item.insert_before_this(child)
-                } else {
-                    false
-                }
-            })
+            .map(|(_, item)| item.insert_before_this(child))
             .unwrap_or_else(|| self.marker.insert_before_this(child))
     }
 }
 
-trait VecExt<T> {
-    fn get_next_closest_mounted_sibling(
-        &self,
-        start_at: usize,
-    ) -> Option<&Option<T>>;
-}
-
-impl<T> VecExt<T> for Vec<Option<T>> {
-    fn get_next_closest_mounted_sibling(
-        &self,
-        start_at: usize,
-    ) -> Option<&Option<T>> {
-        self[start_at..].iter().find(|s| s.is_some())
-    }
-}
-
-/// Calculates the operations needed to get from `from` to `to`.
-fn diff<K: Eq + Hash>(from: &FxIndexSet<K>, to: &FxIndexSet<K>) -> Diff {
-    if from.is_empty() && to.is_empty() {
-        return Diff::default();
-    } else if to.is_empty() {
-        return Diff {
-            clear: true,
-            ..Default::default()
-        };
-    } else if from.is_empty() {
-        return Diff {
-            added: to
-                .iter()
-                .enumerate()
-                .map(|(at, _)| DiffOpAdd {
-                    at,
-                    mode: DiffOpAddMode::Append,
-                })
-                .collect(),
-            ..Default::default()
-        };
-    }
-
-    let mut removed = vec![];
-    let mut moved = vec![];
-    let mut added = vec![];
-    let max_len = std::cmp::max(from.len(), to.len());
-
-    for index in 0..max_len {
-        let from_item = from.get_index(index);
-        let to_item = to.get_index(index);
-
-        // if they're the same, do nothing
-        if from_item != to_item {
-            // if it's only in old, not new, remove it
-            if from_item.is_some() && !to.contains(from_item.unwrap()) {
-                let op = DiffOpRemove { at: index };
-                removed.push(op);
-            }
-            // if it's only in new, not old, add it
-            if to_item.is_some() && !from.contains(to_item.unwrap()) {
-                let op = DiffOpAdd {
-                    at: index,
-                    mode: DiffOpAddMode::Normal,
-                };
-                added.push(op);
-            }
-            // if it's in both old and new, it can either
-            // 1) be moved (and need to move in the DOM)
-            // 2) be moved (but not need to move in the DOM)
-            //    * this would happen if, for example, 2 items
-            //      have been added before it, and it has moved by 2
-            if let Some(from_item) = from_item {
-                if let Some(to_item) = to.get_full(from_item) {
-                    let moves_forward_by = (to_item.0 as i32) - (index as i32);
-                    let move_in_dom = moves_forward_by
-                        != (added.len() as i32) - (removed.len() as i32);
-
-                    let op = DiffOpMove {
-                        from: index,
-                        len: 1,
-                        to: to_item.0,
-                        move_in_dom,
-                    };
-                    moved.push(op);
-                }
-            }
-        }
-    }
-
-    moved = group_adjacent_moves(moved);
-
-    Diff {
-        removed,
-        items_to_move: moved.iter().map(|m| m.len).sum(),
-        moved,
-        added,
-        clear: false,
-    }
-}
-
-/// Group adjacent items that are being moved as a group.
-/// For example from `[2, 3, 5, 6]` to `[1, 2, 3, 4, 5, 6]` should result
-/// in a move for `2,3` and `5,6` rather than 4 individual moves.
-fn group_adjacent_moves(moved: Vec<DiffOpMove>) -> Vec<DiffOpMove> {
-    let mut prev: Option<DiffOpMove> = None;
-    let mut new_moved = Vec::with_capacity(moved.len());
-    for m in moved {
-        match prev {
-            Some(mut p) => {
-                if (m.from == p.from + p.len) && (m.to == p.to + p.len) {
-                    p.len += 1;
-                    prev = Some(p);
-                } else {
-                    new_moved.push(prev.take().unwrap());
-                    prev = Some(m);
-                }
-            }
-            None => prev = Some(m),
-        }
-    }
-    if let Some(prev) = prev {
-        new_moved.push(prev)
-    }
-    new_moved
-}
-
-#[derive(Debug, Default, PartialEq, Eq)]
-struct Diff {
-    removed: Vec<DiffOpRemove>,
-    moved: Vec<DiffOpMove>,
-    items_to_move: usize,
-    added: Vec<DiffOpAdd>,
-    clear: bool,
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct DiffOpMove {
-    /// The index this range is starting relative to `from`.
-    from: usize,
-    /// The number of elements included in this range.
-    len: usize,
-    /// The starting index this range will be moved to relative to `to`.
-    to: usize,
-    /// Marks this move to be applied to the DOM, or just to the underlying
-    /// storage
-    move_in_dom: bool,
-}
-
-impl Default for DiffOpMove {
-    fn default() -> Self {
-        Self {
-            from: 0,
-            to: 0,
-            len: 1,
-            move_in_dom: true,
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-struct DiffOpAdd {
-    at: usize,
-    mode: DiffOpAddMode,
-}
-
-#[derive(Debug, PartialEq, Eq)]
-struct DiffOpRemove {
-    at: usize,
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
-enum DiffOpAddMode {
-    #[default]
-    Normal,
-    Append,
+/// Returns the keys of the items, and the first item with each key, in order.
+///
+/// Only these items are rendered, so that the rendered items line up with their keys.
+fn dedupe<T, I, K>(
+    items: Option<I>,
+    key_fn: impl Fn(&T) -> K,
+) -> (FxIndexSet<K>, Vec<T>)
+where
+    I: IntoIterator<Item = T>,
+    K: Eq + Hash,
+{
+    let items = items.into_iter().flatten();
+    let (capacity, _) = items.size_hint();
+    let mut keys =
+        FxIndexSet::with_capacity_and_hasher(capacity, Default::default());
+    let items = items
+        .filter(|item| keys.insert(key_fn(item)))
+        .collect::<Vec<_>>();
+    (keys, items)
 }
 
-fn apply_diff<T, VFS, V, R>(
+/// Updates `children` to match the keys in `to`, creating views for new keys from `items`.
+///
+/// Items whose keys appear in both lists are kept. The longest run of kept items that are
+/// already in order (the longest increasing subsequence of their old indices) stays where it
+/// is, and every other kept item is moved once, so the number of moves is as small as possible.
+fn apply_diff<K, T, VFS, V, R>(
     parent: Option<&R::Element>,
     marker: &R::Placeholder,
-    diff: Diff,
-    children: &mut Vec<Option<(VFS, V::State)>>,
+    from: &FxIndexSet<K>,
+    to: &FxIndexSet<K>,
+    children: &mut Vec<(VFS, V::State)>,
     view_fn: &dyn Fn(usize, T) -> (VFS, V),
-    mut items: Vec<Option<T>>,
-) where
+    items: Vec<T>,
+) -> KeyedDiffStats
+where
+    K: Eq + Hash,
     VFS: Fn(usize),
     V: Render<R>,
     R: Renderer,
 {
-    // The order of cmds needs to be:
-    // 1. Clear
-    // 2. Removals
-    // 3. Move out
-    // 4. Resize
-    // 5. Move in
-    // 6. Additions
-    // 7. Removes holes
-    if diff.clear {
-        for (_, mut child) in children.drain(0..).flatten() {
-            child.unmount();
-        }
-
-        if diff.added.is_empty() {
-            return;
+    let mut stats = KeyedDiffStats::default();
+    let mut old_children = children.drain(..).map(Some).collect::<Vec<_>>();
+
+    for (key, child) in from.iter().zip(&mut old_children) {
+        if !to.contains(key) {
+            if let Some((_, mut child)) = child.take() {
+                child.unmount();
+                stats.removed += 1;
+            }
         }
     }
 
-    for DiffOpRemove { at } in &diff.removed {
-        let (_, mut item_to_remove) = children[*at].take().unwrap();
-
-        item_to_remove.unmount();
-    }
-
-    let (move_cmds, add_cmds) = unpack_moves(&diff);
-
-    let mut moved_children = move_cmds
+    let sources = to
         .iter()
-        .map(|move_| children[move_.from].take())
+        .map(|key| from.get_index_of(key))
         .collect::<Vec<_>>();
-
-    children.resize_with(children.len() + diff.added.len(), || None);
-
-    for (i, DiffOpMove { to, .. }) in move_cmds
-        .iter()
-        .enumerate()
-        .filter(|(_, move_)| !move_.move_in_dom)
-    {
-        children[*to] = moved_children[i]
-            .take()
-            .inspect(|(set_index, _)| set_index(*to));
-    }
-
-    for (i, DiffOpMove { to, .. }) in move_cmds
-        .into_iter()
-        .enumerate()
-        .filter(|(_, move_)| move_.move_in_dom)
-    {
-        let (set_index, mut each_item) = moved_children[i].take().unwrap();
-
-        if let Some(parent) = parent {
-            if let Some(Some((_, state))) =
-                children.get_next_closest_mounted_sibling(to)
-            {
-                state.insert_before_this_or_marker(
-                    parent,
-                    &mut each_item,
-                    Some(marker.as_ref()),
-                )
-            } else {
-                each_item.try_mount(parent, Some(marker.as_ref()));
-            }
-        }
-
-        set_index(to);
-        children[to] = Some((set_index, each_item));
-    }
-
-    for DiffOpAdd { at, mode } in add_cmds {
-        let item = items[at].take().unwrap();
-        let (set_index, item) = view_fn(at, item);
-        let mut item = item.build();
-
-        if let Some(parent) = parent {
-            match mode {
-                DiffOpAddMode::Normal => {
-                    if let Some(Some((_, state))) =
-                        children.get_next_closest_mounted_sibling(at)
-                    {
-                        state.insert_before_this_or_marker(
-                            parent,
-                            &mut item,
-                            Some(marker.as_ref()),
-                        )
-                    } else {
-                        item.try_mount(parent, Some(marker.as_ref()));
-                    }
+    let stable = longest_increasing_subsequence(&sources);
+
+    // walk backwards, so that the sibling each item is mounted before is already in place
+    let mut new_children = Vec::with_capacity(items.len());
+    for (index, item) in items.into_iter().enumerate().rev() {
+        let (set_index, mut child, needs_mount) = match sources[index] {
+            Some(old_index) => {
+                let (set_index, child) = old_children[old_index]
+                    .take()
+                    .expect("each key should only be used once");
+                if old_index != index {
+                    set_index(index);
                 }
-                DiffOpAddMode::Append => {
-                    item.try_mount(parent, Some(marker.as_ref()));
+                let needs_mount = !stable[index];
+                if needs_mount {
+                    stats.moved += 1;
                 }
+                (set_index, child, needs_mount)
+            }
+            None => {
+                let (set_index, view) = view_fn(index, item);
+                stats.inserted += 1;
+                (set_index, view.build(), true)
+            }
+        };
+
+        if needs_mount {
+            if let Some(parent) = parent {
+                mount_before_next::<V, R>(
+                    parent,
+                    marker,
+                    &new_children,
+                    &mut child,
+                );
             }
         }
 
-        children[at] = Some((set_index, item));
+        new_children.push((set_index, child));
     }
 
-    #[allow(unstable_name_collisions)]
-    children.drain_filter(|c| c.is_none());
+    new_children.reverse();
+    *children = new_children;
+    stats
 }
 
-fn unpack_moves(diff: &Diff) -> (Vec<DiffOpMove>, Vec<DiffOpAdd>) {
-    let mut moves = Vec::with_capacity(diff.items_to_move);
-    let mut adds = Vec::with_capacity(diff.added.len());
-
-    let mut removes_iter = diff.removed.iter();
-    let mut adds_iter = diff.added.iter();
-    let mut moves_iter = diff.moved.iter();
-
-    let mut removes_next = removes_iter.next();
-    let mut adds_next = adds_iter.next();
-    let mut moves_next = moves_iter.next().copied();
-
-    for i in 0..diff.items_to_move + diff.added.len() + diff.removed.len() {
-        if let Some(DiffOpRemove { at, .. }) = removes_next {
-            if i == *at {
-                removes_next = removes_iter.next();
+/// Mounts `child` before the closest following item that exists in the UI, or before the
+/// marker if there are none.
+///
+/// `next` holds the following items in reverse order.
+fn mount_before_next<V, R>(
+    parent: &R::Element,
+    marker: &R::Placeholder,
+    next: &[(impl Fn(usize), V::State)],
+    child: &mut V::State,
+) where
+    V: Render<R>,
+    R: Renderer,
+{
+    let mounted = next
+        .iter()
+        .rev()
+        .any(|(_, sibling)| sibling.insert_before_this(child));
+    if !mounted {
+        child.mount(parent, Some(marker.as_ref()));
+    }
+}
 
-                continue;
-            }
+/// Marks the entries of `sources` that belong to the longest subsequence whose values are
+/// strictly increasing. Empty entries are never part of it.
+fn longest_increasing_subsequence(sources: &[Option<usize>]) -> Vec<bool> {
+    // `tails[n]` is the position of the smallest value that ends an increasing subsequence of
+    // length `n + 1`, and `prev` links each position to the one before it in its subsequence
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev = vec![None; sources.len()];
+    for (index, value) in sources.iter().enumerate() {
+        let Some(value) = value else {
+            continue;
+        };
+        let len = tails.partition_point(|&tail| sources[tail] < Some(*value));
+        if len > 0 {
+            prev[index] = Some(tails[len - 1]);
         }
-
-        match (adds_next, &mut moves_next) {
-            (Some(add), Some(move_)) => {
-                if add.at == i {
-                    adds.push(*add);
-
-                    adds_next = adds_iter.next();
-                } else {
-                    let mut single_move = *move_;
-                    single_move.len = 1;
-
-                    moves.push(single_move);
-
-                    move_.len -= 1;
-                    move_.from += 1;
-                    move_.to += 1;
-
-                    if move_.len == 0 {
-                        moves_next = moves_iter.next().copied();
-                    }
-                }
-            }
-            (Some(add), None) => {
-                adds.push(*add);
-
-                adds_next = adds_iter.next();
-            }
-            (None, Some(move_)) => {
-                let mut single_move = *move_;
-                single_move.len = 1;
-
-                moves.push(single_move);
-
-                move_.len -= 1;
-                move_.from += 1;
-                move_.to += 1;
-
-                if move_.len == 0 {
-                    moves_next = moves_iter.next().copied();
-                }
-            }
-            (None, None) => break,
+        if len == tails.len() {
+            tails.push(index);
+        } else {
+            tails[len] = index;
         }
     }
 
-    (moves, adds)
+    let mut stable = vec![false; sources.len()];
+    let mut next = tails.last().copied();
+    while let Some(index) = next {
+        stable[index] = true;
+        next = prev[index];
+    }
+    stable
 }
-/*
+
 #[cfg(test)]
 mod tests {
+    use super::{longest_increasing_subsequence, KeyedDiffStats, KeyedState};
     use crate::{
-        html::element::{li, ul, HtmlElement, Li},
-        renderer::mock_dom::MockDom,
-        view::{keyed::keyed, Render},
+        renderer::mock_dom::{Document, Element, MockDom},
+        view::{keyed::keyed, Mountable, Render, RenderHtml},
     };
+    use std::sync::{Arc, Mutex};
+
+    type ListState = KeyedState<usize, fn(usize), String, MockDom>;
+
+    fn key(k: &usize) -> usize {
+        *k
+    }
+
+    fn item(_index: usize, key: usize) -> (fn(usize), String) {
+        (|_| {}, key.to_string())
+    }
 
-    fn item(key: usize) -> HtmlElement<Li, (), String, MockDom> {
-        li((), key.to_string())
+    fn build<const N: usize>(items: [usize; N]) -> (Element, ListState) {
+        let el = Document::create_element("ul");
+        let mut state =
+            keyed::<_, _, _, _, _, _, _, MockDom>(items, key, item).build();
+        state.mount(&el, None);
+        (el, state)
+    }
+
+    fn rebuild<const N: usize>(
+        items: [usize; N],
+        state: &mut ListState,
+    ) -> KeyedDiffStats {
+        let stats = Arc::new(Mutex::new(None));
+        keyed::<_, _, _, _, _, _, _, MockDom>(items, key, item)
+            .on_diff({
+                let stats = Arc::clone(&stats);
+                move |diff| *stats.lock().unwrap() = Some(diff)
+            })
+            .rebuild(state);
+        let stats = stats.lock().unwrap().take();
+        stats.expect("the diff hook should be called on rebuild")
+    }
+
+    fn diff(inserted: usize, moved: usize, removed: usize) -> KeyedDiffStats {
+        KeyedDiffStats {
+            inserted,
+            moved,
+            removed,
+        }
     }
 
     #[test]
     fn keyed_creates_list() {
-        let el = ul((), keyed(1..=3, |k| *k, item));
-        let el_state = el.build();
-        assert_eq!(
-            el_state.el.to_debug_html(),
-            "<ul><li>1</li><li>2</li><li>3</li></ul>"
-        );
+        let (el, _state) = build([1, 2, 3]);
+        assert_eq!(el.to_debug_html(), "<ul>123<!></ul>");
     }
 
     #[test]
     fn adding_items_updates_list() {
-        let el = ul((), keyed(1..=3, |k| *k, item));
-        let mut el_state = el.build();
-        let el = ul((), keyed(1..=5, |k| *k, item));
-        el.rebuild(&mut el_state);
-        assert_eq!(
-            el_state.el.to_debug_html(),
-            "<ul><li>1</li><li>2</li><li>3</li><li>4</li><li>5</li></ul>"
-        );
+        let (el, mut state) = build([1, 2, 3]);
+        rebuild([1, 2, 3, 4, 5], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>12345<!></ul>");
     }
 
     #[test]
     fn removing_items_updates_list() {
-        let el = ul((), keyed(1..=3, |k| *k, item));
-        let mut el_state = el.build();
-        let el = ul((), keyed(1..=2, |k| *k, item));
-        el.rebuild(&mut el_state);
-        assert_eq!(
-            el_state.el.to_debug_html(),
-            "<ul><li>1</li><li>2</li></ul>"
-        );
+        let (el, mut state) = build([1, 2, 3]);
+        rebuild([1, 2], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>12<!></ul>");
     }
 
     #[test]
     fn swapping_items_updates_list() {
-        let el = ul((), keyed([1, 2, 3, 4, 5], |k| *k, item));
-        let mut el_state = el.build();
-        let el = ul((), keyed([1, 4, 3, 2, 5], |k| *k, item));
-        el.rebuild(&mut el_state);
-        assert_eq!(
-            el_state.el.to_debug_html(),
-            "<ul><li>1</li><li>4</li><li>3</li><li>2</li><li>5</li></ul>"
-        );
+        let (el, mut state) = build([1, 2, 3, 4, 5]);
+        let stats = rebuild([1, 4, 3, 2, 5], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>14325<!></ul>");
+        assert_eq!(stats, diff(0, 2, 0));
     }
 
     #[test]
     fn swapping_and_removing_orders_correctly() {
-        let el = ul((), keyed([1, 2, 3, 4, 5], |k| *k, item));
-        let mut el_state = el.build();
-        let el = ul((), keyed([1, 4, 3, 5], |k| *k, item));
-        el.rebuild(&mut el_state);
-        assert_eq!(
-            el_state.el.to_debug_html(),
-            "<ul><li>1</li><li>4</li><li>3</li><li>5</li></ul>"
-        );
+        let (el, mut state) = build([1, 2, 3, 4, 5]);
+        rebuild([1, 4, 3, 5], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>1435<!></ul>");
     }
 
     #[test]
     fn arbitrarily_hard_adjustment() {
-        let el = ul((), keyed([1, 2, 3, 4, 5], |k| *k, item));
-        let mut el_state = el.build();
-        let el = ul((), keyed([2, 4, 3], |k| *k, item));
-        el.rebuild(&mut el_state);
-        assert_eq!(
-            el_state.el.to_debug_html(),
-            "<ul><li>2</li><li>4</li><li>3</li></ul>"
-        );
+        let (el, mut state) = build([1, 2, 3, 4, 5]);
+        rebuild([2, 4, 3], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>243<!></ul>");
     }
 
     #[test]
     fn a_series_of_moves() {
-        let el = ul((), keyed([1, 2, 3, 4, 5], |k| *k, item));
-        let mut el_state = el.build();
-        let el = ul((), keyed([2, 4, 3], |k| *k, item));
-        el.rebuild(&mut el_state);
-        let el = ul((), keyed([1, 7, 5, 11, 13, 17], |k| *k, item));
-        el.rebuild(&mut el_state);
-        let el = ul((), keyed([2, 6, 8, 7, 13], |k| *k, item));
-        el.rebuild(&mut el_state);
-        let el = ul((), keyed([13, 4, 5, 3], |k| *k, item));
-        el.rebuild(&mut el_state);
-        let el = ul((), keyed([1, 2, 3, 4], |k| *k, item));
-        el.rebuild(&mut el_state);
-        assert_eq!(
-            el_state.el.to_debug_html(),
-            "<ul><li>1</li><li>2</li><li>3</li><li>4</li></ul>"
-        );
+        let (el, mut state) = build([1, 2, 3, 4, 5]);
+        rebuild([2, 4, 3], &mut state);
+        rebuild([1, 7, 5, 11, 13, 17], &mut state);
+        rebuild([2, 6, 8, 7, 13], &mut state);
+        rebuild([13, 4, 5, 3], &mut state);
+        rebuild([1, 2, 3, 4], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>1234<!></ul>");
     }
 
     #[test]
     fn clearing_works() {
-        let el = ul((), keyed([1, 2, 3, 4, 5], |k| *k, item));
-        let mut el_state = el.build();
-        let el = ul((), keyed([], |k| *k, item));
-        el.rebuild(&mut el_state);
-        assert_eq!(el_state.el.to_debug_html(), "<ul></ul>");
+        let (el, mut state) = build([1, 2, 3, 4, 5]);
+        rebuild([], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul><!></ul>");
+    }
+
+    #[test]
+    fn moving_one_item_moves_only_that_item() {
+        let (el, mut state) = build([1, 2, 3, 4, 5]);
+        let nodes = el.children();
+        let stats = rebuild([5, 1, 2, 3, 4], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>51234<!></ul>");
+        assert_eq!(stats, diff(0, 1, 0));
+        // the existing nodes are reused, not recreated
+        assert_eq!(el.children()[0], nodes[4]);
+        assert_eq!(el.children()[1..5], nodes[0..4]);
+    }
+
+    #[test]
+    fn duplicate_keys_are_rendered_once() {
+        let (el, mut state) = build([1, 2]);
+        let stats = rebuild([1, 1, 2], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>12<!></ul>");
+        assert_eq!(stats, diff(0, 0, 0));
+        rebuild([3, 2, 3, 1], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>321<!></ul>");
+
+        let (el, mut state) = build([2, 2, 1]);
+        assert_eq!(el.to_debug_html(), "<ul>21<!></ul>");
+        rebuild([1, 2], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>12<!></ul>");
+    }
+
+    #[test]
+    fn duplicate_keys_are_rendered_once_on_the_server() {
+        let list =
+            || keyed::<_, _, _, _, _, _, _, MockDom>([2, 2, 1], key, item);
+        assert_eq!(RenderHtml::<MockDom>::to_html(list()), "21<!>");
+
+        // the client hydrates the same items that the server rendered
+        let (el, _) = build([2, 1]);
+        let nodes = el.children();
+        let mut state =
+            RenderHtml::<MockDom>::hydrate_from::<true>(list(), &el);
+        assert_eq!(el.children(), nodes);
+        assert_eq!(state.rendered_items.len(), 2);
+        rebuild([1, 2], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>12<!></ul>");
+    }
+
+    #[test]
+    fn reversing_keeps_one_item_in_place() {
+        let (el, mut state) = build([1, 2, 3, 4, 5]);
+        let stats = rebuild([5, 4, 3, 2, 1], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>54321<!></ul>");
+        assert_eq!(stats, diff(0, 4, 0));
+    }
+
+    #[test]
+    fn inserts_and_removes_do_not_count_as_moves() {
+        let (el, mut state) = build([1, 2, 3, 4, 5]);
+        let stats = rebuild([0, 1, 3, 6, 4, 5, 7], &mut state);
+        assert_eq!(el.to_debug_html(), "<ul>0136457<!></ul>");
+        assert_eq!(stats, diff(3, 0, 1));
+    }
+
+    #[test]
+    fn unmounted_lists_are_reordered() {
+        let el = Document::create_element("ul");
+        let mut state =
+            keyed::<_, _, _, _, _, _, _, MockDom>([1, 2, 3], key, item).build();
+        rebuild([3, 1, 2, 4], &mut state);
+        state.mount(&el, None);
+        assert_eq!(el.to_debug_html(), "<ul>3124<!></ul>");
+    }
+
+    #[test]
+    fn finds_longest_increasing_subsequence() {
+        let stable = longest_increasing_subsequence(&[
+            Some(3),
+            None,
+            Some(0),
+            Some(4),
+            Some(1),
+            Some(2),
+        ]);
+        assert_eq!(stable, [false, false, true, false, true, true]);
+        assert!(longest_increasing_subsequence(&[]).is_empty());
     }
 }
-*/
@@ -1,5 +1,9 @@
-use crate::renderer::Renderer;
-use std::{cell::RefCell, rc::Rc};
+use crate::{
+    hydration::{Cursor, Position, PositionState},
+    renderer::Renderer,
+    ssr::StreamBuilder,
+};
+use std::{cell::RefCell, future::Future, rc::Rc};
 
 /// A typed-erased view type.
 pub mod any_view;
@@ -19,6 +23,9 @@ mod primitives;
 pub mod static_types;
 /// View implementation for string types.
 pub mod strings;
+/// Enter and leave hooks, which allow views to animate as they are mounted and unmounted.
+#[cfg(feature = "reactive_graph")]
+pub mod transition;
 /// View implementations for tuples.
 pub mod tuples;
 
@@ -40,6 +47,118 @@ pub trait Render<R: Renderer>: Sized + 'static {
     fn rebuild(self, state: &mut Self::State);
 }
 
+/// The `RenderHtml` trait allows rendering a view as text, either synchronously to a string or as
+/// a stream that waits for any asynchronous sections of the view.
+///
+/// Like [`Render`], it is generic over the renderer. The output does not depend on the renderer,
+/// so the same view tree can be built for a live interface or serialized, for example to export
+/// HTML or to snapshot a view in a test.
+pub trait RenderHtml<R: Renderer>: Render<R> + Send {
+    /// The type of the view after waiting for all asynchronous data to load.
+    type AsyncOutput: RenderHtml<R>;
+
+    /// The minimum length of text created by this view.
+    const MIN_LENGTH: usize;
+
+    /// “Runs” the view without other side effects. For primitive types, this is a no-op. For
+    /// reactive types, this can be used to gather data about reactivity or about asynchronous data
+    /// that needs to be loaded.
+    fn dry_resolve(&mut self);
+
+    /// Waits for any asynchronous sections of the view to load and returns the output.
+    fn resolve(self) -> impl Future<Output = Self::AsyncOutput> + Send;
+
+    /// An estimated length for this view, when rendered as text.
+    fn html_len(&self) -> usize {
+        Self::MIN_LENGTH
+    }
+
+    /// Renders a view to an HTML string.
+    ///
+    /// Any asynchronous sections that are not ready yet are rendered as placeholders.
+    fn to_html(self) -> String
+    where
+        Self: Sized,
+    {
+        let mut buf = String::with_capacity(self.html_len());
+        self.to_html_with_buf(&mut buf, true);
+        buf
+    }
+
+    /// Renders a view to its raw text content, without escaping and without placeholder markers.
+    fn to_text(self) -> String
+    where
+        Self: Sized,
+    {
+        let mut buf = String::with_capacity(self.html_len());
+        self.to_html_with_buf(&mut buf, false);
+        buf
+    }
+
+    /// Renders a view to an in-order stream of HTML, which waits for each asynchronous section
+    /// of the view before continuing.
+    fn to_html_stream_in_order(self) -> StreamBuilder
+    where
+        Self: Sized,
+    {
+        let mut builder = StreamBuilder::with_capacity(self.html_len());
+        self.to_html_async_with_buf(&mut builder, true);
+        builder.finish()
+    }
+
+    /// Renders a view into a buffer of text.
+    ///
+    /// If `escape` is `true`, text is escaped as HTML and placeholders are written as `<!>`.
+    /// Otherwise, only the raw text content of the view is written.
+    fn to_html_with_buf(self, buf: &mut String, escape: bool);
+
+    /// Renders a view into a stream of text, waiting for any asynchronous sections.
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool)
+    where
+        Self: Sized,
+    {
+        buf.with_buf(|buf| self.to_html_with_buf(buf, escape));
+    }
+
+    /// Makes an existing tree of nodes interactive, by advancing the cursor through it and
+    /// claiming the nodes this view would otherwise have created.
+    ///
+    /// If `FROM_SERVER` is `true`, the existing nodes were rendered from this same view (for
+    /// example, a tree that was persisted and restored), so their contents are left as they are.
+    /// Otherwise, text content is updated to match the view.
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State;
+
+    /// Hydrates using [`RenderHtml::hydrate`], beginning at the first child of the given element.
+    fn hydrate_from<const FROM_SERVER: bool>(
+        self,
+        el: &R::Element,
+    ) -> Self::State
+    where
+        Self: Sized,
+    {
+        let cursor = Cursor::new(el.clone());
+        let position = PositionState::new(Position::FirstChild);
+        self.hydrate::<FROM_SERVER>(&cursor, &position)
+    }
+}
+
+/// Returns the smallest of the given values, for use in `const` contexts.
+pub(crate) const fn min_usize(vals: &[usize]) -> usize {
+    let mut min = usize::MAX;
+    let mut i = 0;
+    while i < vals.len() {
+        if vals[i] < min {
+            min = vals[i];
+        }
+        i += 1;
+    }
+    min
+}
+
 /// Allows a type to be mounted to the DOM.
 pub trait Mountable<R: Renderer> {
     /// Detaches the view from the DOM.
@@ -1,5 +1,8 @@
-use super::{Mountable, Render};
-use crate::renderer::Renderer;
+use super::{strings::push_text, Mountable, Render, RenderHtml};
+use crate::{
+    hydration::{Cursor, PositionState},
+    renderer::Renderer,
+};
 use std::{
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
     num::{
@@ -54,6 +57,34 @@ macro_rules! render_primitive {
 					}
 				}
 			}
+
+			impl<R: Renderer> RenderHtml<R> for $child_type {
+				type AsyncOutput = Self;
+
+				const MIN_LENGTH: usize = 0;
+
+				fn dry_resolve(&mut self) {}
+
+				async fn resolve(self) -> Self::AsyncOutput {
+					self
+				}
+
+				fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+					push_text(buf, &self.to_string(), escape);
+				}
+
+				fn hydrate<const FROM_SERVER: bool>(
+					self,
+					cursor: &Cursor<R>,
+					position: &PositionState,
+				) -> Self::State {
+					let node = cursor.next_text(position);
+					if !FROM_SERVER {
+						R::set_text(&node, &self.to_string());
+					}
+					[<$child_type:camel State>](node, self)
+				}
+			}
 		}
     )*
   };
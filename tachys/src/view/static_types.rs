@@ -1,5 +1,8 @@
-use super::{Mountable, Render};
-use crate::prelude::Renderer;
+use super::{strings::push_text, Mountable, Render, RenderHtml};
+use crate::{
+    hydration::{Cursor, PositionState},
+    prelude::Renderer,
+};
 
 /// A static string that is known at compile time and can be optimized by including its type in the
 /// view tree.
@@ -34,3 +37,35 @@ where
     // This type is specified as static, so no rebuilding is done.
     fn rebuild(self, _state: &mut Self::State) {}
 }
+
+impl<const V: &'static str, R> RenderHtml<R> for Static<V>
+where
+    R::Text: Mountable<R>,
+    R: Renderer,
+{
+    type AsyncOutput = Self;
+
+    const MIN_LENGTH: usize = V.len();
+
+    fn dry_resolve(&mut self) {}
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        self
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        push_text(buf, V, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let node = cursor.next_text(position);
+        if !FROM_SERVER {
+            R::set_text(&node, V);
+        }
+        Some(node)
+    }
+}
@@ -1,7 +1,19 @@
-use super::{Mountable, Render};
-use crate::prelude::Renderer;
+use super::{Mountable, Render, RenderHtml};
+use crate::{
+    hydration::{Cursor, PositionState},
+    prelude::Renderer,
+};
 use std::{borrow::Cow, rc::Rc, sync::Arc};
 
+/// Writes text into the buffer, escaping it as HTML if `escape` is `true`.
+pub(crate) fn push_text(buf: &mut String, text: &str, escape: bool) {
+    if escape {
+        buf.push_str(&html_escape::encode_text(text));
+    } else {
+        buf.push_str(text);
+    }
+}
+
 /// Retained view state for `&str`.
 pub struct StrState<'a, R>
 where
@@ -31,6 +43,41 @@ where
     }
 }
 
+impl<R> RenderHtml<R> for &'static str
+where
+    R: Renderer,
+{
+    type AsyncOutput = Self;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {}
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        self
+    }
+
+    fn html_len(&self) -> usize {
+        self.len()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        push_text(buf, self, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let node = cursor.next_text(position);
+        if !FROM_SERVER {
+            R::set_text(&node, self);
+        }
+        StrState { node, str: self }
+    }
+}
+
 impl<R> Mountable<R> for StrState<'_, R>
 where
     R: Renderer,
@@ -77,6 +124,41 @@ where
     }
 }
 
+impl<R> RenderHtml<R> for String
+where
+    R: Renderer,
+{
+    type AsyncOutput = Self;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {}
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        self
+    }
+
+    fn html_len(&self) -> usize {
+        self.len()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        push_text(buf, &self, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let node = cursor.next_text(position);
+        if !FROM_SERVER {
+            R::set_text(&node, &self);
+        }
+        StringState { node, str: self }
+    }
+}
+
 impl<R> Mountable<R> for StringState<R>
 where
     R: Renderer,
@@ -169,6 +251,41 @@ where
     }
 }
 
+impl<R> RenderHtml<R> for Arc<str>
+where
+    R: Renderer,
+{
+    type AsyncOutput = Self;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {}
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        self
+    }
+
+    fn html_len(&self) -> usize {
+        self.len()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        push_text(buf, &self, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let node = cursor.next_text(position);
+        if !FROM_SERVER {
+            R::set_text(&node, &self);
+        }
+        ArcStrState { node, str: self }
+    }
+}
+
 impl<R> Mountable<R> for ArcStrState<R>
 where
     R: Renderer,
@@ -215,6 +332,41 @@ where
     }
 }
 
+impl<R> RenderHtml<R> for Cow<'static, str>
+where
+    R: Renderer,
+{
+    type AsyncOutput = Self;
+
+    const MIN_LENGTH: usize = 0;
+
+    fn dry_resolve(&mut self) {}
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        self
+    }
+
+    fn html_len(&self) -> usize {
+        self.len()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        push_text(buf, &self, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        let node = cursor.next_text(position);
+        if !FROM_SERVER {
+            R::set_text(&node, &self);
+        }
+        CowStrState { node, str: self }
+    }
+}
+
 impl<R> Mountable<R> for CowStrState<'_, R>
 where
     R: Renderer,
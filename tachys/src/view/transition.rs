@@ -0,0 +1,346 @@
+use super::{Mountable, Render, RenderHtml};
+use crate::{
+    hydration::{Cursor, PositionState},
+    renderer::Renderer,
+    ssr::StreamBuilder,
+};
+use any_spawner::Executor;
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+};
+
+type EnterHook = Arc<dyn Fn() + Send + Sync>;
+type LeaveHook =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = ()>>> + Send + Sync>;
+
+/// Wraps a view, so that hooks can run when it enters or leaves the UI.
+pub fn transition<V>(view: V) -> Transition<V> {
+    Transition {
+        view,
+        on_enter: None,
+        on_leave: None,
+    }
+}
+
+/// A view with hooks that run when it enters or leaves the UI.
+///
+/// When the view is unmounted, the leave hook is called, and its nodes are only removed once
+/// the future it returns has resolved. This works anywhere views are unmounted, including items
+/// removed from a [`keyed`](crate::view::keyed::keyed) list and the previous branch of an
+/// [`Either`](crate::view::either::Either), which stays in place while the new branch is
+/// inserted before it.
+pub struct Transition<V> {
+    view: V,
+    on_enter: Option<EnterHook>,
+    on_leave: Option<LeaveHook>,
+}
+
+impl<V> Debug for Transition<V>
+where
+    V: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transition")
+            .field("view", &self.view)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<V> Transition<V> {
+    /// Calls `hook` each time the view is mounted, after its nodes have been inserted.
+    ///
+    /// This is not called when the view is hydrated, because its nodes are already in place.
+    pub fn on_enter(mut self, hook: impl Fn() + Send + Sync + 'static) -> Self {
+        self.on_enter = Some(Arc::new(hook));
+        self
+    }
+
+    /// Calls `hook` each time the view is unmounted, and waits for the future it returns before
+    /// removing the view's nodes.
+    ///
+    /// If the view is mounted again before the future resolves, its nodes are left in place.
+    pub fn on_leave<Fut>(
+        mut self,
+        hook: impl Fn() -> Fut + Send + Sync + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.on_leave = Some(Arc::new(move || Box::pin(hook())));
+        self
+    }
+}
+
+/// Retained view state for a [`Transition`].
+pub struct TransitionState<S> {
+    // shared with a pending leave, which can outlive this state
+    inner: Rc<RefCell<S>>,
+    on_enter: Option<EnterHook>,
+    on_leave: Option<LeaveHook>,
+    // incremented on each mount, so that a pending leave knows it has been cancelled
+    mounts: Rc<Cell<usize>>,
+    mounted: bool,
+}
+
+impl<S> TransitionState<S> {
+    fn new(
+        inner: S,
+        on_enter: Option<EnterHook>,
+        on_leave: Option<LeaveHook>,
+        mounted: bool,
+    ) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(inner)),
+            on_enter,
+            on_leave,
+            mounts: Rc::new(Cell::new(0)),
+            mounted,
+        }
+    }
+}
+
+impl<V, R> Render<R> for Transition<V>
+where
+    V: Render<R>,
+    V::State: 'static,
+    R: Renderer,
+{
+    type State = TransitionState<V::State>;
+
+    fn build(self) -> Self::State {
+        TransitionState::new(
+            self.view.build(),
+            self.on_enter,
+            self.on_leave,
+            false,
+        )
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        self.view.rebuild(&mut state.inner.borrow_mut());
+        state.on_enter = self.on_enter;
+        state.on_leave = self.on_leave;
+    }
+}
+
+impl<V, R> RenderHtml<R> for Transition<V>
+where
+    V: RenderHtml<R>,
+    V::State: 'static,
+    R: Renderer,
+{
+    type AsyncOutput = Transition<V::AsyncOutput>;
+
+    const MIN_LENGTH: usize = V::MIN_LENGTH;
+
+    fn dry_resolve(&mut self) {
+        self.view.dry_resolve();
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        Transition {
+            view: self.view.resolve().await,
+            on_enter: self.on_enter,
+            on_leave: self.on_leave,
+        }
+    }
+
+    fn html_len(&self) -> usize {
+        self.view.html_len()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        self.view.to_html_with_buf(buf, escape);
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        self.view.to_html_async_with_buf(buf, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        TransitionState::new(
+            self.view.hydrate::<FROM_SERVER>(cursor, position),
+            self.on_enter,
+            self.on_leave,
+            true,
+        )
+    }
+}
+
+impl<S, R> Mountable<R> for TransitionState<S>
+where
+    S: Mountable<R> + 'static,
+    R: Renderer,
+{
+    fn unmount(&mut self) {
+        self.mounted = false;
+        let Some(on_leave) = &self.on_leave else {
+            self.inner.borrow_mut().unmount();
+            return;
+        };
+
+        let leave = on_leave();
+        let inner = Rc::clone(&self.inner);
+        let mounts = Rc::clone(&self.mounts);
+        let mounted_at = mounts.get();
+        Executor::spawn_local(async move {
+            leave.await;
+            if mounts.get() == mounted_at {
+                inner.borrow_mut().unmount();
+            }
+        });
+    }
+
+    fn mount(&mut self, parent: &R::Element, marker: Option<&R::Node>) {
+        self.mounts.set(self.mounts.get() + 1);
+        self.inner.borrow_mut().mount(parent, marker);
+        if !self.mounted {
+            self.mounted = true;
+            if let Some(on_enter) = &self.on_enter {
+                on_enter();
+            }
+        }
+    }
+
+    fn insert_before_this(&self, child: &mut dyn Mountable<R>) -> bool {
+        self.inner.borrow().insert_before_this(child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{transition, Transition};
+    use crate::{
+        renderer::mock_dom::{Document, MockDom},
+        view::{keyed::keyed, Mountable, Render},
+    };
+    use any_spawner::Executor;
+    use either_of::Either;
+    use futures::channel::oneshot;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+    use tokio::task;
+
+    /// Returns a leave hook that waits until the returned sender is used.
+    fn wait_for_leave() -> (
+        impl Fn() -> oneshot::Receiver<()> + Send + Sync,
+        oneshot::Sender<()>,
+    ) {
+        let (tx, rx) = oneshot::channel();
+        let rx = Mutex::new(Some(rx));
+        let hook = move || rx.lock().unwrap().take().expect("left twice");
+        (hook, tx)
+    }
+
+    async fn settle() {
+        for _ in 0..3 {
+            Executor::tick().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn either_keeps_the_old_branch_until_it_has_left() {
+        _ = Executor::init_tokio();
+        task::LocalSet::new()
+            .run_until(async {
+                static ENTERED: AtomicUsize = AtomicUsize::new(0);
+                type View =
+                    Either<Transition<&'static str>, Transition<&'static str>>;
+
+                let root = Document::create_element("div");
+                let (leave, done) = wait_for_leave();
+                let left: View =
+                    Either::Left(transition("a").on_leave(move || {
+                        let rx = leave();
+                        async move { _ = rx.await }
+                    }));
+                let mut state = Render::<MockDom>::build(left);
+                state.mount(&root, None);
+
+                let right: View =
+                    Either::Right(transition("b").on_enter(|| {
+                        ENTERED.fetch_add(1, Ordering::Relaxed);
+                    }));
+                Render::<MockDom>::rebuild(right, &mut state);
+                assert_eq!(root.to_debug_html(), "<div>ba</div>");
+                assert_eq!(ENTERED.load(Ordering::Relaxed), 1);
+
+                done.send(()).unwrap();
+                settle().await;
+                assert_eq!(root.to_debug_html(), "<div>b</div>");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn removed_keyed_items_leave_before_they_are_removed() {
+        _ = Executor::init_tokio();
+        task::LocalSet::new()
+            .run_until(async {
+                let (leave, done) = wait_for_leave();
+                let leave = Arc::new(leave);
+                let list = |items: Vec<usize>| {
+                    let leave = Arc::clone(&leave);
+                    keyed::<_, _, _, _, _, _, _, MockDom>(
+                        items,
+                        |item: &usize| *item,
+                        move |_, item: usize| {
+                            let leave = Arc::clone(&leave);
+                            let view = transition(item.to_string()).on_leave(
+                                move || {
+                                    let rx = leave();
+                                    async move { _ = rx.await }
+                                },
+                            );
+                            (|_: usize| {}, view)
+                        },
+                    )
+                };
+
+                let root = Document::create_element("ul");
+                let mut state = Render::<MockDom>::build(list(vec![1, 2, 3]));
+                state.mount(&root, None);
+                Render::<MockDom>::rebuild(list(vec![1, 3]), &mut state);
+                assert_eq!(root.to_debug_html(), "<ul>123<!></ul>");
+
+                done.send(()).unwrap();
+                settle().await;
+                assert_eq!(root.to_debug_html(), "<ul>13<!></ul>");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn mounting_again_cancels_the_leave() {
+        _ = Executor::init_tokio();
+        task::LocalSet::new()
+            .run_until(async {
+                let root = Document::create_element("div");
+                let (leave, done) = wait_for_leave();
+                let view = transition("a").on_leave(move || {
+                    let rx = leave();
+                    async move { _ = rx.await }
+                });
+                let mut state = Render::<MockDom>::build(view);
+                state.mount(&root, None);
+                state.unmount();
+                state.mount(&root, None);
+
+                done.send(()).unwrap();
+                settle().await;
+                assert_eq!(root.to_debug_html(), "<div>a</div>");
+            })
+            .await;
+    }
+}
@@ -1,5 +1,9 @@
-use super::{Mountable, Render};
-use crate::renderer::Renderer;
+use super::{Mountable, Render, RenderHtml};
+use crate::{
+    hydration::{Cursor, PositionState},
+    renderer::Renderer,
+    ssr::StreamBuilder,
+};
 
 impl<R> Render<R> for ()
 where
@@ -14,6 +18,33 @@ where
     fn rebuild(self, _state: &mut Self::State) {}
 }
 
+impl<R> RenderHtml<R> for ()
+where
+    R: Renderer,
+{
+    type AsyncOutput = ();
+
+    const MIN_LENGTH: usize = 3;
+
+    fn dry_resolve(&mut self) {}
+
+    async fn resolve(self) -> Self::AsyncOutput {}
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        if escape {
+            buf.push_str("<!>");
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        cursor.next_placeholder(position)
+    }
+}
+
 impl<R> Mountable<R> for ()
 where
     R: Renderer,
@@ -39,6 +70,40 @@ impl<R: Renderer, A: Render<R>> Render<R> for (A,) {
     }
 }
 
+impl<R: Renderer, A: RenderHtml<R>> RenderHtml<R> for (A,) {
+    type AsyncOutput = (A::AsyncOutput,);
+
+    const MIN_LENGTH: usize = A::MIN_LENGTH;
+
+    fn dry_resolve(&mut self) {
+        self.0.dry_resolve();
+    }
+
+    async fn resolve(self) -> Self::AsyncOutput {
+        (self.0.resolve().await,)
+    }
+
+    fn html_len(&self) -> usize {
+        self.0.html_len()
+    }
+
+    fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+        self.0.to_html_with_buf(buf, escape);
+    }
+
+    fn to_html_async_with_buf(self, buf: &mut StreamBuilder, escape: bool) {
+        self.0.to_html_async_with_buf(buf, escape);
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        self.0.hydrate::<FROM_SERVER>(cursor, position)
+    }
+}
+
 macro_rules! impl_view_for_tuples {
 	($first:ident, $($ty:ident),* $(,)?) => {
 		impl<$first, $($ty),*, Rndr> Render<Rndr> for ($first, $($ty,)*)
@@ -70,6 +135,70 @@ macro_rules! impl_view_for_tuples {
 
 		}
 
+		impl<$first, $($ty),*, Rndr> RenderHtml<Rndr> for ($first, $($ty,)*)
+		where
+			$first: RenderHtml<Rndr>,
+			$($ty: RenderHtml<Rndr>),*,
+			Rndr: Renderer,
+		{
+			type AsyncOutput = ($first::AsyncOutput, $($ty::AsyncOutput,)*);
+
+			const MIN_LENGTH: usize = $first::MIN_LENGTH $(+ $ty::MIN_LENGTH)*;
+
+			fn dry_resolve(&mut self) {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				$first.dry_resolve();
+				$($ty.dry_resolve());*
+			}
+
+			async fn resolve(self) -> Self::AsyncOutput {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				futures::join!(
+					$first.resolve(),
+					$($ty.resolve()),*
+				)
+			}
+
+			fn html_len(&self) -> usize {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				$first.html_len() $(+ $ty.html_len())*
+			}
+
+			fn to_html_with_buf(self, buf: &mut String, escape: bool) {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				$first.to_html_with_buf(buf, escape);
+				$($ty.to_html_with_buf(buf, escape));*
+			}
+
+			fn to_html_async_with_buf(
+				self,
+				buf: &mut StreamBuilder,
+				escape: bool,
+			) {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				$first.to_html_async_with_buf(buf, escape);
+				$($ty.to_html_async_with_buf(buf, escape));*
+			}
+
+			fn hydrate<const FROM_SERVER: bool>(
+				self,
+				cursor: &Cursor<Rndr>,
+				position: &PositionState,
+			) -> Self::State {
+				#[allow(non_snake_case)]
+				let ($first, $($ty,)*) = self;
+				(
+					$first.hydrate::<FROM_SERVER>(cursor, position),
+					$($ty.hydrate::<FROM_SERVER>(cursor, position)),*
+				)
+			}
+		}
+
         impl<$first, $($ty),*, Rndr> Mountable<Rndr> for ($first, $($ty,)*) where
 			$first: Mountable<Rndr>,
 			$($ty: Mountable<Rndr>),*,